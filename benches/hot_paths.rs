@@ -0,0 +1,57 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+use rustyproof::cnf::{Cnf, SaturationConfig};
+use rustyproof::generate::{pigeonhole, random_clauses, random_ksat, random_stmt};
+use rustyproof::solver::Solver;
+
+fn cnf_conversion(c: &mut Criterion) {
+    let stmt = random_stmt(8, 4, 2);
+    c.bench_function("cnf of random statement (8 symbols, depth 4)", |b| {
+        b.iter(|| black_box(&stmt).try_cnf().unwrap())
+    });
+}
+
+fn clause_insertion(c: &mut Criterion) {
+    // With 2000 clauses of 3 out of 12 symbols, many clauses are duplicates
+    let clauses = random_clauses(12, 2000, 3, 2);
+    c.bench_function("insert 2000 random 3-clauses (12 symbols)", |b| {
+        b.iter(|| Cnf::of_vec(black_box(&clauses)))
+    });
+}
+
+fn saturation(c: &mut Criterion) {
+    let config = SaturationConfig::default();
+
+    let ksat = random_ksat(8, 20, 3, 3);
+    c.bench_function("saturate random 3-SAT (8 symbols, 20 clauses)", |b| {
+        b.iter_batched(|| ksat.clone(), |mut cnf| cnf.saturate(&config), BatchSize::SmallInput)
+    });
+
+    let php = pigeonhole(2);
+    c.bench_function("saturate pigeonhole (2 holes)", |b| {
+        b.iter_batched(|| php.clone(), |mut cnf| cnf.saturate(&config), BatchSize::SmallInput)
+    });
+}
+
+fn solve(c: &mut Criterion) {
+    let ksat = random_ksat(60, 256, 3, 4);
+    c.bench_function("solve random 3-SAT (60 symbols, 256 clauses)", |b| {
+        b.iter(|| {
+            let mut solver = Solver::new();
+            solver.add_cnf(black_box(&ksat));
+            solver.solve()
+        })
+    });
+
+    let php = pigeonhole(6);
+    c.bench_function("solve pigeonhole (6 holes)", |b| {
+        b.iter(|| {
+            let mut solver = Solver::new();
+            solver.add_cnf(black_box(&php));
+            solver.solve()
+        })
+    });
+}
+
+criterion_group!(benches, cnf_conversion, clause_insertion, saturation, solve);
+criterion_main!(benches);
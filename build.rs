@@ -0,0 +1,24 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // The C header is generated along with the C API, see src/ffi.rs. It is written to OUT_DIR
+    // rather than to the source tree, and include/rustyproof.h is the copy that is committed.
+    #[cfg(feature = "ffi")]
+    {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let out = std::env::var("OUT_DIR").unwrap();
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        println!("cargo:rerun-if-changed=include/rustyproof.h");
+
+        let header = format!("{out}/rustyproof.h");
+        cbindgen::generate(&dir)
+            .expect("Cannot generate the C header")
+            .write_to_file(&header);
+
+        let generated = std::fs::read_to_string(&header).unwrap();
+        if std::fs::read_to_string(format!("{dir}/include/rustyproof.h")).ok() != Some(generated) {
+            println!("cargo:warning=include/rustyproof.h is out of date, copy it from {header}");
+        }
+    }
+}
@@ -0,0 +1,87 @@
+#ifndef RUSTYPROOF_H
+#define RUSTYPROOF_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// A knowledge base, created by [rp_kb_new].
+typedef struct RpKnowledgeBase RpKnowledgeBase;
+
+// A statement, created by [rp_parse].
+typedef struct RpStmt RpStmt;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Parses an expression in the input syntax of the REPL, given as a C string in UTF-8.
+// Returns null if the expression is not valid, or if parsing panicked, in which case the error
+// message is stored in `error`, unless it is null.
+//
+// # Safety
+//
+// `input` must be a valid C string, and `error` must be null or point to memory that a
+// pointer can be written to.
+struct RpStmt *rp_parse(const char *input, char **error);
+
+// Returns a statement as rustyproof shows it, as a C string that the application frees with
+// [rp_string_free]. Returns null if `stmt` is null, or if showing it panicked.
+//
+// # Safety
+//
+// `stmt` must be null or a statement returned by [rp_parse] that is not freed yet.
+char *rp_stmt_to_string(const struct RpStmt *stmt);
+
+// Frees a statement. Does nothing if `stmt` is null.
+//
+// # Safety
+//
+// `stmt` must be null or a statement returned by [rp_parse] that is not freed yet.
+void rp_stmt_free(struct RpStmt *stmt);
+
+// Creates a knowledge base without axioms. Returns null if that panicked.
+struct RpKnowledgeBase *rp_kb_new(void);
+
+// Frees a knowledge base. Does nothing if `kb` is null.
+//
+// # Safety
+//
+// `kb` must be null or a knowledge base returned by [rp_kb_new] that is not freed yet.
+void rp_kb_free(struct RpKnowledgeBase *kb);
+
+// Asserts a statement as an axiom of a knowledge base. The statement is copied, so the
+// application still frees it. Returns the number of axioms of the knowledge base after, -1 if
+// `kb` or `stmt` is null, or -2 if the statement cannot be converted to clauses, or if
+// asserting panicked.
+//
+// # Safety
+//
+// `kb` must be null or a knowledge base returned by [rp_kb_new], and `stmt` must be null or a
+// statement returned by [rp_parse], that are not freed yet.
+int rp_kb_assert(struct RpKnowledgeBase *kb, const struct RpStmt *stmt);
+
+// Answers whether a statement follows from the axioms of a knowledge base: returns 1 if it
+// does, 0 if it does not, -1 if `kb` or `stmt` is null, and -2 if answering panicked.
+//
+// # Safety
+//
+// `kb` must be null or a knowledge base returned by [rp_kb_new], and `stmt` must be null or a
+// statement returned by [rp_parse], that are not freed yet.
+int rp_kb_query(const struct RpKnowledgeBase *kb, const struct RpStmt *stmt);
+
+// Frees a string returned by the API. Does nothing if `s` is null.
+//
+// # Safety
+//
+// `s` must be null or a string returned by the API that is not freed yet.
+void rp_string_free(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RUSTYPROOF_H */
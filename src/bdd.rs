@@ -0,0 +1,408 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::stmt::Stmt;
+
+/// A node of a [Bdd], which stands for the statement that the diagram below it represents.
+/// Nodes are only meaningful for the [Bdd] that made them. As the diagram is reduced and
+/// ordered, two nodes of the same [Bdd] are equal exactly when their statements are
+/// equivalent.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Node(usize);
+
+impl Node {
+    /// The node of the contradiction: the statement that is false in every model.
+    pub const FALSE: Node = Node(0);
+
+    /// The node of the tautology: the statement that is true in every model.
+    pub const TRUE: Node = Node(1);
+
+    /// Tests whether this is one of the nodes [Self::FALSE] and [Self::TRUE].
+    pub fn is_terminal(&self) -> bool {
+        return self.0 <= 1;
+    }
+}
+
+/// A node that decides a symbol: it continues with `low` when the symbol is false, and with
+/// `high` when it is true.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+struct Decision {
+    /// The position of the symbol in the variable order, or [TERMINAL] for the terminals.
+    level: usize,
+    low: Node,
+    high: Node
+}
+
+/// The level of the terminal nodes, which is below the level of every symbol.
+const TERMINAL: usize = usize::MAX;
+
+/// A binary operator applied by [Bdd::apply].
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+enum Op {
+    And,
+    Or,
+    Xor
+}
+
+impl Op {
+    fn eval(&self, a: bool, b: bool) -> bool {
+        return match self {
+            Op::And => a && b,
+            Op::Or => a || b,
+            Op::Xor => a != b,
+        };
+    }
+}
+
+/// A reduced ordered binary decision diagram (ROBDD): a set of statements, each represented
+/// by a [Node] of a shared diagram in which the symbols are decided in a fixed order, and in
+/// which no node is redundant or duplicated. Since every statement has exactly one such
+/// diagram, equivalence is tested by comparing nodes, and models are counted in time linear
+/// in the size of the diagram, without searching.
+///
+/// The size of a diagram depends heavily on the variable order, and can grow exponentially
+/// with the number of symbols, so a [Bdd] is an alternative to resolution and the solver for
+/// statements with few symbols, or with structure that a good order captures. Symbols that
+/// are not in the order are added to the end of it when they first occur. Symbols that only
+/// occur bound by a quantifier are in the order too, but are not counted by
+/// [Self::count_models].
+///
+/// For example, `(A & B) | (A & C)` and `A & (B | C)` compile to the same node.
+#[derive(Clone, Debug)]
+pub struct Bdd {
+    /// The symbols, in the order in which they are decided.
+    order: Vec<String>,
+
+    /// The position of every symbol in the order.
+    levels: HashMap<String, usize>,
+
+    /// The levels of the symbols that only occurred bound by a quantifier so far.
+    bound: HashSet<usize>,
+
+    /// All nodes, the terminals first.
+    nodes: Vec<Decision>,
+
+    /// Every node by its decision, so that no node is made twice.
+    unique: HashMap<Decision, Node>,
+
+    /// The results of [Self::apply] computed so far.
+    computed: HashMap<(Op, Node, Node), Node>
+}
+
+impl Bdd {
+    /// Creates a diagram without statements, deciding symbols in the order in which they
+    /// occur.
+    pub fn new() -> Bdd {
+        return Self::with_order(&[]);
+    }
+
+    /// Creates a diagram without statements that decides the symbols of the given statement in
+    /// name order.
+    pub fn for_stmt(stmt: &Stmt) -> Bdd {
+        let symbols = stmt.symbols();
+        return Self::with_order(&symbols.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+
+    /// Creates a diagram without statements that decides the given symbols first, in the
+    /// given order. Other symbols are decided after them, in the order in which they occur.
+    pub fn with_order(order: &[&str]) -> Bdd {
+        let terminal = |node| Decision { level: TERMINAL, low: node, high: node };
+        let mut bdd = Bdd {
+            order: Vec::new(),
+            levels: HashMap::new(),
+            bound: HashSet::new(),
+            nodes: vec![terminal(Node::FALSE), terminal(Node::TRUE)],
+            unique: HashMap::new(),
+            computed: HashMap::new()
+        };
+        for symbol in order {
+            bdd.level(symbol, true);
+        }
+        return bdd;
+    }
+
+    /// Returns the symbols in the order in which they are decided.
+    pub fn order(&self) -> &[String] {
+        return &self.order;
+    }
+
+    /// Returns the position of the given symbol in the order, adding it to the end if it is
+    /// new. An occurrence that is not free, but bound by a quantifier, does not make the
+    /// symbol count as a free symbol.
+    fn level(&mut self, symbol: &str, free: bool) -> usize {
+        if let Some(level) = self.levels.get(symbol) {
+            if free {
+                self.bound.remove(level);
+            }
+            return *level;
+        }
+
+        let level = self.order.len();
+        self.order.push(symbol.to_string());
+        self.levels.insert(symbol.to_string(), level);
+        if !free {
+            self.bound.insert(level);
+        }
+        return level;
+    }
+
+    /// Returns the node that decides the symbol at the given level, making it if it is new.
+    /// A decision with the same node on both sides is redundant, and is left out.
+    fn make(&mut self, level: usize, low: Node, high: Node) -> Node {
+        if low == high {
+            return low;
+        }
+
+        let decision = Decision { level, low, high };
+        if let Some(node) = self.unique.get(&decision) {
+            return *node;
+        }
+
+        let node = Node(self.nodes.len());
+        self.nodes.push(decision);
+        self.unique.insert(decision, node);
+        return node;
+    }
+
+    fn decision(&self, node: Node) -> Decision {
+        return self.nodes[node.0];
+    }
+
+    /// Returns the node of the given symbol.
+    pub fn symbol(&mut self, symbol: &str) -> Node {
+        let level = self.level(symbol, true);
+        return self.make(level, Node::FALSE, Node::TRUE);
+    }
+
+    /// Compiles a statement into this diagram, and returns its node.
+    pub fn compile(&mut self, stmt: &Stmt) -> Node {
+        return self.compile_in(stmt, &mut Vec::new());
+    }
+
+    /// Compiles a statement, where `bound` holds the symbols bound by the quantifiers around
+    /// it, innermost last.
+    fn compile_in(&mut self, stmt: &Stmt, bound: &mut Vec<String>) -> Node {
+        return match stmt {
+            Stmt::Cont => Node::FALSE,
+            Stmt::Taut => Node::TRUE,
+            Stmt::Symbol(s) => {
+                let level = self.level(s, !bound.contains(s));
+                self.make(level, Node::FALSE, Node::TRUE)
+            },
+            Stmt::Necessarily(_) | Stmt::Possibly(_) => self.symbol(&stmt.to_string()),
+            Stmt::Not(o) => {
+                let o = self.compile_in(o, bound);
+                self.not(o)
+            },
+            Stmt::And(os) => os.iter().fold(Node::TRUE, |acc, o| {
+                let o = self.compile_in(o, bound);
+                self.and(acc, o)
+            }),
+            Stmt::Or(os) => os.iter().fold(Node::FALSE, |acc, o| {
+                let o = self.compile_in(o, bound);
+                self.or(acc, o)
+            }),
+            Stmt::Implies(a, b) => {
+                let a = self.compile_in(a, bound);
+                let b = self.compile_in(b, bound);
+                let not_a = self.not(a);
+                self.or(not_a, b)
+            },
+            Stmt::Equiv(a, b) => {
+                let a = self.compile_in(a, bound);
+                let b = self.compile_in(b, bound);
+                let xor = self.xor(a, b);
+                self.not(xor)
+            },
+            Stmt::Xor(a, b) => {
+                let a = self.compile_in(a, bound);
+                let b = self.compile_in(b, bound);
+                self.xor(a, b)
+            },
+            Stmt::Ite(c, t, e) => {
+                let c = self.compile_in(c, bound);
+                let t = self.compile_in(t, bound);
+                let e = self.compile_in(e, bound);
+                self.ite(c, t, e)
+            },
+            Stmt::Forall(s, o) | Stmt::Exists(s, o) => {
+                bound.push(s.clone());
+                let o = self.compile_in(o, bound);
+                bound.pop();
+
+                let t = self.restrict(o, s, true);
+                let f = self.restrict(o, s, false);
+                if matches!(stmt, Stmt::Forall(..)) { self.and(t, f) } else { self.or(t, f) }
+            },
+        };
+    }
+
+    /// Applies a binary operator to two nodes, by deciding the first symbol of either, and
+    /// applying the operator to the nodes below it.
+    fn apply(&mut self, op: Op, a: Node, b: Node) -> Node {
+        if a.is_terminal() && b.is_terminal() {
+            return if op.eval(a == Node::TRUE, b == Node::TRUE) { Node::TRUE } else { Node::FALSE };
+        }
+        if let Some(node) = self.computed.get(&(op, a, b)) {
+            return *node;
+        }
+
+        let (da, db) = (self.decision(a), self.decision(b));
+        let level = da.level.min(db.level);
+        let (a_low, a_high) = if da.level == level { (da.low, da.high) } else { (a, a) };
+        let (b_low, b_high) = if db.level == level { (db.low, db.high) } else { (b, b) };
+
+        let low = self.apply(op, a_low, b_low);
+        let high = self.apply(op, a_high, b_high);
+        let node = self.make(level, low, high);
+
+        self.computed.insert((op, a, b), node);
+        return node;
+    }
+
+    /// Returns the node of the conjunction of two nodes: true where both hold.
+    pub fn and(&mut self, a: Node, b: Node) -> Node {
+        return self.apply(Op::And, a, b);
+    }
+
+    /// Returns the node of the disjunction of two nodes: true where either holds.
+    pub fn or(&mut self, a: Node, b: Node) -> Node {
+        return self.apply(Op::Or, a, b);
+    }
+
+    /// Returns the node of the exclusive disjunction of two nodes: true where exactly one of
+    /// them holds.
+    pub fn xor(&mut self, a: Node, b: Node) -> Node {
+        return self.apply(Op::Xor, a, b);
+    }
+
+    /// Returns the node of the negation of a node: true where it does not hold.
+    pub fn not(&mut self, a: Node) -> Node {
+        return self.apply(Op::Xor, a, Node::TRUE);
+    }
+
+    /// Returns the node of the if-then-else of three nodes: `t` where `c` holds, and `e`
+    /// elsewhere.
+    pub fn ite(&mut self, c: Node, t: Node, e: Node) -> Node {
+        let then = self.and(c, t);
+        let not_c = self.not(c);
+        let otherwise = self.and(not_c, e);
+        return self.or(then, otherwise);
+    }
+
+    /// Restricts a node to the given value of a symbol: returns the node of its statement with
+    /// the symbol replaced by [Stmt::Taut] or [Stmt::Cont], like [Stmt::fix]. The result does not depend on the symbol.
+    ///
+    /// For example, restricting `A & B` to `A` being true gives `B`.
+    pub fn restrict(&mut self, node: Node, symbol: &str, value: bool) -> Node {
+        return match self.levels.get(symbol) {
+            Some(level) => self.restrict_at(node, *level, value, &mut HashMap::new()),
+            None => node,
+        };
+    }
+
+    fn restrict_at(&mut self, node: Node, level: usize, value: bool, done: &mut HashMap<Node, Node>) -> Node {
+        let d = self.decision(node);
+        if d.level == TERMINAL || d.level > level {
+            return node;
+        }
+        if d.level == level {
+            return if value { d.high } else { d.low };
+        }
+        if let Some(out) = done.get(&node) {
+            return *out;
+        }
+
+        let low = self.restrict_at(d.low, level, value, done);
+        let high = self.restrict_at(d.high, level, value, done);
+        let out = self.make(d.level, low, high);
+        done.insert(node, out);
+        return out;
+    }
+
+    /// Tests whether two nodes have equivalent statements, which is the case exactly when
+    /// they are the same node.
+    pub fn equivalent(&self, a: Node, b: Node) -> bool {
+        return a == b;
+    }
+
+    /// Tests whether the statement of a node is true in some model.
+    pub fn is_satisfiable(&self, node: Node) -> bool {
+        return node != Node::FALSE;
+    }
+
+    /// Tests whether the statement of a node is true in every model.
+    pub fn is_tautology(&self, node: Node) -> bool {
+        return node == Node::TRUE;
+    }
+
+    /// Counts the models of the statement of a node, among the assignments to all symbols of
+    /// the order, including those that the statement does not mention, but leaving out
+    /// symbols that only occurred bound by a quantifier. Returns `None` if the count does not
+    /// fit in a `u128`, which can happen from 128 symbols on.
+    ///
+    /// For example, `A | B` has 3 models when the order is `A, B`, and 6 when it is `A, B, C`.
+    /// `exists P. P` has 1 model, since `P` is not a free symbol.
+    pub fn count_models(&self, node: Node) -> Option<u128> {
+        let count = self.count_below(node, &mut HashMap::new())?;
+        return count.checked_mul(pow2(self.free_between(0, self.level_of(node)))?);
+    }
+
+    /// Returns the level of a node, where the terminals are below all symbols of the order.
+    fn level_of(&self, node: Node) -> usize {
+        return self.decision(node).level.min(self.order.len());
+    }
+
+    /// Returns the number of free symbols in the order from the first level up to, but not
+    /// including, the second.
+    fn free_between(&self, from: usize, to: usize) -> usize {
+        return (from..to).filter(|level| !self.bound.contains(level)).count();
+    }
+
+    /// Counts the assignments to the free symbols from the level of the node onward that
+    /// satisfy its statement, or returns `None` if the count does not fit in a `u128`.
+    fn count_below(&self, node: Node, done: &mut HashMap<Node, u128>) -> Option<u128> {
+        if node.is_terminal() {
+            return Some(if node == Node::TRUE { 1 } else { 0 });
+        }
+        if let Some(count) = done.get(&node) {
+            return Some(*count);
+        }
+
+        // Symbols skipped between a node and the node below it can have either value
+        let d = self.decision(node);
+        let low = self.count_below(d.low, done)?.checked_mul(pow2(self.free_between(d.level + 1, self.level_of(d.low)))?)?;
+        let high = self.count_below(d.high, done)?.checked_mul(pow2(self.free_between(d.level + 1, self.level_of(d.high)))?)?;
+        let count = low.checked_add(high)?;
+
+        done.insert(node, count);
+        return Some(count);
+    }
+
+    /// Returns the number of nodes of the diagram of a node, including the terminals it
+    /// reaches.
+    pub fn size(&self, node: Node) -> usize {
+        let mut seen = vec![node];
+        let mut i = 0;
+        while i < seen.len() {
+            let d = self.decision(seen[i]);
+            for next in [d.low, d.high] {
+                if !seen[i].is_terminal() && !seen.contains(&next) {
+                    seen.push(next);
+                }
+            }
+            i += 1;
+        }
+        return seen.len();
+    }
+}
+
+impl Default for Bdd {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+/// Returns 2 to the given power, or `None` if it does not fit in a `u128`.
+fn pow2(n: usize) -> Option<u128> {
+    return 1u128.checked_shl(u32::try_from(n).ok()?);
+}
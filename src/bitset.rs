@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{BuildHasherDefault, Hash, Hasher};
+
+use crate::cnf::Disj;
+use crate::literal::Literal;
+use crate::symbol::{Symbol, SymbolTable};
+
+/// The number of symbols in a word of a [BitClause].
+const WORD: usize = u64::BITS as usize;
+
+/// A clause as a pair of bitsets: one with a bit for every symbol that occurs positively, and
+/// one with a bit for every symbol that occurs negatively, where symbols are numbered by a
+/// [BitEncoding]. Testing subsumption, resolving and comparing clauses this way takes a few
+/// operations on whole words, rather than walking sets of literals and comparing names.
+///
+/// Words missing at the end count as zero, so clauses encoded before and after symbols were
+/// added to the encoding can still be compared.
+#[derive(Clone, Debug, Default)]
+pub struct BitClause {
+    pos: Vec<u64>,
+    neg: Vec<u64>
+}
+
+impl BitClause {
+    /// Creates a clause without literals, i.e. the empty clause.
+    pub fn new() -> BitClause {
+        return BitClause { pos: Vec::new(), neg: Vec::new() };
+    }
+
+    /// Adds the literal of the symbol with the given number.
+    pub fn insert(&mut self, symbol: usize, polarity: bool) {
+        let words = if polarity { &mut self.pos } else { &mut self.neg };
+        if words.len() <= symbol / WORD {
+            words.resize(symbol / WORD + 1, 0);
+        }
+        words[symbol / WORD] |= 1 << (symbol % WORD);
+    }
+
+    /// Tests whether the literal of the symbol with the given number occurs in this clause.
+    pub fn contains(&self, symbol: usize, polarity: bool) -> bool {
+        let words = if polarity { &self.pos } else { &self.neg };
+        return word(words, symbol / WORD) & (1 << (symbol % WORD)) != 0;
+    }
+
+    /// Returns the number of literals of this clause.
+    pub fn len(&self) -> usize {
+        return self.pos.iter().chain(self.neg.iter()).map(|w| w.count_ones() as usize).sum();
+    }
+
+    /// Tests whether this clause has no literals, i.e. whether it is a contradiction.
+    pub fn is_empty(&self) -> bool {
+        return self.pos.iter().chain(self.neg.iter()).all(|w| *w == 0);
+    }
+
+    /// Tests whether this clause subsumes the other: whether all its literals occur in the
+    /// other, see [Disj::subsumes].
+    pub fn subsumes(&self, other: &BitClause) -> bool {
+        return is_subset(&self.pos, &other.pos) && is_subset(&self.neg, &other.neg);
+    }
+
+    /// Returns the literals of this clause, as the number of their symbol and their polarity,
+    /// ordered by number with the negative literal first.
+    pub fn literals(&self) -> Vec<(usize, bool)> {
+        let mut out = Vec::with_capacity(self.len());
+        for i in 0..self.pos.len().max(self.neg.len()) {
+            let (pos, neg) = (word(&self.pos, i), word(&self.neg, i));
+
+            let mut rest = pos | neg;
+            while rest != 0 {
+                let bit = rest.trailing_zeros() as usize;
+                rest &= rest - 1;
+
+                if neg & (1 << bit) != 0 {
+                    out.push((i * WORD + bit, false));
+                }
+                if pos & (1 << bit) != 0 {
+                    out.push((i * WORD + bit, true));
+                }
+            }
+        }
+        return out;
+    }
+
+    /// Returns the resolvent of this clause and the other, see [Disj::resolve_vec]. Two
+    /// clauses only have a resolvent if exactly one symbol occurs positively in one and
+    /// negatively in the other: when there are more such symbols, every resolvent still has
+    /// both literals of another one of them, and is a tautology.
+    pub fn resolve(&self, other: &BitClause) -> Option<BitClause> {
+        let words = self.pos.len().max(self.neg.len()).max(other.pos.len()).max(other.neg.len());
+
+        let mut clashes = 0;
+        let mut out = BitClause { pos: Vec::with_capacity(words), neg: Vec::with_capacity(words) };
+        for i in 0..words {
+            let (ap, an) = (word(&self.pos, i), word(&self.neg, i));
+            let (bp, bn) = (word(&other.pos, i), word(&other.neg, i));
+
+            let clash = (ap & bn) | (an & bp);
+            clashes += clash.count_ones();
+            if clashes > 1 {
+                return None;
+            }
+
+            out.pos.push((ap | bp) & !clash);
+            out.neg.push((an | bn) & !clash);
+        }
+
+        return if clashes == 1 { Some(out) } else { None };
+    }
+}
+
+/// Returns the word with the given index, or zero if there is no such word.
+fn word(words: &[u64], i: usize) -> u64 {
+    return words.get(i).copied().unwrap_or(0);
+}
+
+/// Tests whether all bits set in `a` are set in `b`.
+fn is_subset(a: &[u64], b: &[u64]) -> bool {
+    return a.iter().enumerate().all(|(i, w)| w & !word(b, i) == 0);
+}
+
+impl PartialEq for BitClause {
+    fn eq(&self, other: &Self) -> bool {
+        return self.subsumes(other) && other.subsumes(self);
+    }
+}
+
+impl Eq for BitClause {}
+
+// Missing words count as zero, so zero words at the end must not change the hash
+impl Hash for BitClause {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for words in [&self.pos, &self.neg] {
+            let len = words.iter().rposition(|w| *w != 0).map_or(0, |i| i + 1);
+            words[..len].hash(state);
+        }
+    }
+}
+
+/// Numbers the symbols of clauses, to convert them to [BitClause]s and back. Every symbol gets
+/// the next number when it is first added.
+#[derive(Clone, Debug, Default)]
+pub struct BitEncoding {
+    symbols: Vec<Symbol>,
+    numbers: HashMap<Symbol, usize, BuildHasherDefault<DefaultHasher>>
+}
+
+impl BitEncoding {
+    pub fn new() -> BitEncoding {
+        return BitEncoding { symbols: Vec::new(), numbers: HashMap::default() };
+    }
+
+    /// Creates an encoding of the given symbols, numbered in the order in which they are given.
+    pub fn of_symbols(symbols: impl IntoIterator<Item = impl AsRef<str>>) -> BitEncoding {
+        let mut encoding = Self::new();
+        for symbol in symbols {
+            encoding.add(symbol.as_ref());
+        }
+        return encoding;
+    }
+
+    /// Adds a symbol, unless it is already known, and returns its number.
+    pub fn add(&mut self, symbol: &str) -> usize {
+        return self.add_symbol(Symbol::intern(symbol));
+    }
+
+    fn add_symbol(&mut self, symbol: Symbol) -> usize {
+        if let Some(n) = self.numbers.get(&symbol) {
+            return *n;
+        }
+
+        self.symbols.push(symbol);
+        self.numbers.insert(symbol, self.symbols.len() - 1);
+        return self.symbols.len() - 1;
+    }
+
+    /// Returns the number of the given symbol, or `None` if it is not known.
+    pub fn number(&self, symbol: &str) -> Option<usize> {
+        let symbol = SymbolTable::global().read().unwrap().get(symbol)?;
+        return self.numbers.get(&symbol).copied();
+    }
+
+    /// Returns the symbol with the given number, or `None` if there is no such symbol.
+    pub fn symbol(&self, number: usize) -> Option<&str> {
+        return self.symbols.get(number).map(Symbol::name);
+    }
+
+    /// Returns the number of symbols.
+    pub fn len(&self) -> usize {
+        return self.symbols.len();
+    }
+
+    /// Tests whether no symbols are known.
+    pub fn is_empty(&self) -> bool {
+        return self.symbols.is_empty();
+    }
+
+    /// Converts a clause to a [BitClause], adding the symbols that are not yet known.
+    pub fn encode(&mut self, disj: &Disj) -> BitClause {
+        let mut out = BitClause::new();
+        for lit in disj.literals() {
+            out.insert(self.add_symbol(lit.atom), lit.polarity);
+        }
+        return out;
+    }
+
+    /// Converts a [BitClause] back to a clause. Panics if it has a symbol that is not known.
+    pub fn decode(&self, bits: &BitClause) -> Disj {
+        let lits = bits.literals().into_iter().map(|(s, polarity)| Literal { atom: self.symbols[s], polarity });
+        return Disj::of_literals(lits).unwrap();
+    }
+}
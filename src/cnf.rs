@@ -1,15 +1,24 @@
-use std::collections::HashSet;
-use std::fmt::Display;
-use std::hash::Hash;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::{Debug, Display};
+use std::hash::BuildHasherDefault;
+use std::ops::{AddAssign, Range};
+use std::time::{Duration, Instant};
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-/// A disjunction of symbols, either inverted or not. Symbols are represented as [char]s.
-/// Internally, the disjunction is represented as two disjoint sets, one with non-inverted
-/// (positive) symbols, and one with inverted (negative) symbols.
-#[derive(PartialEq, Eq, Clone)]
+use crate::bitset::{BitClause, BitEncoding};
+use crate::literal::Literal;
+use crate::solver::Queries;
+
+
+/// A disjunction of literals: symbols, either inverted or not. Internally, the disjunction is
+/// represented as a set of [Literal]s, which never contains both literals of a symbol.
+#[derive(PartialEq, Eq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Disj {
-    pos: HashSet<char>,
-    neg: HashSet<char>
+    lits: BTreeSet<Literal>
 }
 
 #[allow(unused)]
@@ -19,68 +28,86 @@ impl Disj {
     /// For example, given `pos = {P, Q}` and `neg = {R}`, it creates a disjunction stating
     /// `P | Q | !R`.
     /// 
-    /// The sets should typically be disjoint, since otherwise the statement would
-    /// be a tautology (i.e. `P | !P` is always true, other disjuncts will no longer
-    /// matter then). However, a tautology is useless and can't be represented in a 
-    /// unified way, so instead the sets are made disjunct by removing the terms that
-    /// exist in both sets.
-    /// 
-    /// For example, given `pos = {P, Q}` and `neg = {Q, R}`, logically you'd make the
-    /// statement `P | Q | !Q | !R`, but that is a logical tautology. Instead, it creates
-    /// the statement `P | !R`, which is the result of resolving `P | Q` and `!Q | !R`
-    /// against eachother. This side effect of non-disjoint sets allows for resolution between two
-    /// statements to be as simple as passing the unions of respectively the positive and
-    /// negative symbols of both statements.
+    /// When the sets share a symbol, the disjunction is a tautology: `P | !P | Q` holds
+    /// whatever the values of its symbols are. A tautology says nothing, so it is no use as a
+    /// clause, and it is not represented as one: `None` is returned instead, so that it can be
+    /// left out of a [Cnf].
     /// 
     /// Note that when both sets are empty, the resulting disjunction is a contradiction
     /// by vacuous truth: _"Do any of the disjuncts satisfy? No, because there are no disjuncts."_
-    pub fn new(mut pos: HashSet<char>, mut neg: HashSet<char>) -> Disj {
-        // Remove terms that are both in pos and neg: if we have P | !P then we essentially have stated a tautology
-        let mut isc = Vec::new();
-
-        for i in pos.intersection(&neg) {
-            isc.push(*i);
+    pub fn new(pos: HashSet<String>, neg: HashSet<String>) -> Option<Disj> {
+        if !pos.is_disjoint(&neg) {
+            return None;
         }
 
-        for i in isc.iter() {
-            pos.remove(i);
-            neg.remove(i);
+        let pos = pos.into_iter().map(|atom| Literal::pos(&atom));
+        let neg = neg.into_iter().map(|atom| Literal::neg(&atom));
+        return Some(Disj { lits: pos.chain(neg).collect() });
+    }
+
+    /// Creates a new [Disj] of the given literals, or returns `None` if it is a tautology,
+    /// i.e. if it has both literals of a symbol. See [Self::new].
+    pub fn of_literals(lits: impl IntoIterator<Item = Literal>) -> Option<Disj> {
+        let lits: BTreeSet<Literal> = lits.into_iter().collect();
+        if lits.iter().any(|l| lits.contains(&!l)) {
+            return None;
         }
 
-        return Disj { pos, neg };
+        return Some(Disj { lits });
     }
 
-    /// Creates a new [Disj], given the sets with positive and negative symbols as slices.
-    pub fn of_slices(pos: &[char], neg: &[char]) -> Disj {
+    /// Creates a new [Disj], given the sets with positive and negative symbols as slices, or
+    /// returns `None` if it is a tautology, see [Self::new].
+    pub fn of_slices(pos: &[&str], neg: &[&str]) -> Option<Disj> {
         return Self::new(
-            pos.iter().copied().collect(),
-            neg.iter().copied().collect()
+            pos.iter().map(|s| s.to_string()).collect(),
+            neg.iter().map(|s| s.to_string()).collect()
         );
     }
 
-    /// Returns the set of non-inverted (positive) disjuncts.
-    pub fn pos(&self) -> &HashSet<char> {
-        return &self.pos;
+    /// Returns the literals of this disjunction, in order.
+    pub fn literals(&self) -> &BTreeSet<Literal> {
+        return &self.lits;
     }
 
-    /// Returns the set of inverted (negative) disjuncts.
-    pub fn neg(&self) -> &HashSet<char> {
-        return &self.neg;
+    /// Returns the number of literals of this disjunction.
+    pub fn len(&self) -> usize {
+        return self.lits.len();
+    }
+
+    /// Tests whether this disjunction has no literals, i.e. whether it is a contradiction.
+    pub fn is_empty(&self) -> bool {
+        return self.lits.is_empty();
+    }
+
+    /// Tests whether the given literal is part of this disjunction.
+    pub fn contains(&self, lit: &Literal) -> bool {
+        return self.lits.contains(lit);
+    }
+
+    /// Returns the non-inverted (positive) disjuncts, in order of name.
+    pub fn pos(&self) -> impl Iterator<Item = &str> {
+        return self.lits.iter().filter(|l| l.polarity).map(|l| l.atom.name());
+    }
+
+    /// Returns the inverted (negative) disjuncts, in order of name.
+    pub fn neg(&self) -> impl Iterator<Item = &str> {
+        return self.lits.iter().filter(|l| !l.polarity).map(|l| l.atom.name());
     }
 
     /// Tests whether the given term is part of this disjunction in non-inverted form.
-    pub fn is_pos(&self, term: char) -> bool {
-        return self.pos.contains(&term);
+    pub fn is_pos(&self, term: &str) -> bool {
+        return self.contains(&Literal::pos(term));
     }
 
     /// Tests whether the given term is part of this disjunction in inverted form.
-    pub fn is_neg(&self, term: char) -> bool {
-        return self.neg.contains(&term);
+    pub fn is_neg(&self, term: &str) -> bool {
+        return self.contains(&Literal::neg(term));
     }
 
     /// Tests whether the given term is not part of this disjunction.
-    pub fn is_unknown(&self, term: char) -> bool {
-        return !self.is_pos(term) && !self.is_neg(term);
+    pub fn is_unknown(&self, term: &str) -> bool {
+        return !self.lits.iter().any(|l| l.atom == term);
     }
 
     /// Tests whether this disjunction presents a contradiction. A contradictory disjunction
@@ -88,7 +115,13 @@ impl Disj {
     /// be satisfied, so by vacuous truth it is a contradiction. Resolution will generate a
     /// contradiction if two statements are contradictory.
     pub fn is_contradiction(&self) -> bool {
-        return self.pos.is_empty() && self.neg.is_empty();
+        return self.lits.is_empty();
+    }
+
+    /// Tests whether this disjunction is a Horn clause: a clause with at most one
+    /// non-inverted symbol, like `P | !Q | !R`, which reads as `(Q & R) -> P`.
+    pub fn is_horn(&self) -> bool {
+        return self.pos().count() <= 1;
     }
 
     /// Given a specific term to resolve over, resolves this statement against the other.
@@ -102,100 +135,109 @@ impl Disj {
     /// - This or the other statement do not state the given term
     /// - Both this and the other statement state the given term positively
     /// - Both this and the other statement state the given term negatively
-    pub fn resolve(&self, other: &Self, c: char) -> Option<Disj> {
-        if self.is_unknown(c) || other.is_unknown(c) {
-            return None;
-        }
-        if self.is_pos(c) && other.is_pos(c) {
-            return None;
-        }
-        if self.is_neg(c) && other.is_neg(c) {
-            return None;
-        }
-
-        let mut pos = HashSet::new();
-        let mut neg = HashSet::new();
-
-        pos.extend(self.pos());
-        pos.extend(other.pos());
-        neg.extend(self.neg());
-        neg.extend(other.neg());
-        pos.remove(&c);
-        neg.remove(&c);
-
-        if (!pos.is_disjoint(&neg)) {
+    /// - The resolvent is a tautology, because the statements also clash on another term
+    pub fn resolve(&self, other: &Self, c: &str) -> Option<Disj> {
+        let lit = self.lits.iter().find(|l| l.atom == c)?;
+        if !other.contains(&!lit) {
             return None;
         }
 
-        return Some(Self::new(pos, neg));
+        return Self::of_literals(self.lits.iter().chain(other.lits.iter()).filter(|l| l.atom != c).cloned());
     }
 
+    /// Returns the disjunction of the terms of this disjunction and the other, or `None` if
+    /// that is a tautology.
     pub fn combine(&self, other: &Self) -> Option<Disj> {
-        let mut pos = HashSet::new();
-        let mut neg = HashSet::new();
-
-        pos.extend(self.pos());
-        pos.extend(other.pos());
-        neg.extend(self.neg());
-        neg.extend(other.neg());
-
-        if (!pos.is_disjoint(&neg)) {
-            return None;
-        }
-
-        return Some(Self::new(pos, neg));
+        return Self::of_literals(self.lits.iter().chain(other.lits.iter()).cloned());
     }
 
     /// Returns all the possible resolutions between this statement and the other.
     pub fn resolve_vec(&self, other: &Self) -> Vec<Disj> {
-        let mut out = Vec::new();
+        return self.lits.iter()
+            .filter_map(|l| self.resolve(other, &l.atom))
+            .collect();
+    }
 
-        let mut syms = HashSet::<char>::new();
+    /// Tests whether this clause subsumes the other, i.e. whether all literals of this clause
+    /// occur in the other. Then the other clause follows from this one, and is redundant next
+    /// to it. Every clause subsumes itself.
+    ///
+    /// For example, `P | !Q` subsumes `P | !Q | R`.
+    pub fn subsumes(&self, other: &Self) -> bool {
+        return self.lits.is_subset(&other.lits);
+    }
 
-        syms.extend(self.pos.iter());
-        syms.extend(self.neg.iter());
-        syms.extend(other.pos.iter());
-        syms.extend(other.neg.iter());
+    /// Tests whether this clause is subsumed by the other, i.e. whether the other subsumes this
+    /// one, see [Self::subsumes].
+    pub fn subsumed_by(&self, other: &Self) -> bool {
+        return other.subsumes(self);
+    }
 
-        for c in syms.iter() {
-            if let Some(s) = self.resolve(other, *c) {
-                out.push(s);
-            }
-        }
+    /// Compares this clause to the other in the order in which clauses are shown: shorter
+    /// clauses first, so that the contradiction and units come before wider clauses, and
+    /// clauses of the same width lexicographically by their literals (see [Literal]).
+    ///
+    /// Unlike the partial order of subsumption, this is a total order. It is used wherever a
+    /// [Cnf] is printed or exported, see [Cnf::sorted].
+    pub fn display_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        return (self.len(), &self.lits).cmp(&(other.len(), &other.lits));
+    }
 
-        return out;
+    /// Writes this clause for LaTeX math mode, like `P \lor \lnot Q`, or `\bot` if it is
+    /// empty.
+    pub fn to_latex(&self) -> String {
+        if self.is_contradiction() {
+            return String::from("\\bot");
+        }
+        return self.lits.iter().map(Literal::to_latex).collect::<Vec<_>>().join(" \\lor ");
     }
 
-    pub fn implies(l: char, r: char) -> Disj {
+    /// Returns the clause `!l | r`, or `None` if `l` and `r` are the same symbol, which makes
+    /// it a tautology.
+    pub fn implies(l: &str, r: &str) -> Option<Disj> {
         return Self::of_slices(&[r], &[l]);
     }
 
-    pub fn axiom(t: char) -> Disj {
-        return Self::of_slices(&[t], &[]);
+    pub fn axiom(t: &str) -> Disj {
+        return Self { lits: BTreeSet::from([Literal::pos(t)]) };
     }
 
-    pub fn axiom_not(t: char) -> Disj {
-        return Self::of_slices(&[], &[t]);
+    pub fn axiom_not(t: &str) -> Disj {
+        return Self { lits: BTreeSet::from([Literal::neg(t)]) };
     }
 
     pub fn contradiction() -> Disj {
-        return Self { pos: HashSet::new(), neg: HashSet::new() }
+        return Self { lits: BTreeSet::new() }
     }
 }
 
-// Hash is somehow not implemented on HashSet itself so we have to manually implement Hash
-impl Hash for Disj {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        for c in self.pos.iter() {
-            if !self.is_pos(*c) {
-                c.hash(state);
-            }
-        }
-        for c in self.neg.iter() {
-            if !self.is_neg(*c) {
-                c.hash(state);
-            }
-        }
+impl<'a> IntoIterator for &'a Disj {
+    type Item = &'a Literal;
+    type IntoIter = std::collections::btree_set::Iter<'a, Literal>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.lits.iter();
+    }
+}
+
+// Unlike Display, Debug shows the set of literals of the clause.
+impl Debug for Disj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Disj ")?;
+        return f.debug_set().entries(self.lits.iter().map(|l| format!("{l}"))).finish();
+    }
+}
+
+// Clauses are partially ordered by subsumption: a clause is less than another when it strictly
+// subsumes it. Clauses of which neither subsumes the other are incomparable.
+impl PartialOrd for Disj {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        return match (self.subsumes(other), other.subsumes(self)) {
+            (true, true) => Some(std::cmp::Ordering::Equal),
+            (true, false) => Some(std::cmp::Ordering::Less),
+            (false, true) => Some(std::cmp::Ordering::Greater),
+            (false, false) => None,
+        };
     }
 }
 
@@ -205,46 +247,172 @@ impl Display for Disj {
             return write!(f, "~");
         }
 
-        let mut sep = false;
-
-        for p in self.pos.iter() {
-            if sep {
+        for (i, l) in self.lits.iter().enumerate() {
+            if i > 0 {
                 write!(f, " | ")?;
-            } else {
-                sep = true;
             }
-
-            write!(f, "{p}")?;
+            write!(f, "{l}")?;
         }
 
-        for n in self.neg.iter() {
-            if sep {
-                write!(f, " | ")?;
-            } else {
-                sep = true;
-            }
+        Ok(())
+    }
+}
 
-            write!(f, "!{n}")?;
-        }
+/// Which pairs of clauses [Cnf::saturate] resolves. Restricted strategies resolve fewer pairs,
+/// which is faster, but they are not complete: the clauses may be unsatisfiable even though the
+/// empty clause does not follow, and they are saturated only in the sense that the strategy
+/// finds no more resolvents. On Horn clauses, both restricted strategies derive the empty
+/// clause whenever the clauses are unsatisfiable.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+pub enum ResolutionStrategy {
+    /// Binary resolution: every pair of clauses is resolved. This derives the empty clause
+    /// whenever the clauses are unsatisfiable.
+    #[default]
+    Full,
 
-        Ok(())
+    /// Unit resolution: only pairs of which at least one clause is a unit clause, like `P`,
+    /// are resolved. Resolvents are never wider than the clauses they follow from.
+    Unit,
+
+    /// Input resolution: only pairs of which at least one clause is an input clause are
+    /// resolved. The input clauses are the clauses a saturation starts with.
+    Input
+}
+
+/// The configuration of [Cnf::saturate]: which [ResolutionStrategy] to use, and limits on how
+/// long to saturate. Every limit is optional, and the default configuration has no limits at
+/// all, in which case saturation can take very long: the number of resolvents can grow
+/// exponentially with the number of symbols.
+#[derive(Clone, Default, Debug)]
+pub struct SaturationConfig {
+    /// The pairs of clauses to resolve.
+    pub strategy: ResolutionStrategy,
+
+    /// The maximum number of rounds, in each of which the clauses added in the previous round
+    /// are resolved with all clauses, or `None` to resolve until the clauses are saturated or
+    /// refuted.
+    pub max_rounds: Option<usize>,
+
+    /// The maximum number of inferences, i.e. resolvents derived, whether they are new or not.
+    pub max_inferences: Option<usize>,
+
+    /// The maximum number of clauses. Saturation stops as soon as the clauses outgrow it.
+    pub max_clauses: Option<usize>,
+
+    /// The maximum number of literals of a resolvent. Longer resolvents are discarded, which
+    /// saves much memory, as long clauses are many but rarely lead to the empty clause. The
+    /// clauses are then not necessarily closed under resolution, and the empty clause may be
+    /// missed, see [SolveStats::is_incomplete].
+    pub max_length: Option<usize>,
+
+    /// The maximum time to spend on saturation.
+    pub max_time: Option<Duration>
+}
+
+impl SaturationConfig {
+    /// Tests whether a limit other than the number of rounds is exceeded, given the number
+    /// of inferences made, the number of clauses, and when saturation started.
+    fn is_exceeded(&self, inferences: usize, clauses: usize, start: Instant) -> bool {
+        return self.max_inferences.is_some_and(|max| inferences > max)
+            || self.max_clauses.is_some_and(|max| clauses > max)
+            || self.max_time.is_some_and(|max| start.elapsed() > max);
+    }
+}
+
+/// The outcome of [Cnf::saturate].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SaturationOutcome {
+    /// No new resolvents follow: the clauses are closed under resolution.
+    Saturated,
+
+    /// The empty clause was derived: the clauses are unsatisfiable.
+    Refuted,
+
+    /// A limit of the [SaturationConfig] was hit before the clauses were saturated or
+    /// refuted.
+    LimitReached
+}
+
+/// Counts of the work done by [Cnf::saturate] or a [Saturation], to measure how well it
+/// performs.
+#[derive(PartialEq, Eq, Clone, Default, Debug)]
+pub struct SolveStats {
+    /// The number of pairs of clashing clauses that were resolved, including those whose
+    /// resolvent is a tautology and is left out.
+    pub inferences: usize,
+
+    /// The number of resolvents computed, whether they are new or not. This is what the
+    /// maximum number of inferences of a [SaturationConfig] limits.
+    pub generated: usize,
+
+    /// The number of clauses left out because another clause subsumes them: resolvents that
+    /// are not added, and clauses that are removed for a new clause.
+    pub subsumed: usize,
+
+    /// The number of resolvents discarded because they are longer than the maximum length of
+    /// the [SaturationConfig].
+    pub discarded: usize,
+
+    /// The wall time spent saturating.
+    pub time: Duration
+}
+
+impl SolveStats {
+    /// Tests whether resolvents were discarded for their length, in which case the clauses are
+    /// possibly incomplete: saturation may have ended without all resolvents, or without the
+    /// empty clause even though the clauses are unsatisfiable.
+    pub fn is_incomplete(&self) -> bool {
+        return self.discarded > 0;
+    }
+}
+
+impl AddAssign<&SolveStats> for SolveStats {
+    fn add_assign(&mut self, other: &SolveStats) {
+        self.inferences += other.inferences;
+        self.generated += other.generated;
+        self.subsumed += other.subsumed;
+        self.discarded += other.discarded;
+        self.time += other.time;
     }
 }
 
+// Shows all counts on one line, like `12 inferences, 10 generated, 4 subsumed in 0.0012s`. The
+// number of discarded resolvents is only shown when some were discarded.
+impl Display for SolveStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} inferences, {} generated, {} subsumed", self.inferences, self.generated, self.subsumed)?;
+        if self.is_incomplete() {
+            write!(f, ", {} discarded", self.discarded)?;
+        }
+        return write!(f, " in {}s", self.time.as_secs_f64());
+    }
+}
+
+/// A set of terms of a normal form, like the clauses of a [Cnf]. Unlike a plain [HashSet],
+/// which hashes with random keys, it hashes the same way on every run, so that the order in
+/// which its terms are iterated only depends on how it was built, and on the order in which
+/// their symbols were first interned (see [Symbol](crate::symbol::Symbol)), which hash by
+/// their ID. This keeps the solver and everything that walks clauses reproducible from run to
+/// run.
+pub type TermSet<T> = HashSet<T, BuildHasherDefault<DefaultHasher>>;
+
 /// A statement in conjunction-normal form (CNF). A [Cnf] object acts as a set of
-/// [Disj] objects.
+/// [Disj] objects. A [Cnf] without clauses is a tautology.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Cnf {
-    pub terms: HashSet<Disj>
+    pub terms: TermSet<Disj>
 }
 
+#[allow(unused)]
 impl Cnf {
     pub fn new() -> Cnf {
         return Cnf {
-            terms: HashSet::new()
+            terms: TermSet::default()
         };
     }
 
-    pub fn of_vec(vec: &Vec<Disj>) -> Cnf {
+    pub fn of_vec(vec: &[Disj]) -> Cnf {
         let mut cnf = Self::new();
         for disj in vec.iter() {
             cnf.insert((*disj).clone());
@@ -256,6 +424,17 @@ impl Cnf {
         self.terms.clear();
     }
 
+    /// Returns the number of clauses this set has room for without allocating more memory.
+    pub fn capacity(&self) -> usize {
+        return self.terms.capacity();
+    }
+
+    /// Frees as much of the memory that the clauses do not need as possible, e.g. after many
+    /// clauses were removed.
+    pub fn shrink_to_fit(&mut self) {
+        self.terms.shrink_to_fit();
+    }
+
     pub fn insert(&mut self, disj: Disj) -> bool {
         return self.terms.insert(disj);
     }
@@ -272,18 +451,182 @@ impl Cnf {
         return self.terms.contains(disj);
     }
 
+    /// Tests whether this [Cnf] contains all clauses of the other, syntactically. See
+    /// [Self::entails] for a semantic check.
     pub fn contains_all(&self, cnf: &Cnf) -> bool {
         return cnf.terms.iter().all({ |e| 
             self.contains(e)
         });
     }
 
+    /// Tests whether the other [Cnf] follows from this one: whether every model of this one
+    /// is a model of the other. Every clause of the other is refuted: it follows when its
+    /// negation is unsatisfiable together with this [Cnf].
+    ///
+    /// Unlike [Self::contains_all], this does not need the clauses of the other to be
+    /// present. For example, `(P)` entails `(P | Q)`.
+    pub fn entails(&self, other: &Cnf) -> bool {
+        return Queries::new(self).entails(other);
+    }
+
+    /// Conditions this [Cnf] on the given value of a symbol: clauses that the value satisfies
+    /// are left out, and the literals of the symbol that the value falsifies are removed from
+    /// the other clauses. The result does not mention the symbol. A clause of which the only
+    /// literal is falsified becomes the empty clause, a contradiction.
+    ///
+    /// For example, conditioning `(P | Q) & (!P | R)` on `P` being true gives `(R)`.
+    pub fn condition(&self, symbol: &str, value: bool) -> Cnf {
+        let mut out = Cnf::new();
+
+        for disj in self.terms.iter() {
+            if disj.contains(&Literal::new(symbol, value)) {
+                continue;
+            }
+
+            let lits = disj.literals().iter().filter(|l| l.atom != symbol).cloned();
+            out.insert(Disj::of_literals(lits).unwrap());
+        }
+
+        return out;
+    }
+
+    /// Conditions this [Cnf] on a partial assignment: on the value of every symbol in the
+    /// assignment, see [Self::condition].
+    pub fn assume(&self, assignment: &[(String, bool)]) -> Cnf {
+        return assignment.iter().fold(self.clone(), |cnf, (sym, value)| cnf.condition(sym, *value));
+    }
+
+    /// Returns the pure literals of this [Cnf]: the symbols that occur in one polarity only,
+    /// along with that polarity, in order of name.
+    ///
+    /// For example, the pure literals of `(P | Q) & (!Q | P) & (!R)` are `P` and `!R`.
+    pub fn pure_literals(&self) -> BTreeSet<(String, bool)> {
+        let mut pos = BTreeSet::new();
+        let mut neg = BTreeSet::new();
+        for disj in self.terms.iter() {
+            pos.extend(disj.pos());
+            neg.extend(disj.neg());
+        }
+
+        let pure_pos = pos.difference(&neg).map(|s| (s.to_string(), true));
+        let pure_neg = neg.difference(&pos).map(|s| (s.to_string(), false));
+        return pure_pos.chain(pure_neg).collect();
+    }
+
+    /// Removes all clauses that contain a pure literal (see [Self::pure_literals]), until no
+    /// pure literals remain. Giving a pure literal its polarity satisfies all clauses it occurs
+    /// in, and no other clauses mention its symbol, so the result is satisfiable exactly when
+    /// this [Cnf] is: any model of the result is extended to a model of this [Cnf] by making the
+    /// eliminated literals true. The result is not equivalent, though, as it no longer
+    /// constrains the eliminated symbols.
+    ///
+    /// For example, `(P | Q) & (!Q | R) & (!R | Q)` becomes `(!Q | R) & (!R | Q)`.
+    pub fn eliminate_pure_literals(&self) -> Cnf {
+        let mut out = self.clone();
+
+        loop {
+            let pure = Vec::from_iter(out.pure_literals());
+            if pure.is_empty() {
+                return out;
+            }
+            out = out.assume(&pure);
+        }
+    }
+
+    /// Returns the clauses in the order in which they are shown: units first, then by width,
+    /// then lexicographically by their literals, see [Disj::display_cmp]. This keeps printed
+    /// and exported clause sets easy to scan and stable to compare.
+    pub fn sorted(&self) -> Vec<&Disj> {
+        let mut clauses = Vec::from_iter(self.terms.iter());
+        clauses.sort_by(|a, b| a.display_cmp(b));
+        return clauses;
+    }
+
+    /// Writes these clauses for LaTeX math mode, in the order in which they are shown, like
+    /// `\lnot R \land (P \lor Q)`, or `\top` if there are none. Clauses are parenthesized
+    /// when there is more than one.
+    pub fn to_latex(&self) -> String {
+        if self.terms.is_empty() {
+            return String::from("\\top");
+        }
+
+        let single = self.terms.len() == 1;
+        let clauses: Vec<String> = self.sorted().into_iter().map(|d| match d.len() {
+            _ if single => d.to_latex(),
+            0 | 1 => d.to_latex(),
+            _ => format!("({})", d.to_latex()),
+        }).collect();
+        return clauses.join(" \\land ");
+    }
+
+    /// Returns the symbols that occur in the clauses of this [Cnf], in order of name.
+    pub fn symbols(&self) -> BTreeSet<String> {
+        let mut out = BTreeSet::new();
+        for disj in self.terms.iter() {
+            out.extend(disj.literals().iter().map(|l| l.atom.to_string()));
+        }
+        return out;
+    }
+
+    /// Tests whether a clause of this [Cnf] subsumes the given clause, so that adding it would
+    /// add nothing new. This is forward subsumption.
+    pub fn is_subsumed(&self, disj: &Disj) -> bool {
+        return self.terms.iter().any(|d| disj.subsumed_by(d));
+    }
+
+    /// Removes the clauses of this [Cnf] that are strictly subsumed by the given clause, and
+    /// returns how many were removed. This is backward subsumption.
+    pub fn remove_subsumed_by(&mut self, disj: &Disj) -> usize {
+        let len = self.terms.len();
+        self.terms.retain(|d| d == disj || !d.subsumed_by(disj));
+        return len - self.terms.len();
+    }
+
+    /// Inserts the given clause, unless a clause of this [Cnf] subsumes it, and removes the
+    /// clauses that it subsumes. Returns whether the clause was inserted. Inserting clauses
+    /// this way keeps a [Cnf] free of subsumed clauses.
+    pub fn insert_unsubsumed(&mut self, disj: Disj) -> bool {
+        if self.is_subsumed(&disj) {
+            return false;
+        }
+
+        self.remove_subsumed_by(&disj);
+        return self.insert(disj);
+    }
+
+    /// Removes all clauses of this [Cnf] that are subsumed by another clause, and returns how
+    /// many were removed. The result has the same models.
+    ///
+    /// For example, `(P) & (P | Q | R) & (!Q | R) & (!Q | R | S)` becomes `(P) & (!Q | R)`.
+    pub fn remove_subsumed(&mut self) -> usize {
+        // Shorter clauses subsume longer ones, so they are kept first
+        let mut clauses = Vec::from_iter(self.terms.drain());
+        clauses.sort_by_key(Disj::len);
+
+        let mut removed = 0;
+        for disj in clauses {
+            if self.is_subsumed(&disj) {
+                removed += 1;
+            } else {
+                self.insert(disj);
+            }
+        }
+        return removed;
+    }
+
+    /// Tests whether all terms of this [Cnf] are Horn clauses. See [Disj::is_horn].
+    pub fn is_horn(&self) -> bool {
+        return self.terms.iter().all(Disj::is_horn);
+    }
+
     pub fn resolve(&self, out: &mut Cnf) -> bool {
         let stmts = Vec::from_iter(self.terms.iter());
+        let occurrences = Occurrences::of_clauses(stmts.iter().copied());
         let mut change = false;
 
         for i in 0..stmts.len() {
-            for j in (i+1)..stmts.len() {
+            // Only clauses with a complementary literal resolve with this one
+            for j in occurrences.partners(stmts[i]).into_iter().filter(|j| *j > i) {
                 let a = stmts[i];
                 let b = stmts[j];
 
@@ -296,14 +639,389 @@ impl Cnf {
 
         return change;
     }
+
+    /// Adds all resolvents of the clauses to the clauses, round by round, until no new
+    /// resolvents follow or the empty clause is derived, or a limit of the given configuration
+    /// is hit. In the latter case, the clauses hold all resolvents found so far. Limits are
+    /// checked after resolving a clause with all others, so a limit also stops saturation
+    /// halfway a round.
+    ///
+    /// Clauses that are subsumed by other clauses are left out throughout: they are removed
+    /// before saturating, resolvents that are subsumed are not added, and clauses that a new
+    /// resolvent subsumes are removed. For instance, given `P`, the resolvent `P | Q` is not
+    /// added. This keeps the clauses small, and does not change their models.
+    ///
+    /// Returns how saturation ended, along with [SolveStats] on the work it took.
+    pub fn saturate(&mut self, config: &SaturationConfig) -> (SaturationOutcome, SolveStats) {
+        let clauses = std::mem::take(self);
+        return self.saturate_with(clauses, config);
+    }
+
+    /// Adds the given clauses to these clauses, and saturates them like [Self::saturate], under
+    /// the assumption that these clauses are already saturated: all their resolvents are
+    /// already present, or subsumed by a clause that is. When that saturation stopped at a
+    /// limit, this does not hold, and [Self::saturate] must be used instead.
+    ///
+    /// Only new clauses are resolved: in every round, the clauses that were added in the
+    /// previous round, the frontier, are resolved with each other and with the older clauses,
+    /// but older clauses are never resolved with each other again. This makes adding a few
+    /// clauses to a large saturated set of clauses much cheaper than saturating it again.
+    pub fn saturate_with(&mut self, new: Cnf, config: &SaturationConfig) -> (SaturationOutcome, SolveStats) {
+        let mut saturation = Saturation::new(std::mem::take(self), new);
+        let outcome = saturation.run(config);
+        let stats = saturation.stats().clone();
+        *self = saturation.into_clauses();
+        return (outcome, stats);
+    }
+}
+
+/// The number of clauses of the frontier that [Saturation::run] resolves at once, before it
+/// adds their resolvents. With the `parallel` feature, the clauses of a batch are resolved on
+/// all threads; without it, one at a time, so no resolvents are computed in vain when a limit
+/// stops saturation.
+const BATCH: usize = if cfg!(feature = "parallel") { 64 } else { 1 };
+
+/// An occurrence index of a list of clauses: for every literal, the positions of the clauses
+/// in the list that contain it. The clauses that resolve with a clause are those containing the
+/// complement of one of its literals, so the index finds them without trying every clause.
+#[derive(Clone, Debug)]
+pub struct Occurrences {
+    index: HashMap<Literal, Vec<usize>, BuildHasherDefault<DefaultHasher>>
 }
 
+impl Occurrences {
+    pub fn new() -> Occurrences {
+        return Occurrences { index: HashMap::default() };
+    }
+
+    /// Indexes the given clauses, each by its position in the iteration.
+    pub fn of_clauses<'a>(clauses: impl IntoIterator<Item = &'a Disj>) -> Occurrences {
+        let mut occurrences = Self::new();
+        for (i, disj) in clauses.into_iter().enumerate() {
+            occurrences.insert(i, disj);
+        }
+        return occurrences;
+    }
+
+    /// Adds the clause at the given position to the index. Positions must be inserted in
+    /// increasing order, so that [Self::get] lists them in order.
+    pub fn insert(&mut self, position: usize, disj: &Disj) {
+        for lit in disj.literals() {
+            self.index.entry(lit.clone()).or_default().push(position);
+        }
+    }
+
+    /// Returns the positions of the clauses that contain the given literal, in increasing
+    /// order.
+    pub fn get(&self, lit: &Literal) -> &[usize] {
+        return self.index.get(lit).map(Vec::as_slice).unwrap_or(&[]);
+    }
+
+    /// Returns the positions of the clauses that have a literal whose complement occurs in the
+    /// given clause: the clauses it may resolve with. They are given in increasing order, and
+    /// every position once, even when the clauses clash on more than one symbol.
+    pub fn partners(&self, disj: &Disj) -> Vec<usize> {
+        let mut out = Vec::new();
+        for lit in disj.literals() {
+            out.extend_from_slice(self.get(&!lit));
+        }
+        out.sort_unstable();
+        out.dedup();
+        return out;
+    }
+}
+
+impl Default for Occurrences {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+/// A saturation in progress, see [Cnf::saturate_with]: the clauses found so far, and the
+/// frontier among them, which are the clauses that are not yet resolved with the others. All
+/// other clauses are resolved with each other.
+///
+/// A saturation can be run in parts: when [Self::run] stops at a limit, even halfway a round,
+/// it can be run again to continue where it stopped. Its state can be taken apart by
+/// [Self::into_parts], [Self::inputs] and [Self::stats], and put together again by
+/// [Self::new], [Self::set_inputs] and [Self::set_stats], for instance to store it on disk and
+/// resume it later.
+#[derive(Clone, Debug)]
+pub struct Saturation {
+    /// All clauses, including the frontier.
+    clauses: Cnf,
+
+    /// The clauses that are not yet resolved with the other clauses, in the order in which
+    /// they are resolved.
+    frontier: Vec<Disj>,
+
+    /// The input clauses of [ResolutionStrategy::Input]: the clauses before the first time the
+    /// saturation ran with that strategy.
+    inputs: Option<TermSet<Disj>>,
+
+    /// Numbers the symbols of the clauses, see [Self::bits].
+    encoding: BitEncoding,
+
+    /// Every clause as a [BitClause], which are resolved and tested for subsumption instead.
+    bits: HashMap<Disj, BitClause, BuildHasherDefault<DefaultHasher>>,
+
+    /// The work done over all runs, see [Self::stats].
+    stats: SolveStats
+}
+
+impl Saturation {
+    /// Starts to saturate the given new clauses along with the given clauses, which must be
+    /// resolved with each other already. New clauses that are subsumed are left out, and
+    /// clauses that new clauses subsume are removed.
+    pub fn new(clauses: Cnf, new: Cnf) -> Saturation {
+        // Resolvents have no other symbols than the clauses they are resolved from
+        let mut encoding = BitEncoding::of_symbols(clauses.symbols().union(&new.symbols()));
+        let bits = clauses.terms.iter().map(|d| (d.clone(), encoding.encode(d))).collect();
+
+        let mut saturation = Saturation { clauses, frontier: Vec::new(), inputs: None, encoding, bits, stats: SolveStats::default() };
+        for disj in new.sorted() {
+            let bits = saturation.encoding.encode(disj);
+            if saturation.insert(disj.clone(), bits) {
+                saturation.frontier.push(disj.clone());
+            }
+        }
+        return saturation;
+    }
+
+    /// Inserts the given clause, with its [BitClause], unless a clause subsumes it, and removes
+    /// the clauses that it subsumes, like [Cnf::insert_unsubsumed]. Returns whether the clause
+    /// was inserted.
+    fn insert(&mut self, disj: Disj, bits: BitClause) -> bool {
+        if self.bits.values().any(|b| b.subsumes(&bits)) {
+            self.stats.subsumed += 1;
+            return false;
+        }
+
+        // No clause equals the new one, so all clauses it subsumes are strictly subsumed
+        let subsumed = Vec::from_iter(self.bits.iter().filter(|(_, b)| bits.subsumes(b)).map(|(d, _)| d.clone()));
+        self.stats.subsumed += subsumed.len();
+        for d in subsumed {
+            self.bits.remove(&d);
+            self.clauses.terms.remove(&d);
+        }
+
+        self.clauses.insert(disj.clone());
+        self.bits.insert(disj, bits);
+        return true;
+    }
+
+    /// Returns all clauses found so far, including the frontier.
+    pub fn clauses(&self) -> &Cnf {
+        return &self.clauses;
+    }
+
+    /// Returns the frontier: the clauses that are not yet resolved with the other clauses.
+    pub fn frontier(&self) -> &[Disj] {
+        return &self.frontier;
+    }
+
+    /// Returns the work done since this saturation started, over all times it ran, including
+    /// the clauses left out by [Self::new] because they are subsumed.
+    pub fn stats(&self) -> &SolveStats {
+        return &self.stats;
+    }
+
+    /// Returns all clauses found so far, including the frontier, ending this saturation.
+    pub fn into_clauses(self) -> Cnf {
+        return self.clauses;
+    }
+
+    /// Returns the input clauses of [ResolutionStrategy::Input], or `None` if this saturation
+    /// did not run with that strategy yet.
+    pub fn inputs(&self) -> Option<Cnf> {
+        return self.inputs.as_ref().map(|terms| Cnf { terms: terms.clone() });
+    }
+
+    /// Sets the input clauses of [ResolutionStrategy::Input], to resume a saturation that ran
+    /// with that strategy before, see [Self::inputs].
+    pub fn set_inputs(&mut self, inputs: Option<Cnf>) {
+        self.inputs = inputs.map(|cnf| cnf.terms);
+    }
+
+    /// Sets the work done so far, to resume a saturation that ran before, see [Self::stats].
+    pub fn set_stats(&mut self, stats: SolveStats) {
+        self.stats = stats;
+    }
+
+    /// Splits this saturation into the clauses that are resolved with each other, and the
+    /// frontier, which [Self::new] accepts to resume it. The input clauses and the stats are
+    /// not kept, see [Self::set_inputs] and [Self::set_stats] to restore them.
+    pub fn into_parts(self) -> (Cnf, Cnf) {
+        let mut clauses = self.clauses;
+        let mut frontier = Cnf::new();
+        for disj in self.frontier {
+            clauses.terms.remove(&disj);
+            frontier.insert(disj);
+        }
+        return (clauses, frontier);
+    }
+
+    /// Resolves the frontier with all clauses, round by round, until no new resolvents
+    /// follow or the empty clause is derived, or a limit of the given configuration is hit.
+    /// Limits apply to this call only, e.g. the maximum time is counted from the start of this
+    /// call. Only the pairs of clauses that the [ResolutionStrategy] of the configuration
+    /// allows are resolved.
+    pub fn run(&mut self, config: &SaturationConfig) -> SaturationOutcome {
+        let start = Instant::now();
+        let outcome = self.run_from(start, config);
+        self.stats.time += start.elapsed();
+        return outcome;
+    }
+
+    /// Runs this saturation like [Self::run], which started at the given time.
+    fn run_from(&mut self, start: Instant, config: &SaturationConfig) -> SaturationOutcome {
+        let mut inferences = 0;
+        let mut rounds = 0;
+
+        if config.strategy == ResolutionStrategy::Input && self.inputs.is_none() {
+            self.inputs = Some(self.clauses.terms.clone());
+        }
+
+        loop {
+            // Clauses of the frontier may have been subsumed by later ones
+            let clauses = &self.clauses;
+            self.frontier.retain(|d| clauses.contains(d));
+
+            if self.clauses.contains(&Disj::contradiction()) {
+                return SaturationOutcome::Refuted;
+            }
+            if self.frontier.is_empty() {
+                return SaturationOutcome::Saturated;
+            }
+            if config.max_rounds.is_some_and(|max| rounds >= max) {
+                return SaturationOutcome::LimitReached;
+            }
+
+            let new: TermSet<&Disj> = self.frontier.iter().collect();
+            let old = Vec::from_iter(self.clauses.sorted().into_iter().filter(|d| !new.contains(d)).cloned());
+            let mut next = Vec::new();
+
+            // The old clauses are at the positions before the frontier
+            let occurrences = Occurrences::of_clauses(old.iter().chain(self.frontier.iter()));
+            let bits = Vec::from_iter(old.iter().chain(self.frontier.iter()).map(|d| self.bits[d].clone()));
+
+            for batch in (0..self.frontier.len()).step_by(BATCH) {
+                let end = self.frontier.len().min(batch + BATCH);
+                let found = self.resolve_batch(batch..end, &old, &bits, &occurrences, config);
+
+                for (i, (attempts, found)) in (batch..end).zip(found) {
+                    self.stats.inferences += attempts;
+                    for (res, res_bits) in found {
+                        inferences += 1;
+                        self.stats.generated += 1;
+                        if config.max_length.is_some_and(|max| res.len() > max) {
+                            self.stats.discarded += 1;
+                            continue;
+                        }
+                        if self.insert(res.clone(), res_bits) {
+                            next.push(res);
+                        }
+                        if self.clauses.contains(&Disj::contradiction()) {
+                            return SaturationOutcome::Refuted;
+                        }
+                    }
+
+                    if config.is_exceeded(inferences, self.clauses.terms.len(), start) {
+                        // The clauses of the frontier up to i are resolved with all older clauses
+                        // and the rest of the frontier, and the resolvents of this round join the
+                        // frontier, so they are resolved with them later on
+                        self.frontier.drain(..=i);
+                        self.frontier.extend(next);
+                        let clauses = &self.clauses;
+                        self.frontier.retain(|d| clauses.contains(d));
+                        return SaturationOutcome::LimitReached;
+                    }
+                }
+            }
+
+            self.frontier = next;
+            rounds += 1;
+        }
+    }
+
+    /// Returns the resolvents of the clause of the frontier at the given index with the old
+    /// clauses and the later clauses of the frontier, as far as the [ResolutionStrategy] of the
+    /// configuration allows, in the order of its partners, each along with its [BitClause].
+    /// Also returns the number of partners it was resolved with, including those that give a
+    /// tautology. The occurrences index the old clauses, followed by the frontier, and the
+    /// bits are the [BitClause]s of these clauses in the same order.
+    fn resolve_one(&self, i: usize, old: &[Disj], bits: &[BitClause], occurrences: &Occurrences, config: &SaturationConfig) -> (usize, Vec<(Disj, BitClause)>) {
+        let disj = &self.frontier[i];
+        let mut attempts = 0;
+        let mut out = Vec::new();
+
+        // Every pair of new clauses is resolved once
+        let position = old.len() + i;
+        for j in occurrences.partners(disj).into_iter().filter(|j| *j < old.len() || *j > position) {
+            let other = if j < old.len() { &old[j] } else { &self.frontier[j - old.len()] };
+            let allowed = match config.strategy {
+                ResolutionStrategy::Full => true,
+                ResolutionStrategy::Unit => disj.len() == 1 || other.len() == 1,
+                ResolutionStrategy::Input => {
+                    let inputs = self.inputs.as_ref().unwrap();
+                    inputs.contains(disj) || inputs.contains(other)
+                },
+            };
+            if !allowed {
+                continue;
+            }
+
+            attempts += 1;
+            if let Some(res) = bits[position].resolve(&bits[j]) {
+                out.push((self.encoding.decode(&res), res));
+            }
+        }
+        return (attempts, out);
+    }
+
+    /// Resolves the clauses of the frontier in the given range, see [Self::resolve_one], and
+    /// returns their resolvents in order.
+    #[cfg(not(feature = "parallel"))]
+    fn resolve_batch(&self, range: Range<usize>, old: &[Disj], bits: &[BitClause], occurrences: &Occurrences, config: &SaturationConfig) -> Vec<(usize, Vec<(Disj, BitClause)>)> {
+        return range.map(|i| self.resolve_one(i, old, bits, occurrences, config)).collect();
+    }
+
+    /// Resolves the clauses of the frontier in the given range on all threads, see
+    /// [Self::resolve_one], and returns their resolvents in order. Collecting keeps the order
+    /// of the range, so the result is the same as when resolving one clause after the other.
+    #[cfg(feature = "parallel")]
+    fn resolve_batch(&self, range: Range<usize>, old: &[Disj], bits: &[BitClause], occurrences: &Occurrences, config: &SaturationConfig) -> Vec<(usize, Vec<(Disj, BitClause)>)> {
+        return range.into_par_iter().map(|i| self.resolve_one(i, old, bits, occurrences, config)).collect();
+    }
+}
+
+// Debug shows every clause by an ID: its index when the clauses are sorted as they are shown.
+impl Debug for Cnf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cnf ")?;
+        return f.debug_map().entries(self.sorted().into_iter().enumerate()).finish();
+    }
+}
+
+impl Default for Cnf {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+// Like in the input syntax, a Cnf without clauses is shown as the tautology `*`, and the empty
+// clause as the contradiction `~`, so `(~)` is a contradictory Cnf. Clauses are shown in the
+// order of Cnf::sorted.
 impl Display for Cnf {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.terms.is_empty() {
+            return write!(f, "*");
+        }
+
         let mut sep = false;
 
         write!(f, "(")?;
-        for t in self.terms.iter() {
+        for t in self.sorted() {
             if sep {
                 write!(f, ") & (")?;
             } else {
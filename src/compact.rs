@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+
+use crate::cnf::{Cnf, Disj};
+use crate::literal::Literal;
+
+/// Tests whether the given symbol is auxiliary: a symbol whose name starts with `_`, like
+/// `_carry.3`. Auxiliary symbols only serve to encode other symbols, and their values are not
+/// of interest by themselves, so [compact] may eliminate them.
+pub fn is_auxiliary(sym: &str) -> bool {
+    return sym.starts_with('_');
+}
+
+/// Simplifies the given [Cnf] into a smaller one that has the same models, as far as symbols
+/// that are not auxiliary are concerned (see [is_auxiliary]). Four simplifications are
+/// applied, until none of them applies anymore:
+/// - Clauses that are subsumed by other clauses are removed: given `A`, the clause `A | B`
+///   says nothing new.
+/// - Clauses that contain a pure auxiliary literal, an auxiliary symbol that occurs in one
+///   polarity only, are removed: the symbol can always be given the value that satisfies
+///   them. See [Cnf::eliminate_pure_literals].
+/// - Auxiliary symbols that are equivalent to another symbol, by the clauses `X | !Y` and
+///   `!X | Y`, are replaced by that symbol. Likewise, auxiliary symbols that are equivalent to
+///   the negation of another symbol, by the clauses `X | Y` and `!X | !Y`, are replaced by
+///   that negated symbol.
+/// - Auxiliary symbols are eliminated by resolving all clauses that contain them positively
+///   with all clauses that contain them negatively, as long as this does not produce more
+///   clauses than it removes.
+pub fn compact(cnf: &Cnf) -> Cnf {
+    let mut clauses: Vec<Disj> = cnf.terms.iter().cloned().collect();
+
+    loop {
+        remove_subsumed(&mut clauses);
+
+        if eliminate_pure(&mut clauses) {
+            continue;
+        }
+        if substitute_equivalence(&mut clauses) {
+            continue;
+        }
+        if eliminate_symbol(&mut clauses) {
+            continue;
+        }
+
+        break;
+    }
+
+    return Cnf::of_vec(&clauses);
+}
+
+/// Removes all clauses that are subsumed by another clause.
+fn remove_subsumed(clauses: &mut Vec<Disj>) {
+    // Shorter clauses subsume longer ones, so check them first
+    clauses.sort_by_key(Disj::len);
+
+    let mut kept: Vec<Disj> = Vec::new();
+    for disj in clauses.drain(..) {
+        if !kept.iter().any(|k| k.subsumes(&disj)) {
+            kept.push(disj);
+        }
+    }
+
+    *clauses = kept;
+}
+
+/// Removes all clauses that contain a pure auxiliary literal. Returns false if there is no
+/// such literal.
+fn eliminate_pure(clauses: &mut Vec<Disj>) -> bool {
+    let pure: Vec<(String, bool)> = Cnf::of_vec(clauses).pure_literals()
+        .into_iter()
+        .filter(|(sym, _)| is_auxiliary(sym))
+        .collect();
+
+    if pure.is_empty() {
+        return false;
+    }
+
+    clauses.retain(|d| !pure.iter().any(|(sym, value)| d.contains(&Literal::new(sym, *value))));
+    return true;
+}
+
+/// Returns the literals of a binary clause, in order.
+fn binary(disj: &Disj) -> Option<[Literal; 2]> {
+    let mut lits = disj.literals().iter().cloned();
+
+    let a = lits.next()?;
+    let b = lits.next()?;
+    if lits.next().is_some() {
+        return None;
+    }
+
+    return Some([a, b]);
+}
+
+/// Finds an auxiliary symbol that is equivalent to another symbol or its negation, and
+/// replaces it. Returns false if there is no such symbol.
+fn substitute_equivalence(clauses: &mut Vec<Disj>) -> bool {
+    let binaries: HashSet<[Literal; 2]> = clauses.iter().filter_map(binary).collect();
+
+    for [a, b] in binaries.iter() {
+        // The clause `x | y` (with signs) along with `!x | !y` means that x is equivalent to !y
+        let mut dual = [!a, !b];
+        dual.sort();
+        if !binaries.contains(&dual) {
+            continue;
+        }
+
+        // x is equivalent to y if their signs differ, and to !y if their signs are the same
+        let same = a.polarity != b.polarity;
+        let (x, y) = (&a.atom, &b.atom);
+
+        let (from, to) = if is_auxiliary(y) {
+            (y, x)
+        } else if is_auxiliary(x) {
+            (x, y)
+        } else {
+            continue;
+        };
+
+        substitute(clauses, from, to, same);
+        return true;
+    }
+
+    return false;
+}
+
+/// Replaces the symbol `from` in all clauses by the symbol `to`, or by its negation if not
+/// `same`. Clauses that become tautological are removed.
+fn substitute(clauses: &mut Vec<Disj>, from: &str, to: &str, same: bool) {
+    let mut out = Cnf::new();
+
+    for disj in clauses.iter() {
+        let lits = disj.literals().iter().map(|l| if l.atom == from {
+            Literal::new(to, l.polarity == same)
+        } else {
+            l.clone()
+        });
+
+        if let Some(disj) = Disj::of_literals(lits) {
+            out.insert(disj);
+        }
+    }
+
+    *clauses = out.terms.into_iter().collect();
+}
+
+/// Eliminates an auxiliary symbol by resolution, if this does not increase the number of
+/// clauses. Returns false if no symbol could be eliminated.
+fn eliminate_symbol(clauses: &mut Vec<Disj>) -> bool {
+    let mut symbols: Vec<String> = clauses.iter()
+        .flat_map(|d| d.literals().iter().map(|l| l.atom.to_string()))
+        .filter(|s| is_auxiliary(s))
+        .collect();
+    symbols.sort();
+    symbols.dedup();
+
+    for sym in symbols.iter() {
+        let pos: Vec<&Disj> = clauses.iter().filter(|d| d.is_pos(sym)).collect();
+        let neg: Vec<&Disj> = clauses.iter().filter(|d| d.is_neg(sym)).collect();
+
+        let mut resolvents = Cnf::new();
+        for p in pos.iter() {
+            for n in neg.iter() {
+                if let Some(disj) = p.resolve(n, sym) {
+                    resolvents.insert(disj);
+                }
+            }
+        }
+
+        if resolvents.terms.len() > pos.len() + neg.len() {
+            continue;
+        }
+
+        clauses.retain(|d| d.is_unknown(sym));
+        clauses.extend(resolvents.terms);
+        return true;
+    }
+
+    return false;
+}
@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+
+use crate::solver::Model;
+
+/// Maps a [Model] of an encoded problem back to an answer in the domain of that problem, e.g.
+/// the filled in grid of a sudoku rather than the truth values of its cell symbols.
+pub trait Decoder {
+    /// Reads the answer from the given model and presents it as text.
+    fn decode(&self, model: &Model) -> String;
+}
+
+impl<F> Decoder for F where F: Fn(&Model) -> String {
+    fn decode(&self, model: &Model) -> String {
+        return self(model);
+    }
+}
+
+/// A set of named [Decoder]s, which are all applied to a model to present it. Decoders are
+/// applied in the order they were registered.
+pub struct Decoders {
+    decoders: Vec<(String, Box<dyn Decoder>)>
+}
+
+impl Decoders {
+    pub fn new() -> Decoders {
+        return Decoders { decoders: Vec::new() };
+    }
+
+    /// Registers a decoder under the given name. A decoder that was registered under the same
+    /// name before is replaced.
+    pub fn register(&mut self, name: &str, decoder: impl Decoder + 'static) {
+        let decoder: Box<dyn Decoder> = Box::new(decoder);
+
+        match self.decoders.iter_mut().find(|(n, _)| n == name) {
+            Some(entry) => entry.1 = decoder,
+            None => self.decoders.push((name.to_string(), decoder)),
+        }
+    }
+
+    /// Removes the decoder with the given name. Returns false if there was no such decoder.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        let len = self.decoders.len();
+        self.decoders.retain(|(n, _)| n != name);
+        return self.decoders.len() != len;
+    }
+
+    /// Returns the decoder with the given name.
+    pub fn get(&self, name: &str) -> Option<&dyn Decoder> {
+        return self.decoders.iter().find(|(n, _)| n == name).map(|(_, d)| d.as_ref());
+    }
+
+    /// Tests whether no decoders are registered.
+    pub fn is_empty(&self) -> bool {
+        return self.decoders.is_empty();
+    }
+
+    /// Applies all decoders to the given model, returning the name of each decoder along with
+    /// its answer.
+    pub fn decode(&self, model: &Model) -> Vec<(String, String)> {
+        return self.decoders.iter().map(|(n, d)| (n.clone(), d.decode(model))).collect();
+    }
+}
+
+impl Default for Decoders {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+/// Finds the symbols of the form `prefix.a.b.c` that are true in the given model, and returns
+/// the parts after the prefix of each of them, e.g. `["a", "b", "c"]`.
+pub fn indices(model: &Model, prefix: &str) -> Vec<Vec<String>> {
+    let start = format!("{prefix}.");
+
+    return model.true_symbols()
+        .filter_map(|s| s.strip_prefix(&start))
+        .map(|s| s.split('.').map(String::from).collect())
+        .collect();
+}
+
+/// A [Decoder] for one-hot encoded variables: symbols of the form `prefix.key.value`, where
+/// exactly one value is true for every key. The key may be absent, as for symbols declared by
+/// `enum prefix in {...}`. Every key is presented on its own line as `key = value`.
+pub struct OneHot {
+    prefix: String
+}
+
+impl OneHot {
+    pub fn new(prefix: &str) -> OneHot {
+        return OneHot { prefix: prefix.to_string() };
+    }
+}
+
+impl Decoder for OneHot {
+    fn decode(&self, model: &Model) -> String {
+        let mut values: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        for mut parts in indices(model, &self.prefix) {
+            let value = parts.pop().unwrap_or_default();
+            let key = if parts.is_empty() {
+                self.prefix.clone()
+            } else {
+                parts.join(".")
+            };
+
+            values.entry(key).or_default().push(value);
+        }
+
+        let mut out = String::new();
+        for (key, v) in values.iter() {
+            out.push_str(&format!("{key} = {}\n", v.join(", ")));
+        }
+
+        return out;
+    }
+}
+
+/// A [Decoder] for grids: symbols of the form `prefix.row.col.value`, where rows and columns
+/// are numbered from 1. The grid is presented row by row, with cells separated by spaces.
+/// Cells without a true symbol are presented as `.`.
+pub struct Grid {
+    prefix: String
+}
+
+impl Grid {
+    pub fn new(prefix: &str) -> Grid {
+        return Grid { prefix: prefix.to_string() };
+    }
+
+    /// Reads the cells of the grid from a model, indexed by row and column.
+    pub fn cells(&self, model: &Model) -> BTreeMap<(usize, usize), String> {
+        let mut cells = BTreeMap::new();
+
+        for parts in indices(model, &self.prefix) {
+            if let [row, col, value] = &parts[..] {
+                if let (Ok(row), Ok(col)) = (row.parse::<usize>(), col.parse::<usize>()) {
+                    cells.insert((row, col), value.clone());
+                }
+            }
+        }
+
+        return cells;
+    }
+}
+
+impl Decoder for Grid {
+    fn decode(&self, model: &Model) -> String {
+        let cells = self.cells(model);
+        let rows = cells.keys().map(|(r, _)| *r).max().unwrap_or(0);
+        let cols = cells.keys().map(|(_, c)| *c).max().unwrap_or(0);
+
+        let mut out = String::new();
+        for row in 1..=rows {
+            let line: Vec<&str> = (1..=cols)
+                .map(|col| cells.get(&(row, col)).map(String::as_str).unwrap_or("."))
+                .collect();
+
+            out.push_str(&line.join(" "));
+            out.push('\n');
+        }
+
+        return out;
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::cnf::{Cnf, Disj};
+use crate::parser::parse_stmt;
+
+/// Writes a [Cnf] in the DIMACS CNF format, which is understood by most SAT solvers.
+///
+/// DIMACS numbers its variables, so symbols are numbered in order of name, starting at 1.
+/// Every number is listed with its symbol in a comment line (`c <number> <name>`), so that
+/// the output of other solvers can be mapped back to symbols. Clauses are written in the order
+/// in which they are shown, see [Cnf::sorted].
+pub fn write(cnf: &Cnf) -> String {
+    let mut symbols = BTreeSet::new();
+    for disj in cnf.terms.iter() {
+        symbols.extend(disj.literals().iter().map(|l| l.atom.name()));
+    }
+
+    let mut out = String::new();
+    let mut numbers = HashMap::new();
+
+    for (i, s) in symbols.iter().enumerate() {
+        numbers.insert(*s, i + 1);
+        out.push_str(&format!("c {} {s}\n", i + 1));
+    }
+
+    out.push_str(&format!("p cnf {} {}\n", symbols.len(), cnf.terms.len()));
+
+    for disj in cnf.sorted() {
+        for l in disj.literals().iter() {
+            let n = numbers[l.atom.name()] as i64;
+            out.push_str(&format!("{} ", if l.polarity { n } else { -n }));
+        }
+        out.push_str("0\n");
+    }
+
+    return out;
+}
+
+/// Tests whether a comment line (`c ...`) of the DIMACS format starts with the given line.
+fn is_comment(line: &str) -> bool {
+    return line == "c" || line.starts_with("c ") || line.starts_with("c\t");
+}
+
+/// Tests whether the given text is in the DIMACS CNF format: whether it starts with the
+/// problem line `p cnf`, possibly after comment lines. The input syntax of rustyproof can also
+/// have lines starting with `c `, like `c | d`, so comment lines alone do not count.
+pub fn is_dimacs(text: &str) -> bool {
+    return text.lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !is_comment(l))
+        .is_some_and(|l| l.starts_with("p cnf"));
+}
+
+/// Reads a [Cnf] in the DIMACS CNF format, as written by [write] or by other tools.
+///
+/// Variables are named by the comment lines that [write] adds (`c <number> <name>`), so that
+/// clauses written by rustyproof read back into the same symbols. Variables without such a
+/// comment are named `x<number>`, e.g. `x12`. Tautological clauses are left out. Fails if the
+/// problem line is missing or malformed, or if a literal is not a number or is out of the
+/// range of variables that the problem line declares.
+pub fn read(text: &str) -> Result<Cnf, String> {
+    let mut names = HashMap::new();
+    let mut vars = None;
+    let mut cnf = Cnf::new();
+    let mut pos = HashSet::new();
+    let mut neg = HashSet::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+        if is_comment(line) {
+            // Only comments naming a variable by a valid symbol are of interest
+            let mut words = line.split_whitespace().skip(1);
+            if let (Some(number), Some(name), None) = (words.next(), words.next(), words.next()) {
+                let valid = parse_stmt(name).is_ok_and(|s| s.as_symbol() == Some(name));
+                if let (Ok(number), true) = (number.parse::<u64>(), valid) {
+                    names.insert(number, name.to_string());
+                }
+            }
+            continue;
+        }
+        // Some benchmark collections end their files with a line `%`, followed by junk
+        if line.starts_with('%') {
+            break;
+        }
+        if line.starts_with('p') {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words[..] {
+                ["p", "cnf", v, _] if vars.is_none() => {
+                    vars = Some(v.parse::<u64>().map_err(|_| format!("Line {}: Expected the number of variables", i + 1))?);
+                },
+                _ => return Err(format!("Line {}: Expected 'p cnf <variables> <clauses>'", i + 1)),
+            }
+            continue;
+        }
+
+        let vars = vars.ok_or(format!("Line {}: Expected 'p cnf <variables> <clauses>' before the clauses", i + 1))?;
+        for word in line.split_whitespace() {
+            let lit = word.parse::<i64>().map_err(|_| format!("Line {}: Expected a literal, found '{word}'", i + 1))?;
+            if lit.unsigned_abs() > vars {
+                return Err(format!("Line {}: Variable {} is out of range, there are {vars} variables", i + 1, lit.unsigned_abs()));
+            }
+
+            if lit == 0 {
+                if let Some(disj) = Disj::new(std::mem::take(&mut pos), std::mem::take(&mut neg)) {
+                    cnf.insert(disj);
+                }
+                continue;
+            }
+
+            let number = lit.unsigned_abs();
+            let name = names.get(&number).cloned().unwrap_or_else(|| format!("x{number}"));
+            if lit > 0 {
+                pos.insert(name);
+            } else {
+                neg.insert(name);
+            }
+        }
+    }
+
+    if vars.is_none() {
+        return Err(String::from("Expected 'p cnf <variables> <clauses>'"));
+    }
+
+    // The last clause may lack its terminating 0
+    if !pos.is_empty() || !neg.is_empty() {
+        if let Some(disj) = Disj::new(pos, neg) {
+            cnf.insert(disj);
+        }
+    }
+
+    return Ok(cnf);
+}
@@ -0,0 +1,238 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::{Debug, Display};
+
+use crate::cnf::TermSet;
+
+
+/// A conjunction of symbols, either inverted or not: the dual of a [Disj](crate::cnf::Disj).
+/// Symbols are represented by their names. Internally, the conjunction is represented as two
+/// sorted sets, one with non-inverted (positive) symbols, and one with inverted (negative)
+/// symbols.
+///
+/// Note that when both sets are empty, the conjunction is a tautology by vacuous truth:
+/// _"Do all of the conjuncts satisfy? Yes, because there are no conjuncts."_ When the sets
+/// share a symbol, the conjunction is a contradiction, like `P & !P`.
+#[derive(PartialEq, Eq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Conj {
+    pos: BTreeSet<String>,
+    neg: BTreeSet<String>
+}
+
+#[allow(unused)]
+impl Conj {
+    /// Creates a new [Conj], given the sets with positive and negative symbols.
+    ///
+    /// For example, given `pos = {P, Q}` and `neg = {R}`, it creates a conjunction stating
+    /// `P & Q & !R`.
+    pub fn new(pos: HashSet<String>, neg: HashSet<String>) -> Conj {
+        return Conj { pos: pos.into_iter().collect(), neg: neg.into_iter().collect() };
+    }
+
+    /// Creates a new [Conj], given the sets with positive and negative symbols as slices.
+    pub fn of_slices(pos: &[&str], neg: &[&str]) -> Conj {
+        return Self::new(
+            pos.iter().map(|s| s.to_string()).collect(),
+            neg.iter().map(|s| s.to_string()).collect()
+        );
+    }
+
+    /// Returns the set of non-inverted (positive) conjuncts.
+    pub fn pos(&self) -> &BTreeSet<String> {
+        return &self.pos;
+    }
+
+    /// Returns the set of inverted (negative) conjuncts.
+    pub fn neg(&self) -> &BTreeSet<String> {
+        return &self.neg;
+    }
+
+    /// Tests whether the given term is part of this conjunction in non-inverted form.
+    pub fn is_pos(&self, term: &str) -> bool {
+        return self.pos.contains(term);
+    }
+
+    /// Tests whether the given term is part of this conjunction in inverted form.
+    pub fn is_neg(&self, term: &str) -> bool {
+        return self.neg.contains(term);
+    }
+
+    /// Tests whether the given term is not part of this conjunction.
+    pub fn is_unknown(&self, term: &str) -> bool {
+        return !self.is_pos(term) && !self.is_neg(term);
+    }
+
+    /// Tests whether this conjunction presents a tautology, i.e. whether it has no terms.
+    pub fn is_tautology(&self) -> bool {
+        return self.pos.is_empty() && self.neg.is_empty();
+    }
+
+    /// Tests whether this conjunction presents a contradiction, i.e. whether it states a
+    /// symbol both positively and negatively.
+    pub fn is_contradiction(&self) -> bool {
+        return !self.pos.is_disjoint(&self.neg);
+    }
+
+    /// Returns the conjunction of the terms of this conjunction and the other, or `None` if
+    /// that is a contradiction.
+    pub fn combine(&self, other: &Self) -> Option<Conj> {
+        let mut pos = self.pos.clone();
+        let mut neg = self.neg.clone();
+
+        pos.extend(other.pos().iter().cloned());
+        neg.extend(other.neg().iter().cloned());
+
+        if !pos.is_disjoint(&neg) {
+            return None;
+        }
+
+        return Some(Conj { pos, neg });
+    }
+
+    pub fn fact(t: &str) -> Conj {
+        return Self::of_slices(&[t], &[]);
+    }
+
+    pub fn fact_not(t: &str) -> Conj {
+        return Self::of_slices(&[], &[t]);
+    }
+
+    pub fn tautology() -> Conj {
+        return Self { pos: BTreeSet::new(), neg: BTreeSet::new() }
+    }
+}
+
+impl Debug for Conj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return f.debug_struct("Conj").field("pos", &self.pos).field("neg", &self.neg).finish();
+    }
+}
+
+impl Display for Conj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_tautology() {
+            return write!(f, "*");
+        }
+
+        let mut sep = false;
+
+        for p in self.pos.iter() {
+            if sep {
+                write!(f, " & ")?;
+            } else {
+                sep = true;
+            }
+
+            write!(f, "{p}")?;
+        }
+
+        for n in self.neg.iter() {
+            if sep {
+                write!(f, " & ")?;
+            } else {
+                sep = true;
+            }
+
+            write!(f, "!{n}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A statement in disjunctive normal form (DNF): the dual of a [Cnf](crate::cnf::Cnf). A
+/// [Dnf] object acts as a set of [Conj] objects, each of which is a way in which the
+/// statement can be true. A [Dnf] without terms is a contradiction.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Dnf {
+    pub terms: TermSet<Conj>
+}
+
+#[allow(unused)]
+impl Dnf {
+    pub fn new() -> Dnf {
+        return Dnf {
+            terms: TermSet::default()
+        };
+    }
+
+    pub fn of_vec(vec: &[Conj]) -> Dnf {
+        let mut dnf = Self::new();
+        for conj in vec.iter() {
+            dnf.insert((*conj).clone());
+        }
+        return dnf;
+    }
+
+    pub fn clear(&mut self) {
+        self.terms.clear();
+    }
+
+    /// Adds a term to this [Dnf]. Contradictory terms are left out, as they can never be
+    /// true. Returns false if the term was left out or already present.
+    pub fn insert(&mut self, conj: Conj) -> bool {
+        if conj.is_contradiction() {
+            return false;
+        }
+        return self.terms.insert(conj);
+    }
+
+    pub fn insert_all(&mut self, dnf: &Dnf) -> bool {
+        let mut ch = false;
+        for conj in dnf.terms.iter() {
+            ch |= self.insert((*conj).clone());
+        }
+        return ch;
+    }
+
+    pub fn contains(&self, conj: &Conj) -> bool {
+        return self.terms.contains(conj);
+    }
+
+    /// Returns the terms in the order in which they are shown: shorter terms first, then
+    /// sorted by their symbols, like [Cnf::sorted](crate::cnf::Cnf::sorted) does for clauses.
+    pub fn sorted(&self) -> Vec<&Conj> {
+        let mut terms = Vec::from_iter(self.terms.iter());
+        terms.sort_by_key(|c| (c.pos.len() + c.neg.len(), &c.pos, &c.neg));
+        return terms;
+    }
+}
+
+// Like for Cnf, Debug shows every term by an ID: its index when the terms are sorted.
+impl Debug for Dnf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Dnf ")?;
+        return f.debug_map().entries(self.sorted().into_iter().enumerate()).finish();
+    }
+}
+
+impl Default for Dnf {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl Display for Dnf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.terms.is_empty() {
+            return write!(f, "~");
+        }
+
+        let mut sep = false;
+
+        write!(f, "(")?;
+        for t in self.sorted() {
+            if sep {
+                write!(f, ") | (")?;
+            } else {
+                sep = true;
+            }
+
+            write!(f, "{t}")?;
+        }
+        write!(f, ")")?;
+
+        Ok(())
+    }
+}
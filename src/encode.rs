@@ -0,0 +1,315 @@
+use std::fmt::Display;
+use std::fs;
+
+use crate::cnf::{Cnf, Disj};
+use crate::decode::{self, Decoders, Grid, OneHot};
+use crate::dimacs;
+use crate::solver::{Model, Solver};
+
+/// A sudoku puzzle. The grid consists of `n * n` boxes of `n * n` cells each, e.g. a regular
+/// sudoku has 9 boxes of 3 by 3 cells. Every row, column and box must contain each value
+/// from 1 to `n * n` exactly once.
+///
+/// The value `v` of the cell at row `r` and column `c` is encoded as the symbol `cell.r.c.v`,
+/// counting from 1.
+#[derive(Clone)]
+pub struct Sudoku {
+    box_size: usize,
+    cells: Vec<Option<usize>>
+}
+
+impl Sudoku {
+    /// Parses a sudoku from text. Every cell is a single character: a digit for a given
+    /// value, or `.`, `_` or `0` for an empty cell. Whitespace is ignored. The size of the
+    /// grid is derived from the number of cells, so both 4x4 and 9x9 sudokus are accepted.
+    pub fn parse(text: &str) -> Result<Sudoku, String> {
+        let mut cells = Vec::new();
+
+        for c in text.chars() {
+            match c {
+                '.' | '_' | '0' => cells.push(None),
+                '1'..='9' => cells.push(Some(c as usize - '0' as usize)),
+                c if c.is_whitespace() => {},
+                c => return Err(format!("Unexpected character '{c}'")),
+            }
+        }
+
+        let box_size = [2, 3].into_iter().find(|n| n * n * n * n == cells.len())
+            .ok_or(format!("A sudoku must have 16 or 81 cells, found {}", cells.len()))?;
+
+        let sudoku = Sudoku { box_size, cells };
+        for c in sudoku.cells.iter().flatten() {
+            if *c > sudoku.size() {
+                return Err(format!("Value {c} does not fit in a {0}x{0} sudoku", sudoku.size()));
+            }
+        }
+
+        return Ok(sudoku);
+    }
+
+    /// Returns the number of rows, columns and values of this sudoku.
+    pub fn size(&self) -> usize {
+        return self.box_size * self.box_size;
+    }
+
+    /// Returns the value of the cell at the given row and column, counting from 1.
+    pub fn get(&self, row: usize, col: usize) -> Option<usize> {
+        return self.cells[(row - 1) * self.size() + col - 1];
+    }
+
+    /// Returns the symbol stating that the cell at the given row and column has the given value.
+    pub fn atom(row: usize, col: usize, value: usize) -> String {
+        return format!("cell.{row}.{col}.{value}");
+    }
+
+    /// Encodes this sudoku as a set of clauses, which is satisfiable if and only if the
+    /// sudoku has a solution.
+    pub fn encode(&self) -> Cnf {
+        let n = self.size();
+        let mut cnf = Cnf::new();
+
+        // Groups of cells that must each hold every value exactly once
+        let mut groups: Vec<Vec<(usize, usize)>> = Vec::new();
+        for i in 1..=n {
+            groups.push((1..=n).map(|j| (i, j)).collect());
+            groups.push((1..=n).map(|j| (j, i)).collect());
+
+            let r = (i - 1) / self.box_size * self.box_size;
+            let c = (i - 1) % self.box_size * self.box_size;
+            groups.push((0..n).map(|j| (r + j / self.box_size + 1, c + j % self.box_size + 1)).collect());
+        }
+
+        for row in 1..=n {
+            for col in 1..=n {
+                let atoms: Vec<String> = (1..=n).map(|v| Self::atom(row, col, v)).collect();
+                exactly_one(&mut cnf, &atoms);
+
+                if let Some(v) = self.get(row, col) {
+                    cnf.insert(Disj::axiom(&Self::atom(row, col, v)));
+                }
+            }
+        }
+
+        for group in groups.iter() {
+            for v in 1..=n {
+                let atoms: Vec<String> = group.iter().map(|(r, c)| Self::atom(*r, *c, v)).collect();
+                exactly_one(&mut cnf, &atoms);
+            }
+        }
+
+        return cnf;
+    }
+
+    /// Reads the solution of this sudoku from a model of its encoding.
+    pub fn decode(&self, model: &Model) -> Sudoku {
+        let n = self.size();
+        let values = Grid::new("cell").cells(model);
+
+        let mut cells = Vec::new();
+        for row in 1..=n {
+            for col in 1..=n {
+                cells.push(values.get(&(row, col)).and_then(|v| v.parse().ok()));
+            }
+        }
+
+        return Sudoku { box_size: self.box_size, cells };
+    }
+
+    /// Registers a decoder that presents the solution of this sudoku as a grid.
+    pub fn register_decoders(&self, decoders: &mut Decoders) {
+        let sudoku = self.clone();
+        decoders.register("sudoku", move |m: &Model| sudoku.decode(m).to_string());
+    }
+}
+
+impl Display for Sudoku {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let n = self.size();
+
+        for row in 1..=n {
+            if row > 1 && (row - 1) % self.box_size == 0 {
+                writeln!(f)?;
+            }
+
+            for col in 1..=n {
+                if col > 1 && (col - 1) % self.box_size == 0 {
+                    write!(f, " ")?;
+                }
+
+                match self.get(row, col) {
+                    Some(v) => write!(f, "{v}")?,
+                    None => write!(f, ".")?,
+                }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An undirected graph, to be colored such that no two adjacent nodes share a color.
+///
+/// Node `x` having color `c` is encoded as the symbol `node.x.c`, counting colors from 1.
+pub struct Graph {
+    nodes: Vec<String>,
+    edges: Vec<(usize, usize)>
+}
+
+impl Graph {
+    /// Parses a graph from text. Every line holds either a single node name, or two node names
+    /// separated by whitespace or `-`, declaring an edge between them. Node names consist of
+    /// letters, digits and `_`. Text after `#` is a comment.
+    pub fn parse(text: &str) -> Result<Graph, String> {
+        let mut graph = Graph { nodes: Vec::new(), edges: Vec::new() };
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("");
+            let names: Vec<&str> = line
+                .split(|c: char| c.is_whitespace() || c == '-')
+                .filter(|s| !s.is_empty())
+                .collect();
+
+            for name in names.iter() {
+                if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    return Err(format!("Line {}: invalid node name '{name}'", i + 1));
+                }
+            }
+
+            match names[..] {
+                [] => {},
+                [a] => {
+                    graph.node(a);
+                },
+                [a, b] => {
+                    let a = graph.node(a);
+                    let b = graph.node(b);
+                    graph.edges.push((a, b));
+                },
+                _ => return Err(format!("Line {}: expected a node or an edge", i + 1)),
+            }
+        }
+
+        return Ok(graph);
+    }
+
+    /// Returns the index of the node with the given name, adding it if it is new.
+    fn node(&mut self, name: &str) -> usize {
+        if let Some(i) = self.nodes.iter().position(|n| n == name) {
+            return i;
+        }
+
+        self.nodes.push(name.to_string());
+        return self.nodes.len() - 1;
+    }
+
+    /// Returns the symbol stating that the given node has the given color.
+    pub fn atom(node: &str, color: usize) -> String {
+        return format!("node.{node}.{color}");
+    }
+
+    /// Encodes the problem of coloring this graph with `k` colors as a set of clauses, which is
+    /// satisfiable if and only if such a coloring exists.
+    pub fn encode_coloring(&self, k: usize) -> Cnf {
+        let mut cnf = Cnf::new();
+
+        for node in self.nodes.iter() {
+            let atoms: Vec<String> = (1..=k).map(|c| Self::atom(node, c)).collect();
+            exactly_one(&mut cnf, &atoms);
+        }
+
+        for (a, b) in self.edges.iter() {
+            for c in 1..=k {
+                cnf.insert(Disj::of_slices(&[], &[&Self::atom(&self.nodes[*a], c), &Self::atom(&self.nodes[*b], c)]).unwrap());
+            }
+        }
+
+        return cnf;
+    }
+
+    /// Reads the color of every node from a model of the coloring encoding.
+    pub fn decode_coloring(&self, model: &Model) -> Vec<(String, usize)> {
+        let mut colors: Vec<(String, usize)> = decode::indices(model, "node").into_iter()
+            .filter_map(|parts| match &parts[..] {
+                [node, color] => color.parse().ok().map(|c| (node.clone(), c)),
+                _ => None,
+            })
+            .collect();
+
+        colors.sort_by_key(|(n, _)| self.nodes.iter().position(|o| o == n));
+        return colors;
+    }
+
+    /// Registers a decoder that presents the color of every node of a coloring.
+    pub fn register_decoders(&self, decoders: &mut Decoders) {
+        decoders.register("coloring", OneHot::new("node"));
+    }
+}
+
+/// Adds clauses stating that exactly one of the given symbols holds.
+fn exactly_one(cnf: &mut Cnf, atoms: &[String]) {
+    let all: Vec<&str> = atoms.iter().map(String::as_str).collect();
+    cnf.insert(Disj::of_slices(&all, &[]).unwrap());
+
+    for i in 0..atoms.len() {
+        for j in (i + 1)..atoms.len() {
+            cnf.insert(Disj::of_slices(&[], &[&atoms[i], &atoms[j]]).unwrap());
+        }
+    }
+}
+
+/// Writes a [Cnf] in the syntax of rustyproof: one clause per line, so that every line can
+/// be entered as an axiom, in the order of [Cnf::sorted].
+fn write_clauses(cnf: &Cnf) -> String {
+    return cnf.sorted().iter().map(|d| format!("{d}\n")).collect();
+}
+
+/// Runs the `encode` command: `encode sudoku <file>` or `encode coloring <graph> <k>`, followed
+/// by `--dimacs` to write DIMACS instead of rustyproof syntax, or `--solve` to solve the problem
+/// and print the decoded solution.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let usage = "Usage: encode sudoku <file> [--dimacs | --solve]\n       encode coloring <graph> <k> [--dimacs | --solve]";
+
+    let (problem, rest) = args.split_first().ok_or(usage)?;
+    let (file, rest) = rest.split_first().ok_or(usage)?;
+    let text = fs::read_to_string(file).map_err(|e| format!("Cannot read {file}: {e}"))?;
+
+    let mut decoders = Decoders::new();
+    let cnf = match problem.as_str() {
+        "sudoku" => {
+            let sudoku = Sudoku::parse(&text)?;
+            sudoku.register_decoders(&mut decoders);
+            sudoku.encode()
+        },
+        "coloring" => {
+            let graph = Graph::parse(&text)?;
+            let k = rest.first()
+                .and_then(|k| k.parse::<usize>().ok())
+                .ok_or("Expected the number of colors")?;
+
+            graph.register_decoders(&mut decoders);
+            graph.encode_coloring(k)
+        },
+        _ => return Err(String::from(usage)),
+    };
+
+    if rest.iter().any(|a| a == "--dimacs") {
+        print!("{}", dimacs::write(&cnf));
+    } else if rest.iter().any(|a| a == "--solve") {
+        let mut solver = Solver::new();
+        solver.add_cnf(&cnf);
+
+        match solver.solve() {
+            Some(model) => {
+                for (_, answer) in decoders.decode(&model) {
+                    print!("{answer}");
+                }
+            },
+            None => println!("No solution"),
+        }
+    } else {
+        print!("{}", write_clauses(&cnf));
+    }
+
+    return Ok(());
+}
@@ -0,0 +1,161 @@
+//! The C API of rustyproof, for C and C++ applications that embed the prover. Its header,
+//! `include/rustyproof.h`, is generated from this module by cbindgen.
+//!
+//! Statements and knowledge bases are opaque handles, created by [rp_parse] and [rp_kb_new],
+//! which the application frees with [rp_stmt_free] and [rp_kb_free]. Strings returned by the
+//! API are freed with [rp_string_free].
+//!
+//! A panic never unwinds into the application: a function that panics returns null, or -2
+//! for functions that return a number.
+
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::kb::KnowledgeBase;
+use crate::parser::parse_stmt;
+use crate::stmt::Stmt;
+
+/// A statement, created by [rp_parse].
+pub struct RpStmt(Stmt);
+
+/// A knowledge base, created by [rp_kb_new].
+pub struct RpKnowledgeBase(KnowledgeBase);
+
+/// Returns a string as a C string that the application frees with [rp_string_free]. Zero
+/// bytes, which C strings cannot hold, are left out.
+fn c_string(s: &str) -> *mut c_char {
+    return CString::new(s.replace('\0', "")).unwrap().into_raw();
+}
+
+/// Runs a function of the API, and returns its result, or the message of the panic if it
+/// panicked, since unwinding into C is undefined behavior.
+fn catch<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    return panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let msg = payload.downcast_ref::<&str>().map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("unknown cause"));
+        format!("Internal error: {msg}")
+    });
+}
+
+/// Parses an expression in the input syntax of the REPL, given as a C string in UTF-8.
+/// Returns null if the expression is not valid, or if parsing panicked, in which case the error
+/// message is stored in `error`, unless it is null.
+///
+/// # Safety
+///
+/// `input` must be a valid C string, and `error` must be null or point to memory that a
+/// pointer can be written to.
+#[no_mangle]
+pub unsafe extern "C" fn rp_parse(input: *const c_char, error: *mut *mut c_char) -> *mut RpStmt {
+    let result = if input.is_null() {
+        Err(String::from("Expected expression, found null"))
+    } else {
+        match CStr::from_ptr(input).to_str() {
+            Ok(input) => catch(|| parse_stmt(input).map_err(|e| e.to_string())).and_then(|r| r),
+            Err(_) => Err(String::from("Expected expression in UTF-8")),
+        }
+    };
+
+    return match result {
+        Ok(stmt) => Box::into_raw(Box::new(RpStmt(stmt))),
+        Err(msg) => {
+            if !error.is_null() {
+                *error = c_string(&msg);
+            }
+            ptr::null_mut()
+        },
+    };
+}
+
+/// Returns a statement as rustyproof shows it, as a C string that the application frees with
+/// [rp_string_free]. Returns null if `stmt` is null, or if showing it panicked.
+///
+/// # Safety
+///
+/// `stmt` must be null or a statement returned by [rp_parse] that is not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rp_stmt_to_string(stmt: *const RpStmt) -> *mut c_char {
+    let Some(stmt) = stmt.as_ref() else {
+        return ptr::null_mut();
+    };
+    return catch(|| c_string(&stmt.0.to_string())).unwrap_or(ptr::null_mut());
+}
+
+/// Frees a statement. Does nothing if `stmt` is null.
+///
+/// # Safety
+///
+/// `stmt` must be null or a statement returned by [rp_parse] that is not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rp_stmt_free(stmt: *mut RpStmt) {
+    if !stmt.is_null() {
+        let _ = catch(|| drop(Box::from_raw(stmt)));
+    }
+}
+
+/// Creates a knowledge base without axioms. Returns null if that panicked.
+#[no_mangle]
+pub extern "C" fn rp_kb_new() -> *mut RpKnowledgeBase {
+    return catch(|| Box::into_raw(Box::new(RpKnowledgeBase(KnowledgeBase::new())))).unwrap_or(ptr::null_mut());
+}
+
+/// Frees a knowledge base. Does nothing if `kb` is null.
+///
+/// # Safety
+///
+/// `kb` must be null or a knowledge base returned by [rp_kb_new] that is not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rp_kb_free(kb: *mut RpKnowledgeBase) {
+    if !kb.is_null() {
+        let _ = catch(|| drop(Box::from_raw(kb)));
+    }
+}
+
+/// Asserts a statement as an axiom of a knowledge base. The statement is copied, so the
+/// application still frees it. Returns the number of axioms of the knowledge base after, -1 if
+/// `kb` or `stmt` is null, or -2 if the statement cannot be converted to clauses, or if
+/// asserting panicked.
+///
+/// # Safety
+///
+/// `kb` must be null or a knowledge base returned by [rp_kb_new], and `stmt` must be null or a
+/// statement returned by [rp_parse], that are not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rp_kb_assert(kb: *mut RpKnowledgeBase, stmt: *const RpStmt) -> c_int {
+    let (Some(kb), Some(stmt)) = (kb.as_mut(), stmt.as_ref()) else {
+        return -1;
+    };
+    return catch(|| match kb.0.assert(stmt.0.clone()) {
+        Ok(_) => c_int::try_from(kb.0.axioms().len()).unwrap_or(c_int::MAX),
+        Err(_) => -2,
+    }).unwrap_or(-2);
+}
+
+/// Answers whether a statement follows from the axioms of a knowledge base: returns 1 if it
+/// does, 0 if it does not, -1 if `kb` or `stmt` is null, and -2 if answering panicked.
+///
+/// # Safety
+///
+/// `kb` must be null or a knowledge base returned by [rp_kb_new], and `stmt` must be null or a
+/// statement returned by [rp_parse], that are not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rp_kb_query(kb: *const RpKnowledgeBase, stmt: *const RpStmt) -> c_int {
+    let (Some(kb), Some(stmt)) = (kb.as_ref(), stmt.as_ref()) else {
+        return -1;
+    };
+    return catch(|| c_int::from(kb.0.entails(&stmt.0))).unwrap_or(-2);
+}
+
+/// Frees a string returned by the API. Does nothing if `s` is null.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by the API that is not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rp_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        let _ = catch(|| drop(CString::from_raw(s)));
+    }
+}
@@ -0,0 +1,527 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+
+/// A first-order term.
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub enum Term {
+    /// A variable, bound by a quantifier.
+    Var(String),
+
+    /// A function applied to a list of arguments. A function without arguments is a constant.
+    Func(String, Vec<Term>)
+}
+
+/// A first-order formula.
+#[derive(PartialEq, Eq, Clone)]
+pub enum Formula {
+    /// The contradictory formula, i.e., "false".
+    Cont,
+
+    /// The tautological formula, i.e., "true".
+    Taut,
+
+    /// A predicate applied to a list of terms. A predicate without arguments is a
+    /// propositional symbol.
+    Pred(String, Vec<Term>),
+
+    /// The inverse of a formula.
+    Not(Box<Formula>),
+
+    /// The conjunction of two formulas.
+    And(Box<Formula>, Box<Formula>),
+
+    /// The disjunction of two formulas.
+    Or(Box<Formula>, Box<Formula>),
+
+    /// The implication of one formula from another.
+    Implies(Box<Formula>, Box<Formula>),
+
+    /// The equivalence (bi-implication) of two formulas.
+    Equiv(Box<Formula>, Box<Formula>),
+
+    /// A formula that holds for every value of the variable.
+    Forall(String, Box<Formula>),
+
+    /// A formula that holds for some value of the variable.
+    Exists(String, Box<Formula>)
+}
+
+/// A first-order literal: a predicate applied to terms, either inverted or not.
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct Literal {
+    pub positive: bool,
+    pub pred: String,
+    pub args: Vec<Term>
+}
+
+/// A first-order clause: a disjunction of literals, where all variables are implicitly
+/// universally quantified. An empty clause is a contradiction.
+#[derive(PartialEq, Eq, Clone)]
+pub struct Clause {
+    pub literals: Vec<Literal>
+}
+
+impl Term {
+    /// Replaces every occurrence of the given variable with the given term.
+    fn substitute(&self, var: &str, term: &Term) -> Term {
+        return match self {
+            Term::Var(v) if v == var => term.clone(),
+            Term::Var(_) => self.clone(),
+            Term::Func(f, args) => Term::Func(
+                f.clone(),
+                args.iter().map(|a| a.substitute(var, term)).collect()
+            ),
+        };
+    }
+
+    /// Collects all function names used in this term, and all variable names if requested.
+    fn names(&self, out: &mut HashSet<String>, vars: bool) {
+        match self {
+            Term::Var(v) => {
+                if vars {
+                    out.insert(v.clone());
+                }
+            },
+            Term::Func(f, args) => {
+                out.insert(f.clone());
+                for a in args.iter() {
+                    a.names(out, vars);
+                }
+            },
+        }
+    }
+}
+
+impl Formula {
+    pub fn taut() -> Formula {
+        return Formula::Taut;
+    }
+
+    pub fn cont() -> Formula {
+        return Formula::Cont;
+    }
+
+    pub fn pred(name: &str, args: Vec<Term>) -> Formula {
+        return Formula::Pred(name.to_string(), args);
+    }
+
+    pub fn not(self) -> Formula {
+        return Formula::Not(Box::new(self));
+    }
+
+    pub fn and(self, e: Formula) -> Formula {
+        return Formula::And(Box::new(self), Box::new(e));
+    }
+
+    pub fn or(self, e: Formula) -> Formula {
+        return Formula::Or(Box::new(self), Box::new(e));
+    }
+
+    pub fn implies(self, e: Formula) -> Formula {
+        return Formula::Implies(Box::new(self), Box::new(e));
+    }
+
+    pub fn equiv(self, e: Formula) -> Formula {
+        return Formula::Equiv(Box::new(self), Box::new(e));
+    }
+
+    pub fn forall(var: &str, e: Formula) -> Formula {
+        return Formula::Forall(var.to_string(), Box::new(e));
+    }
+
+    pub fn exists(var: &str, e: Formula) -> Formula {
+        return Formula::Exists(var.to_string(), Box::new(e));
+    }
+
+    /// Extrapolation expands implications and equivalences to basic conjunctions and disjunctions.
+    /// A resulting formula does not have any implications or equivalences.
+    fn extrapolate(self) -> Self {
+        return match self {
+            Formula::Not(o) => Self::not((*o).extrapolate()),
+            Formula::And(l, r) => (*l).extrapolate().and((*r).extrapolate()),
+            Formula::Or(l, r) => (*l).extrapolate().or((*r).extrapolate()),
+            Formula::Implies(l, r) => Self::not((*l).extrapolate()).or((*r).extrapolate()),
+            Formula::Equiv(l, r) => Self::and(
+                Self::not((*l).clone().extrapolate()).or((*r).clone().extrapolate()),
+                Self::not((*r).extrapolate()).or((*l).extrapolate())
+            ),
+            Formula::Forall(v, o) => Self::forall(&v, (*o).extrapolate()),
+            Formula::Exists(v, o) => Self::exists(&v, (*o).extrapolate()),
+            f => f,
+        };
+    }
+
+    /// Pushes negations inwards, up to the predicates. Negated quantifiers are dualized, i.e.
+    /// `!forall x. P(x)` becomes `exists x. !P(x)`. This can only happen after [Self::extrapolate].
+    fn nnf_pos(self) -> Self {
+        return match self {
+            Formula::Not(o) => (*o).nnf_neg(),
+            Formula::And(l, r) => (*l).nnf_pos().and((*r).nnf_pos()),
+            Formula::Or(l, r) => (*l).nnf_pos().or((*r).nnf_pos()),
+            Formula::Forall(v, o) => Self::forall(&v, (*o).nnf_pos()),
+            Formula::Exists(v, o) => Self::exists(&v, (*o).nnf_pos()),
+            Formula::Pred(_, _) | Formula::Taut | Formula::Cont => self,
+            _ => panic!("Must extrapolate implications before NNF"),
+        };
+    }
+
+    /// Negates this formula by pushing negations inwards. Used with [Self::nnf_pos].
+    fn nnf_neg(self) -> Self {
+        return match self {
+            Formula::Not(o) => (*o).nnf_pos(),
+            Formula::And(l, r) => (*l).nnf_neg().or((*r).nnf_neg()),
+            Formula::Or(l, r) => (*l).nnf_neg().and((*r).nnf_neg()),
+            Formula::Forall(v, o) => Self::exists(&v, (*o).nnf_neg()),
+            Formula::Exists(v, o) => Self::forall(&v, (*o).nnf_neg()),
+            Formula::Pred(_, _) => Self::not(self),
+            Formula::Taut => Formula::Cont,
+            Formula::Cont => Formula::Taut,
+            _ => panic!("Must extrapolate implications before NNF"),
+        };
+    }
+
+    /// Collects all predicate and function names used in this formula, and all variable
+    /// names if requested.
+    fn names(&self, out: &mut HashSet<String>, vars: bool) {
+        match self {
+            Formula::Cont | Formula::Taut => {},
+            Formula::Pred(p, args) => {
+                out.insert(p.clone());
+                for a in args.iter() {
+                    a.names(out, vars);
+                }
+            },
+            Formula::Not(o) => o.names(out, vars),
+            Formula::And(l, r) | Formula::Or(l, r) | Formula::Implies(l, r) | Formula::Equiv(l, r) => {
+                l.names(out, vars);
+                r.names(out, vars);
+            },
+            Formula::Forall(v, o) | Formula::Exists(v, o) => {
+                if vars {
+                    out.insert(v.clone());
+                }
+                o.names(out, vars);
+            },
+        }
+    }
+
+    /// Replaces every free occurrence of the given variable with the given term.
+    fn substitute(self, var: &str, term: &Term) -> Self {
+        return match self {
+            Formula::Pred(p, args) => Formula::Pred(p, args.iter().map(|a| a.substitute(var, term)).collect()),
+            Formula::Not(o) => Self::not((*o).substitute(var, term)),
+            Formula::And(l, r) => (*l).substitute(var, term).and((*r).substitute(var, term)),
+            Formula::Or(l, r) => (*l).substitute(var, term).or((*r).substitute(var, term)),
+            Formula::Implies(l, r) => (*l).substitute(var, term).implies((*r).substitute(var, term)),
+            Formula::Equiv(l, r) => (*l).substitute(var, term).equiv((*r).substitute(var, term)),
+            Formula::Forall(v, o) if v != var => Self::forall(&v, (*o).substitute(var, term)),
+            Formula::Exists(v, o) if v != var => Self::exists(&v, (*o).substitute(var, term)),
+            f => f,
+        };
+    }
+
+    /// Renames all bound variables such that every quantifier binds a distinct variable, which
+    /// does not clash with any other name in the formula.
+    fn rename_apart(self, used: &mut HashSet<String>) -> Self {
+        return match self {
+            Formula::Not(o) => Self::not((*o).rename_apart(used)),
+            Formula::And(l, r) => (*l).rename_apart(used).and((*r).rename_apart(used)),
+            Formula::Or(l, r) => (*l).rename_apart(used).or((*r).rename_apart(used)),
+            Formula::Forall(v, o) => {
+                let n = fresh(&v, used);
+                Self::forall(&n, (*o).substitute(&v, &Term::Var(n.clone())).rename_apart(used))
+            },
+            Formula::Exists(v, o) => {
+                let n = fresh(&v, used);
+                Self::exists(&n, (*o).substitute(&v, &Term::Var(n.clone())).rename_apart(used))
+            },
+            f => f,
+        };
+    }
+
+    /// Removes all quantifiers from this formula and adds them to the given prefix, in
+    /// order of nesting. Returns the remaining quantifier-free matrix. This is only valid
+    /// after [Self::nnf_pos] and [Self::rename_apart].
+    fn pull_quantifiers(self, prefix: &mut Vec<(bool, String)>) -> Self {
+        return match self {
+            Formula::And(l, r) => {
+                let l = (*l).pull_quantifiers(prefix);
+                l.and((*r).pull_quantifiers(prefix))
+            },
+            Formula::Or(l, r) => {
+                let l = (*l).pull_quantifiers(prefix);
+                l.or((*r).pull_quantifiers(prefix))
+            },
+            Formula::Forall(v, o) => {
+                prefix.push((true, v));
+                (*o).pull_quantifiers(prefix)
+            },
+            Formula::Exists(v, o) => {
+                prefix.push((false, v));
+                (*o).pull_quantifiers(prefix)
+            },
+            f => f,
+        };
+    }
+
+    /// Splits a prenex formula into its quantifier prefix and its matrix.
+    fn split_prefix(self) -> (Vec<(bool, String)>, Self) {
+        let mut prefix = Vec::new();
+        let mut e = self;
+        loop {
+            e = match e {
+                Formula::Forall(v, o) => {
+                    prefix.push((true, v));
+                    *o
+                },
+                Formula::Exists(v, o) => {
+                    prefix.push((false, v));
+                    *o
+                },
+                f => return (prefix, f),
+            };
+        }
+    }
+
+    /// Wraps a matrix in the given quantifier prefix.
+    fn join_prefix(prefix: Vec<(bool, String)>, matrix: Self) -> Self {
+        return prefix.into_iter().rev().fold(matrix, |e, (universal, v)| {
+            if universal {
+                Self::forall(&v, e)
+            } else {
+                Self::exists(&v, e)
+            }
+        });
+    }
+
+    /// Translates this formula to prenex normal form: all quantifiers are moved to the front
+    /// of the formula, and the remaining quantifier-free matrix is in negation normal form.
+    /// Bound variables are renamed apart, so that no two quantifiers bind the same variable.
+    pub fn prenex(&self) -> Self {
+        // Variables may keep their name when it is not bound already and not used as a function
+        let mut used = HashSet::new();
+        self.names(&mut used, false);
+
+        let e = self.clone().extrapolate().nnf_pos().rename_apart(&mut used);
+
+        let mut prefix = Vec::new();
+        let matrix = e.pull_quantifiers(&mut prefix);
+        return Self::join_prefix(prefix, matrix);
+    }
+
+    /// Translates this formula to Skolem normal form: a prenex formula with only universal
+    /// quantifiers. Every existentially quantified variable is replaced by a fresh Skolem
+    /// function, applied to the universally quantified variables that precede it.
+    ///
+    /// For example, `forall x. exists y. P(x, y)` becomes `forall x. P(x, sk(x))`.
+    /// The result is equisatisfiable to the original formula, but not equivalent.
+    pub fn skolemize(&self) -> Self {
+        let prenex = self.prenex();
+
+        let mut used = HashSet::new();
+        prenex.names(&mut used, true);
+
+        let (prefix, mut matrix) = prenex.split_prefix();
+        let mut universal = Vec::new();
+
+        for (forall, v) in prefix.into_iter() {
+            if forall {
+                universal.push((true, v));
+            } else {
+                let args = universal.iter().map(|(_, u)| Term::Var(u.clone())).collect();
+                let sk = Term::Func(fresh("sk", &mut used), args);
+                matrix = matrix.substitute(&v, &sk);
+            }
+        }
+
+        return Self::join_prefix(universal, matrix);
+    }
+
+    /// Converts a quantifier-free formula in negation normal form to a list of clauses.
+    fn matrix_clauses(self) -> Vec<Vec<Literal>> {
+        return match self {
+            Formula::Taut => vec![],
+            Formula::Cont => vec![vec![]],
+            Formula::Pred(p, args) => vec![vec![Literal { positive: true, pred: p, args }]],
+            Formula::Not(o) => match *o {
+                Formula::Pred(p, args) => vec![vec![Literal { positive: false, pred: p, args }]],
+                _ => panic!("Not in NNF"),
+            },
+            Formula::And(l, r) => {
+                let mut out = (*l).matrix_clauses();
+                out.extend((*r).matrix_clauses());
+                out
+            },
+            Formula::Or(l, r) => {
+                let l = (*l).matrix_clauses();
+                let r = (*r).matrix_clauses();
+
+                let mut out = Vec::new();
+                for lc in l.iter() {
+                    for rc in r.iter() {
+                        let mut c = lc.clone();
+                        c.extend(rc.iter().cloned());
+                        out.push(c);
+                    }
+                }
+                out
+            },
+            _ => panic!("Not quantifier-free"),
+        };
+    }
+
+    /// Clausifies this formula: it is Skolemized, the universal quantifiers are dropped, and
+    /// the matrix is translated to conjunctive normal form. Clauses that contain a literal
+    /// and its inverse are tautologies and are left out.
+    pub fn clauses(&self) -> Vec<Clause> {
+        let (_, matrix) = self.skolemize().split_prefix();
+
+        let mut out: Vec<Clause> = Vec::new();
+        for lits in matrix.matrix_clauses().into_iter() {
+            let mut clause = Clause { literals: Vec::new() };
+            for lit in lits.into_iter() {
+                if !clause.literals.contains(&lit) {
+                    clause.literals.push(lit);
+                }
+            }
+
+            if !clause.is_tautology() && !out.contains(&clause) {
+                out.push(clause);
+            }
+        }
+
+        return out;
+    }
+}
+
+impl Literal {
+    /// Tests whether this literal is the inverse of the other.
+    pub fn is_inverse(&self, other: &Literal) -> bool {
+        return self.positive != other.positive && self.pred == other.pred && self.args == other.args;
+    }
+}
+
+impl Clause {
+    /// Tests whether this clause contains a literal and its inverse.
+    pub fn is_tautology(&self) -> bool {
+        return self.literals.iter().any(|l| {
+            self.literals.iter().any(|o| l.is_inverse(o))
+        });
+    }
+
+    /// Tests whether this clause has no literals, making it a contradiction.
+    pub fn is_contradiction(&self) -> bool {
+        return self.literals.is_empty();
+    }
+}
+
+/// Generates a name based on the given one, which is not in the set of used names, and
+/// adds it to that set.
+fn fresh(base: &str, used: &mut HashSet<String>) -> String {
+    let mut name = base.to_string();
+    let mut i = 1;
+    while used.contains(&name) {
+        name = format!("{base}{i}");
+        i += 1;
+    }
+
+    used.insert(name.clone());
+    return name;
+}
+
+/// Writes a list of arguments: `(a, b, c)`, or nothing if the list is empty.
+fn write_args(f: &mut std::fmt::Formatter<'_>, args: &[Term]) -> std::fmt::Result {
+    if args.is_empty() {
+        return Ok(());
+    }
+
+    write!(f, "(")?;
+    for (i, a) in args.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{a}")?;
+    }
+    write!(f, ")")
+}
+
+impl Display for Term {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Term::Var(v) => write!(f, "{v}"),
+            Term::Func(name, args) => {
+                write!(f, "{name}")?;
+                write_args(f, args)
+            },
+        };
+    }
+}
+
+impl Formula {
+    /// Writes an operand of a binary operator. Quantified operands are wrapped in parentheses,
+    /// since their body would otherwise extend over the rest of the formula.
+    fn fmt_operand(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Formula::Forall(_, _) | Formula::Exists(_, _) => write!(f, "({self})"),
+            _ => write!(f, "{self}"),
+        };
+    }
+
+    fn fmt_binary(f: &mut std::fmt::Formatter<'_>, l: &Formula, op: &str, r: &Formula) -> std::fmt::Result {
+        write!(f, "(")?;
+        l.fmt_operand(f)?;
+        write!(f, " {op} ")?;
+        r.fmt_operand(f)?;
+        write!(f, ")")
+    }
+}
+
+impl Display for Formula {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Formula::Cont => write!(f, "~"),
+            Formula::Taut => write!(f, "*"),
+            Formula::Pred(p, args) => {
+                write!(f, "{p}")?;
+                write_args(f, args)
+            },
+            Formula::Not(o) => {
+                write!(f, "!")?;
+                o.fmt_operand(f)
+            },
+            Formula::And(l, r) => Self::fmt_binary(f, l, "&", r),
+            Formula::Or(l, r) => Self::fmt_binary(f, l, "|", r),
+            Formula::Implies(l, r) => Self::fmt_binary(f, l, "->", r),
+            Formula::Equiv(l, r) => Self::fmt_binary(f, l, "<->", r),
+            Formula::Forall(v, o) => write!(f, "forall {v}. {o}"),
+            Formula::Exists(v, o) => write!(f, "exists {v}. {o}"),
+        };
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.positive {
+            write!(f, "!")?;
+        }
+        write!(f, "{}", self.pred)?;
+        write_args(f, &self.args)
+    }
+}
+
+impl Display for Clause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_contradiction() {
+            return write!(f, "~");
+        }
+
+        for (i, l) in self.literals.iter().enumerate() {
+            if i > 0 {
+                write!(f, " | ")?;
+            }
+            write!(f, "{l}")?;
+        }
+
+        Ok(())
+    }
+}
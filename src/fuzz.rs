@@ -0,0 +1,66 @@
+use crate::parser::parse_stmt;
+use crate::solver::Solver;
+use crate::stmt::Stmt;
+
+/// The maximum length of an input that [fuzz_roundtrip] checks. Longer inputs are skipped, as
+/// their normal forms can grow exponentially.
+pub const MAX_INPUT: usize = 256;
+
+/// Runs an arbitrary input through the pipeline of parsing, conversion to normal forms and
+/// solving, and asserts that every step keeps its promises. Meant as the body of a fuzz
+/// target, like one of cargo-fuzz:
+///
+/// ```ignore
+/// fuzz_target!(|data: &[u8]| rustyproof::fuzz::fuzz_roundtrip(data));
+/// ```
+///
+/// Inputs that are not valid UTF-8, that are longer than [MAX_INPUT] bytes, or that do not
+/// parse are ignored. For other inputs, it asserts that:
+/// - the statement parses back from how it is displayed, into a statement that is displayed
+///   the same way;
+/// - the conversions to NNF, CNF and DNF succeed;
+/// - a model found by the solver satisfies all clauses, as well as the statement itself;
+/// - when no model is found, the DNF has no terms.
+pub fn fuzz_roundtrip(input: &[u8]) {
+    if input.len() > MAX_INPUT {
+        return;
+    }
+    let text = match std::str::from_utf8(input) {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+    let stmt = match parse_stmt(text) {
+        Ok(stmt) => stmt,
+        Err(e) => {
+            assert!(e.position <= text.chars().count(), "Error position {} is out of the input", e.position);
+            return;
+        }
+    };
+
+    let shown = stmt.to_string();
+    let reparsed = parse_stmt(&shown).unwrap_or_else(|e| panic!("{shown} does not parse back: {e}"));
+    assert!(reparsed.to_string() == shown, "{shown} parses back into {reparsed}");
+
+    let nnf = stmt.try_nnf().unwrap_or_else(|e| panic!("NNF of {stmt} failed: {e}"));
+    let cnf = stmt.try_cnf().unwrap_or_else(|e| panic!("CNF of {stmt} failed: {e}"));
+    let dnf = stmt.try_dnf().unwrap_or_else(|e| panic!("DNF of {stmt} failed: {e}"));
+
+    let mut solver = Solver::new();
+    solver.add_cnf(&cnf);
+
+    match solver.solve() {
+        Some(model) => {
+            for disj in cnf.terms.iter() {
+                assert!(model.satisfies(disj), "Model of {stmt} does not satisfy ({disj})");
+            }
+
+            // Symbols that the model does not assign do not occur in the clauses, so any value
+            // satisfies the statement. The NNF of a statement without symbols is a constant.
+            let value = stmt.symbols().iter().fold(nnf, |s, sym| s.fix(sym, model.get(sym).unwrap_or(false)));
+            assert!(value == Stmt::Taut, "Model of {stmt} does not satisfy it");
+        },
+        None => {
+            assert!(dnf.terms.is_empty(), "{stmt} is unsatisfiable, but has DNF {dnf}");
+        },
+    }
+}
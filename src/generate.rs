@@ -0,0 +1,123 @@
+use crate::cnf::{Cnf, Disj};
+use crate::literal::Literal;
+use crate::stmt::Stmt;
+
+/// A small pseudo-random number generator (xorshift64*), so that a generated problem only
+/// depends on its parameters and seed, and is the same on every platform.
+struct Rng {
+    state: u64
+}
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        // The state must never be zero
+        return Rng { state: seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1 };
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        return self.state.wrapping_mul(0x2545_F491_4F6C_DD1D);
+    }
+
+    /// Returns a number from `0` up to, but not including, `n`.
+    fn below(&mut self, n: usize) -> usize {
+        return (self.next() % n as u64) as usize;
+    }
+
+    fn coin(&mut self) -> bool {
+        return self.next() & 1 == 1;
+    }
+}
+
+/// Returns the name of the symbol with the given index. Symbols are named `x1`, `x2`, and so
+/// on, like variables read from DIMACS without names.
+fn symbol(i: usize) -> String {
+    return format!("x{}", i + 1);
+}
+
+/// Generates random clauses over the given number of symbols, each of `width` distinct
+/// symbols with random polarities. The same clause may be generated more than once, so the
+/// clauses also serve to measure how duplicates are left out when they are inserted into a
+/// [Cnf].
+///
+/// Panics if `width` exceeds the number of symbols.
+pub fn random_clauses(symbols: usize, clauses: usize, width: usize, seed: u64) -> Vec<Disj> {
+    assert!(width <= symbols, "Cannot pick {width} distinct symbols out of {symbols}");
+
+    let mut rng = Rng::new(seed);
+    let mut out = Vec::with_capacity(clauses);
+
+    for _ in 0..clauses {
+        let mut picked: Vec<usize> = Vec::with_capacity(width);
+        while picked.len() < width {
+            let s = rng.below(symbols);
+            if !picked.contains(&s) {
+                picked.push(s);
+            }
+        }
+
+        let lits = picked.into_iter().map(|s| Literal::new(&symbol(s), rng.coin()));
+        out.push(Disj::of_literals(lits).unwrap());
+    }
+
+    return out;
+}
+
+/// Generates a random k-SAT problem: `clauses` random clauses of `width` symbols each, see
+/// [random_clauses]. For 3-SAT, problems with about 4.26 times as many clauses as symbols are
+/// the hardest to solve, as about half of them are satisfiable.
+pub fn random_ksat(symbols: usize, clauses: usize, width: usize, seed: u64) -> Cnf {
+    return Cnf::of_vec(&random_clauses(symbols, clauses, width, seed));
+}
+
+/// Generates the pigeonhole problem: `holes + 1` pigeons must each sit in one of `holes`
+/// holes, and no two pigeons share a hole. The problem is unsatisfiable, and notoriously hard
+/// to refute by resolution. The symbol `p<i>.<j>` states that pigeon `i` sits in hole `j`.
+pub fn pigeonhole(holes: usize) -> Cnf {
+    let mut cnf = Cnf::new();
+    let sits = |i: usize, j: usize| format!("p{i}.{j}");
+
+    for i in 0..=holes {
+        let hole: Vec<String> = (0..holes).map(|j| sits(i, j)).collect();
+        let hole: Vec<&str> = hole.iter().map(String::as_str).collect();
+        cnf.insert(Disj::of_slices(&hole, &[]).unwrap());
+    }
+
+    for j in 0..holes {
+        for i in 0..=holes {
+            for k in (i + 1)..=holes {
+                cnf.insert(Disj::of_slices(&[], &[&sits(i, j), &sits(k, j)]).unwrap());
+            }
+        }
+    }
+
+    return cnf;
+}
+
+/// Generates a random statement over the given number of symbols, as a tree of operators of
+/// the given depth whose leaves are symbols, possibly inverted. Every operator is one of `&`,
+/// `|`, `->`, `<->` and `^`, so the statement is meant for measuring the conversion to normal
+/// forms, which grows quickly with the depth.
+pub fn random_stmt(symbols: usize, depth: usize, seed: u64) -> Stmt {
+    return random_stmt_with(&mut Rng::new(seed), symbols, depth);
+}
+
+fn random_stmt_with(rng: &mut Rng, symbols: usize, depth: usize) -> Stmt {
+    if depth == 0 {
+        let leaf = Stmt::symbol(&symbol(rng.below(symbols)));
+        return if rng.coin() { leaf } else { leaf.not() };
+    }
+
+    let l = random_stmt_with(rng, symbols, depth - 1);
+    let r = random_stmt_with(rng, symbols, depth - 1);
+
+    return match rng.below(5) {
+        0 => l.and(r),
+        1 => l.or(r),
+        2 => l.implies(r),
+        3 => l.equiv(r),
+        _ => l.xor(r),
+    };
+}
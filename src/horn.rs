@@ -0,0 +1,140 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::cnf::Cnf;
+
+/// The outcome of answering a goal by backward chaining.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Sld {
+    /// The goal follows from the knowledge base.
+    Proved,
+
+    /// The goal does not follow from the knowledge base.
+    NotProved,
+
+    /// No proof was found within the depth limit, but there may be a deeper one.
+    DepthLimit
+}
+
+/// A set of definite clauses, indexed by their head. A definite clause `H | !B1 | !B2` is
+/// read as the rule `H <- B1 & B2`, and a fact `H` is a rule without body.
+pub struct Rules {
+    rules: HashMap<String, Vec<Vec<String>>>
+}
+
+impl Rules {
+    /// Collects the definite clauses of the given [Cnf] as rules. Clauses without a positive
+    /// symbol do not help proving goals and are left out.
+    pub fn new(cnf: &Cnf) -> Rules {
+        let mut rules: HashMap<String, Vec<Vec<String>>> = HashMap::new();
+
+        for disj in cnf.terms.iter() {
+            if disj.pos().count() != 1 {
+                continue;
+            }
+
+            let head = disj.pos().next().unwrap().to_string();
+            let body = Vec::from_iter(disj.neg().map(str::to_string));
+
+            rules.entry(head).or_default().push(body);
+        }
+
+        for bodies in rules.values_mut() {
+            // Try facts and short rules first
+            bodies.sort_by_key(Vec::len);
+        }
+
+        return Rules { rules };
+    }
+
+    /// Answers whether all given goals follow from the rules, by SLD resolution: the first
+    /// goal is replaced by the body of a rule with that goal as head, until no goals remain.
+    /// Every alternative rule is tried when the remaining goals cannot be proved.
+    ///
+    /// Rules can be recursive (e.g. `P <- Q` and `Q <- P`), so a branch of the search is
+    /// given up after `depth` rule applications.
+    pub fn prove(&self, goals: &[String], depth: usize) -> Sld {
+        let goals: Vec<&str> = goals.iter().map(String::as_str).collect();
+        return self.prove_goals(&goals, depth);
+    }
+
+    /// Derives all symbols that follow from the rules by forward chaining: starting from the
+    /// facts, every rule whose body has been derived completely adds its head, until no more
+    /// symbols can be derived. Every rule is considered only once per symbol in its body, so
+    /// this takes time linear in the size of the rules.
+    pub fn facts(&self) -> HashSet<String> {
+        let mut facts = HashSet::new();
+        let mut agenda: Vec<&str> = Vec::new();
+
+        // For every rule: its head and the number of body symbols not derived yet
+        let mut rules: Vec<(&str, usize)> = Vec::new();
+        let mut waiting: HashMap<&str, Vec<usize>> = HashMap::new();
+
+        for (head, bodies) in self.rules.iter() {
+            for body in bodies.iter() {
+                if body.is_empty() {
+                    agenda.push(head);
+                    continue;
+                }
+
+                for s in body.iter() {
+                    waiting.entry(s).or_default().push(rules.len());
+                }
+                rules.push((head, body.len()));
+            }
+        }
+
+        while let Some(s) = agenda.pop() {
+            if !facts.insert(s.to_string()) {
+                continue;
+            }
+
+            for i in waiting.get(s).into_iter().flatten() {
+                rules[*i].1 -= 1;
+                if rules[*i].1 == 0 {
+                    agenda.push(rules[*i].0);
+                }
+            }
+        }
+
+        return facts;
+    }
+
+    fn prove_goals(&self, goals: &[&str], depth: usize) -> Sld {
+        let (goal, rest) = match goals.split_first() {
+            Some(g) => g,
+            None => return Sld::Proved,
+        };
+
+        if depth == 0 {
+            return Sld::DepthLimit;
+        }
+
+        let mut limited = false;
+
+        for body in self.rules.get(*goal).into_iter().flatten() {
+            let mut next: Vec<&str> = body.iter().map(String::as_str).collect();
+            next.extend_from_slice(rest);
+
+            match self.prove_goals(&next, depth - 1) {
+                Sld::Proved => return Sld::Proved,
+                Sld::DepthLimit => limited = true,
+                Sld::NotProved => {},
+            }
+        }
+
+        return if limited {
+            Sld::DepthLimit
+        } else {
+            Sld::NotProved
+        };
+    }
+}
+
+/// Tests whether the given facts violate a clause of the given [Cnf] that has no positive
+/// symbol, like `!P | !Q`, which reads as `(P & Q) -> ~`. For a Horn [Cnf] and the facts
+/// derived from its rules, this means that the [Cnf] is contradictory.
+pub fn contradicts(cnf: &Cnf, facts: &HashSet<String>) -> bool {
+    return cnf.terms.iter().any(|d| {
+        d.literals().iter().all(|l| !l.polarity && facts.contains(l.atom.name()))
+    });
+}
@@ -0,0 +1,1089 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::cnf::{Cnf, Disj, ResolutionStrategy, SaturationConfig, SaturationOutcome, SolveStats};
+use crate::compact;
+use crate::dimacs;
+use crate::literal::Literal;
+use crate::parser::{ParsedStatement, Parser};
+use crate::proof::Proof;
+use crate::smtlib;
+use crate::solver::{Model, Optimum, Queries, Solver};
+use crate::stmt::Stmt;
+
+/// An axiom of a [KnowledgeBase]: a statement that is assumed to be true, along with its
+/// clauses. Axioms may have a name, by which they can be referred to, and tags, by which
+/// groups of axioms can be disabled and enabled.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Axiom {
+    pub name: Option<String>,
+    pub tags: Vec<String>,
+    pub stmt: Stmt,
+    pub clauses: Cnf
+}
+
+impl Axiom {
+    /// Returns the name of this axiom, or the statement itself if it has no name.
+    pub fn label(&self) -> String {
+        return match &self.name {
+            Some(name) => name.clone(),
+            None => self.stmt.to_string(),
+        };
+    }
+}
+
+impl Display for Axiom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for tag in self.tags.iter() {
+            write!(f, "@{tag} ")?;
+        }
+
+        return match &self.name {
+            Some(name) => write!(f, "{name}: {}", self.stmt),
+            None => write!(f, "{}", self.stmt),
+        };
+    }
+}
+
+/// The differences between two knowledge bases, see [KnowledgeBase::diff]. Named axioms are
+/// matched by name, and changed when their statement or tags differ. Other axioms are matched
+/// by their statement and tags, and are either added or removed.
+#[derive(Debug)]
+pub struct Diff {
+    /// The axioms of the new knowledge base that are not in the old one.
+    pub added: Vec<Axiom>,
+
+    /// The axioms of the old knowledge base that are not in the new one.
+    pub removed: Vec<Axiom>,
+
+    /// The named axioms that differ, as they are in the old and the new knowledge base.
+    pub changed: Vec<(Axiom, Axiom)>,
+
+    /// The clauses of the new knowledge base that are not in the old one.
+    pub added_clauses: Cnf,
+
+    /// The clauses of the old knowledge base that are not in the new one.
+    pub removed_clauses: Cnf
+}
+
+impl Diff {
+    /// Tests whether the knowledge bases have the same axioms.
+    pub fn is_empty(&self) -> bool {
+        return self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty();
+    }
+}
+
+/// How two knowledge bases relate by entailment, see [KnowledgeBase::compare]. For strict
+/// differences, a model witnesses the difference.
+#[derive(Debug)]
+pub enum Entailment {
+    /// Both knowledge bases entail each other: they have the same models.
+    Equivalent,
+
+    /// The first knowledge base entails the second, but not the other way around. The model is
+    /// a model of the second knowledge base, but not of the first.
+    Stronger(Model),
+
+    /// The second knowledge base entails the first, but not the other way around. The model is
+    /// a model of the first knowledge base, but not of the second.
+    Weaker(Model),
+
+    /// Neither knowledge base entails the other. The models are a model of the first knowledge
+    /// base that is not a model of the second, and the other way around.
+    Neither(Model, Model)
+}
+
+/// The methods by which [KnowledgeBase::entails_escalating] answers questions, from cheap
+/// but incomplete to expensive but complete.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Method {
+    /// Every clause of the question is a known clause, or subsumed by one.
+    Containment,
+
+    /// Propagating the negation of every clause of the question along with the unit clauses
+    /// of the axioms leads to a conflict.
+    UnitPropagation,
+
+    /// Resolution from the axioms and the negation of a clause of the question derives the
+    /// empty clause, or saturates without it, within the budget.
+    Resolution,
+
+    /// The solver, which always answers.
+    Solver
+}
+
+impl Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Method::Containment => write!(f, "containment"),
+            Method::UnitPropagation => write!(f, "unit propagation"),
+            Method::Resolution => write!(f, "bounded resolution"),
+            Method::Solver => write!(f, "the solver"),
+        };
+    }
+}
+
+/// The formats a knowledge base can be read from, see [KnowledgeBase::read_as].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Format {
+    /// The input syntax of the REPL, one axiom per line, as written by [KnowledgeBase::write].
+    Rustyproof,
+
+    /// The DIMACS CNF format, see [dimacs::read].
+    Dimacs,
+
+    /// SMT-LIB 2 scripts, see [smtlib::read].
+    SmtLib
+}
+
+impl Format {
+    /// Tells the format of the given text: DIMACS CNF by its problem line (see
+    /// [dimacs::is_dimacs]), SMT-LIB 2 by its first command (see [smtlib::is_smtlib]), and
+    /// otherwise the input syntax of the REPL.
+    pub fn detect(text: &str) -> Format {
+        if smtlib::is_smtlib(text) {
+            return Format::SmtLib;
+        }
+        if dimacs::is_dimacs(text) {
+            return Format::Dimacs;
+        }
+        return Format::Rustyproof;
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Format::Rustyproof => write!(f, "rp"),
+            Format::Dimacs => write!(f, "dimacs"),
+            Format::SmtLib => write!(f, "smtlib"),
+        };
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        return match s {
+            "rp" => Ok(Format::Rustyproof),
+            "dimacs" | "cnf" => Ok(Format::Dimacs),
+            "smtlib" | "smt2" => Ok(Format::SmtLib),
+            _ => Err(format!("Expected format rp, dimacs or smtlib, found \"{s}\"")),
+        };
+    }
+}
+
+/// Returns the negation of a clause: a unit clause for the complement of every literal.
+fn negate(disj: &Disj) -> Cnf {
+    return Cnf::of_vec(&Vec::from_iter(disj.literals().iter().map(|l| Disj::of_literals([!l]).unwrap())));
+}
+
+/// Finds a model of the given premises that falsifies one of the clauses of the given
+/// conclusion. Returns `None` if there is no such model, i.e. if the premises entail the
+/// conclusion. Every clause is tried separately, as the negation of a clause is a set of
+/// unit clauses.
+fn counter_model(premises: &Cnf, conclusion: &Cnf) -> Option<Model> {
+    for disj in conclusion.terms.iter() {
+        let mut solver = Solver::new();
+        solver.add_cnf(premises);
+
+        for l in disj.literals().iter() {
+            solver.add_disj(&Disj::of_literals([!l]).unwrap());
+        }
+
+        if let Some(m) = solver.solve() {
+            return Some(m);
+        }
+    }
+
+    return None;
+}
+
+/// A knowledge base: a list of axioms, along with the clauses that follow from them by
+/// resolution. Every axiom keeps track of its own clauses, so that it can be retracted
+/// later on.
+///
+/// Axioms with a disabled tag are kept, but they are left out of the clauses of the
+/// knowledge base until the tag is enabled again. To this end, every tag has a selector: a
+/// symbol `@tag`, which cannot be written in input. Every clause of a tagged axiom is guarded
+/// by the selectors of its tags: given the tag `@t`, the clause `A | B` becomes the clause
+/// `A | B | !@t`, which reads as `@t -> A | B`. The clauses of the knowledge base are found
+/// under the assumption that the selectors of enabled tags are true, and the selectors of
+/// disabled tags are false. Resolvents are computed from the guarded clauses, and carry the
+/// guards of the clauses they follow from, so they stay valid when tags are toggled.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KnowledgeBase {
+    axioms: Vec<Axiom>,
+
+    /// The index of every named axiom, by name.
+    names: HashMap<String, usize>,
+
+    /// The disabled tags.
+    disabled: HashSet<String>,
+
+    /// The symbols that are fixed to a value, see [Self::fix].
+    fixed: BTreeMap<String, bool>,
+
+    /// The guarded clauses of all axioms, along with resolvents that follow from them.
+    guarded: Cnf,
+
+    /// Guarded clauses that are not resolved yet. They are added to [Self::guarded] when
+    /// resolvents are needed, and only resolved with the clauses there, which are already
+    /// resolved with each other, see [Cnf::saturate_with].
+    pending: Cnf,
+
+    /// Whether [Self::guarded] contains all resolvents, or as many as the limits allow.
+    /// Resolvents are only computed when needed, since resolution is expensive.
+    saturated: bool,
+
+    /// The limits on computing resolvents, see [Self::set_limits].
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_limits"))]
+    limits: SaturationConfig,
+
+    /// Whether computing resolvents stopped at one of [Self::limits], so that not all
+    /// resolvents are known.
+    limited: bool,
+
+    /// The clauses of all enabled axioms.
+    clauses: Cnf,
+
+    /// The clauses of all enabled axioms, along with resolvents that follow from them, if
+    /// [Self::saturated].
+    resolved: Cnf,
+
+    /// Unit and binary clauses that are known to follow from the enabled axioms, see
+    /// [Self::learn].
+    lemmas: Cnf,
+
+    /// The soft statements, along with their weights, see [Self::assert_soft].
+    soft: Vec<(Stmt, u64)>,
+
+    /// The states saved by [Self::push], innermost last, which [Self::pop] returns to.
+    #[cfg_attr(feature = "serde", serde(default))]
+    scopes: Vec<KnowledgeBase>,
+
+    /// The work done computing resolvents so far, see [Self::stats].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    stats: SolveStats
+}
+
+/// The room for clauses that may be allocated but unused before a knowledge base compacts
+/// itself after axioms were removed or changed, see [KnowledgeBase::gc].
+const GC_THRESHOLD: usize = 4096;
+
+/// Returns the limits on resolution of a new knowledge base: at most 10000 clauses, and at
+/// most 5 seconds for every time resolvents are computed.
+fn default_limits() -> SaturationConfig {
+    return SaturationConfig {
+        max_clauses: Some(10000),
+        max_time: Some(Duration::from_secs(5)),
+        ..SaturationConfig::default()
+    };
+}
+
+/// Returns the selector of the given tag.
+fn selector(tag: &str) -> String {
+    return format!("@{tag}");
+}
+
+/// Guards the given clauses by the selectors of the given tags.
+fn guard(clauses: &Cnf, tags: &[String]) -> Cnf {
+    let mut out = Cnf::new();
+
+    for disj in clauses.terms.iter() {
+        let guards = tags.iter().map(|t| Literal::neg(&selector(t)));
+        out.insert(Disj::of_literals(disj.literals().iter().cloned().chain(guards)).unwrap());
+    }
+
+    return out;
+}
+
+impl KnowledgeBase {
+    pub fn new() -> KnowledgeBase {
+        return KnowledgeBase {
+            axioms: Vec::new(),
+            names: HashMap::new(),
+            disabled: HashSet::new(),
+            fixed: BTreeMap::new(),
+            guarded: Cnf::new(),
+            pending: Cnf::new(),
+            saturated: true,
+            limits: default_limits(),
+            limited: false,
+            clauses: Cnf::new(),
+            resolved: Cnf::new(),
+            lemmas: Cnf::new(),
+            soft: Vec::new(),
+            scopes: Vec::new(),
+            stats: SolveStats::default()
+        };
+    }
+
+    /// Returns all axioms, in the order they were asserted, including disabled axioms.
+    pub fn axioms(&self) -> &[Axiom] {
+        return &self.axioms;
+    }
+
+    /// Returns the index of the axiom with the given name.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        return self.names.get(name).copied();
+    }
+
+    /// Tests whether the axiom at the given index is enabled, i.e. whether none of its tags
+    /// are disabled.
+    pub fn is_enabled(&self, index: usize) -> bool {
+        return self.axioms[index].tags.iter().all(|t| !self.disabled.contains(t));
+    }
+
+    /// Returns the clauses of all enabled axioms, without any resolvents.
+    pub fn clauses(&self) -> &Cnf {
+        return &self.clauses;
+    }
+
+    /// Tests whether the knowledge base has no axioms.
+    pub fn is_empty(&self) -> bool {
+        return self.axioms.is_empty();
+    }
+
+    /// Adds an axiom to the knowledge base, and returns it. Fails if the axiom cannot be
+    /// converted to clauses, see [Stmt::try_cnf].
+    pub fn assert(&mut self, stmt: Stmt) -> Result<&Axiom, String> {
+        return self.assert_labelled(None, &[], stmt);
+    }
+
+    /// Adds an axiom with the given name and tags to the knowledge base, and returns it. Fails
+    /// if there already is an axiom with the given name, or if the axiom cannot be converted to
+    /// clauses. When one of the tags is disabled, the axiom is disabled right away. The axiom
+    /// is conditioned on the fixed symbols, see [Self::fix].
+    pub fn assert_labelled(&mut self, name: Option<&str>, tags: &[String], stmt: Stmt) -> Result<&Axiom, String> {
+        if let Some(name) = name {
+            if self.names.contains_key(name) {
+                return Err(format!("There already is an axiom named '{name}'"));
+            }
+        }
+
+        let stmt = self.fixed.iter().fold(stmt, |s, (sym, value)| s.fix(sym, *value));
+        let clauses = stmt.try_cnf().map_err(|e| e.to_string())?;
+
+        if let Some(name) = name {
+            self.names.insert(name.to_string(), self.axioms.len());
+        }
+        let axiom = Axiom {
+            name: name.map(String::from),
+            tags: tags.to_vec(),
+            clauses,
+            stmt
+        };
+        self.axioms.push(axiom);
+
+        let axiom = self.axioms.last().unwrap();
+        self.pending.insert_all(&guard(&axiom.clauses, &axiom.tags));
+        self.saturated = false;
+
+        if self.is_enabled(self.axioms.len() - 1) {
+            self.clauses.insert_all(&self.axioms.last().unwrap().clauses);
+        }
+
+        return Ok(self.axioms.last().unwrap());
+    }
+
+    /// Removes the axiom at the given index, and returns it. Resolvents that followed from
+    /// the axiom are discarded: the resolvents of the remaining axioms are computed again
+    /// when needed. To leave out axioms temporarily, tag them and use [Self::disable], which
+    /// does not need resolvents to be computed again.
+    pub fn retract(&mut self, index: usize) -> Option<Axiom> {
+        if index >= self.axioms.len() {
+            return None;
+        }
+
+        let axiom = self.axioms.remove(index);
+        self.rebuild();
+        return Some(axiom);
+    }
+
+    /// Fixes the given symbol to the given value, for good: all axioms are conditioned on the
+    /// value (see [Stmt::fix]), which removes the clauses it satisfies and shrinks the clauses
+    /// it falsifies. Axioms asserted later on are conditioned likewise. The value itself is
+    /// kept as a unit clause, so that it still follows from the knowledge base. Fails if the
+    /// symbol is already fixed to the other value, or if an axiom cannot be converted to
+    /// clauses, in which case nothing changes.
+    pub fn fix(&mut self, symbol: &str, value: bool) -> Result<(), String> {
+        match self.fixed.get(symbol) {
+            Some(v) if *v == value => return Ok(()),
+            Some(v) => return Err(format!("{symbol} is already fixed to {v}")),
+            None => {}
+        }
+
+        // The axioms are only changed when all of them can be converted to clauses
+        let mut fixed = Vec::new();
+        for axiom in self.axioms.iter() {
+            let stmt = axiom.stmt.fix(symbol, value);
+            let clauses = stmt.try_cnf().map_err(|e| e.to_string())?;
+            fixed.push((stmt, clauses));
+        }
+        for (axiom, (stmt, clauses)) in self.axioms.iter_mut().zip(fixed) {
+            axiom.stmt = stmt;
+            axiom.clauses = clauses;
+        }
+        for (stmt, _) in self.soft.iter_mut() {
+            *stmt = stmt.fix(symbol, value);
+        }
+        self.fixed.insert(symbol.to_string(), value);
+
+        self.rebuild();
+        return Ok(());
+    }
+
+    /// Returns the fixed symbols, along with their values, see [Self::fix].
+    pub fn fixed(&self) -> &BTreeMap<String, bool> {
+        return &self.fixed;
+    }
+
+    /// Returns the unit clauses of the fixed symbols.
+    fn units(&self) -> Cnf {
+        let mut out = Cnf::new();
+        for (sym, value) in self.fixed.iter() {
+            out.insert(if *value { Disj::axiom(sym) } else { Disj::axiom_not(sym) });
+        }
+        return out;
+    }
+
+    /// Finds the names, the guarded clauses and the clauses of all axioms again, after axioms
+    /// were removed or changed. Resolvents are computed again when needed.
+    fn rebuild(&mut self) {
+        self.names.clear();
+        for (i, a) in self.axioms.iter().enumerate() {
+            if let Some(name) = &a.name {
+                self.names.insert(name.clone(), i);
+            }
+        }
+
+        self.guarded.clear();
+        self.limited = false;
+        self.pending = self.units();
+        for a in self.axioms.iter() {
+            self.pending.insert_all(&guard(&a.clauses, &a.tags));
+        }
+        self.saturated = false;
+
+        self.select();
+
+        if self.garbage() > GC_THRESHOLD {
+            self.gc();
+        }
+    }
+
+    /// Disables all axioms with the given tag. Returns false if the tag was already disabled.
+    pub fn disable(&mut self, tag: &str) -> bool {
+        if !self.disabled.insert(tag.to_string()) {
+            return false;
+        }
+
+        self.select();
+        return true;
+    }
+
+    /// Enables the axioms with the given tag again, as far as they have no other disabled tags.
+    /// Returns false if the tag was not disabled.
+    pub fn enable(&mut self, tag: &str) -> bool {
+        if !self.disabled.remove(tag) {
+            return false;
+        }
+
+        self.select();
+        return true;
+    }
+
+    /// Tests whether the given tag is disabled.
+    pub fn is_disabled(&self, tag: &str) -> bool {
+        return self.disabled.contains(tag);
+    }
+
+    /// Applies the given guarded clauses to the enabled tags: clauses guarded by a disabled tag
+    /// are left out, and the guards of the other clauses are removed.
+    fn assume(&self, guarded: &Cnf) -> Cnf {
+        let mut out = Cnf::new();
+
+        for disj in guarded.terms.iter() {
+            let disabled = disj.neg()
+                .filter_map(|s| s.strip_prefix('@'))
+                .any(|t| self.disabled.contains(t));
+
+            if !disabled {
+                let lits = disj.literals().iter().filter(|l| !l.atom.starts_with('@')).cloned();
+                out.insert(Disj::of_literals(lits).unwrap());
+            }
+        }
+
+        return out;
+    }
+
+    /// Finds the clauses of all enabled axioms, and their resolvents if they have been
+    /// computed, after the axioms or the enabled tags changed. Resolvents are not computed
+    /// again: those of the guarded clauses are still valid.
+    fn select(&mut self) {
+        self.clauses = self.units();
+        for i in 0..self.axioms.len() {
+            if self.is_enabled(i) {
+                self.clauses.insert_all(&self.axioms[i].clauses);
+            }
+        }
+
+        if self.saturated {
+            self.resolved = self.assume(&self.guarded);
+            self.resolved.remove_subsumed();
+        }
+
+        // Lemmas may no longer follow when axioms are left out
+        self.lemmas.clear();
+    }
+
+    /// Remembers the unit and binary clauses of the given clauses as lemmas, which must follow
+    /// from the enabled axioms. Lemmas are kept aside from the axioms, and are used to answer
+    /// later questions quickly, see [Self::is_known]. They stay valid while axioms are added,
+    /// and are forgotten when axioms are removed or disabled.
+    pub fn learn(&mut self, clauses: &Cnf) {
+        for disj in clauses.terms.iter() {
+            if disj.len() <= 2 {
+                self.lemmas.insert(disj.clone());
+            }
+        }
+    }
+
+    /// Tests whether every clause of the given clauses is subsumed by a lemma, so that they
+    /// are known to follow from the enabled axioms without proving them again.
+    pub fn is_known(&self, clauses: &Cnf) -> bool {
+        return clauses.terms.iter().all(|d| {
+            self.lemmas.terms.iter().any(|l| l.subsumes(d))
+        });
+    }
+
+    /// Returns the lemmas, see [Self::learn].
+    pub fn lemmas(&self) -> &Cnf {
+        return &self.lemmas;
+    }
+
+    /// Forgets all lemmas.
+    pub fn clear_lemmas(&mut self) {
+        self.lemmas.clear();
+    }
+
+    /// Finds a model of the enabled axioms in which the given statement is false. Returns
+    /// `None` if there is no such model, i.e. if the statement follows from the axioms.
+    pub fn counter_model(&self, stmt: &Stmt) -> Option<Model> {
+        let mut solver = Solver::new();
+        solver.add_cnf(&self.clauses);
+        solver.add_cnf(&stmt.clone().not().cnf());
+        return solver.solve();
+    }
+
+    /// Tests whether the given statement follows from the enabled axioms, i.e. whether it is
+    /// true in every model of the axioms.
+    pub fn entails(&self, stmt: &Stmt) -> bool {
+        return self.counter_model(stmt).is_none();
+    }
+
+    /// Tests whether the given clauses follow from the enabled axioms, like [Self::entails],
+    /// but tries cheap methods first, and escalates to more expensive ones only for the clauses
+    /// that the cheaper ones leave open. Returns the answer along with the last [Method] that
+    /// was needed:
+    ///
+    /// 1. Clauses that are known, or subsumed by a known clause or lemma, follow.
+    /// 2. Clauses whose negation leads to a conflict by unit propagation follow.
+    /// 3. For every clause, resolution from the axioms and its negation either derives the
+    ///    empty clause, in which case it follows, or stops without it, in which case it does
+    ///    not follow. Resolution stops at the limits of [Self::set_limits], and when the budget
+    ///    runs out, after which the remaining clauses are left to the solver.
+    /// 4. The solver answers for the remaining clauses. It is not bounded by the budget, so
+    ///    that every question is answered.
+    pub fn entails_escalating(&self, clauses: &Cnf, budget: Duration) -> (bool, Method) {
+        let start = Instant::now();
+
+        let mut known = Vec::from_iter(self.clauses.terms.iter().chain(self.lemmas.terms.iter()));
+        if self.saturated {
+            known.extend(self.resolved.terms.iter());
+        }
+
+        let mut open = Vec::from_iter(clauses.sorted().into_iter().filter(|d| !known.iter().any(|k| k.subsumes(d))));
+        if open.is_empty() {
+            return (true, Method::Containment);
+        }
+
+        open.retain(|d| {
+            let mut solver = Solver::new();
+            solver.add_cnf(&self.clauses);
+            solver.add_cnf(&negate(d));
+            return !solver.propagates_conflict();
+        });
+        if open.is_empty() {
+            return (true, Method::UnitPropagation);
+        }
+
+        while let Some(disj) = open.first() {
+            let remaining = match budget.checked_sub(start.elapsed()) {
+                Some(remaining) if !remaining.is_zero() => remaining,
+                _ => break,
+            };
+            // Only full resolution saturates without the empty clause when the clause does not
+            // follow
+            let config = SaturationConfig {
+                strategy: ResolutionStrategy::Full,
+                max_time: Some(self.limits.max_time.map_or(remaining, |max| max.min(remaining))),
+                ..self.limits.clone()
+            };
+
+            let mut cnf = self.clauses.clone();
+            cnf.insert_all(&negate(disj));
+            match cnf.saturate(&config) {
+                (SaturationOutcome::Refuted, _) => open.remove(0),
+                // Without the discarded resolvents, the empty clause may have been missed
+                (SaturationOutcome::Saturated, stats) if !stats.is_incomplete() => return (false, Method::Resolution),
+                _ => break,
+            };
+        }
+        if open.is_empty() {
+            return (true, Method::Resolution);
+        }
+
+        let open = Cnf::of_vec(&Vec::from_iter(open.into_iter().cloned()));
+        return (Queries::new(&self.clauses).entails(&open), Method::Solver);
+    }
+
+    /// Adds a soft statement with the given weight: a statement that need not be true, but
+    /// that the models found by [Self::maximize] make true where the axioms allow it. Unlike
+    /// axioms, soft statements are not used to answer questions, and are not written by
+    /// [Self::write]. The statement is conditioned on the fixed symbols, see [Self::fix].
+    pub fn assert_soft(&mut self, stmt: Stmt, weight: u64) {
+        let stmt = self.fixed.iter().fold(stmt, |s, (sym, value)| s.fix(sym, *value));
+        self.soft.push((stmt, weight));
+    }
+
+    /// Returns the soft statements, along with their weights, in the order they were asserted.
+    pub fn soft(&self) -> &[(Stmt, u64)] {
+        return &self.soft;
+    }
+
+    /// Removes all soft statements.
+    pub fn clear_soft(&mut self) {
+        self.soft.clear();
+    }
+
+    /// Finds a model of the enabled axioms that makes soft statements of the largest total
+    /// weight true, see [Solver::maximize]. The statements it makes false are given by their
+    /// index in [Self::soft]. Returns `None` if the axioms are unsatisfiable.
+    ///
+    /// With a weight of 1 for each, this answers which of a set of statements can be true
+    /// together with the axioms: the largest such subset is the set of statements that are
+    /// not violated.
+    pub fn maximize(&self) -> Option<Optimum> {
+        let mut solver = Solver::new();
+        solver.add_cnf(&self.clauses);
+
+        let soft = Vec::from_iter(self.soft.iter().map(|(s, w)| (s.cnf(), *w)));
+        return solver.maximize(&soft);
+    }
+
+    /// Tests for every given statement whether it follows from the enabled axioms. This is
+    /// faster than testing them one by one with [Self::entails], as the questions share work,
+    /// see [Queries].
+    pub fn entails_all(&self, stmts: &[Stmt]) -> Vec<bool> {
+        let mut queries = Queries::new(&self.clauses);
+        return stmts.iter().map(|s| queries.entails(&s.cnf())).collect();
+    }
+
+    /// Finds a minimal set of enabled axioms that is unsatisfiable together with the given
+    /// clauses, and returns their indices. Returns `None` if all enabled axioms together with
+    /// the clauses are satisfiable. Given the negation of a statement, these are the axioms needed to prove
+    /// the statement. Given no clauses, these are the axioms that contradict each other.
+    ///
+    /// Axioms are left out one at a time, as long as the rest stays unsatisfiable. The result
+    /// is minimal in the sense that no axiom can be left out further, but a smaller set may
+    /// exist. The values of fixed symbols are assumed throughout.
+    pub fn core(&self, extra: &Cnf) -> Option<Vec<usize>> {
+        let units = self.units();
+        let unsat = |axioms: &[usize]| {
+            let mut solver = Solver::new();
+            solver.add_cnf(&units);
+            solver.add_cnf(extra);
+            for i in axioms.iter() {
+                solver.add_cnf(&self.axioms[*i].clauses);
+            }
+            return solver.solve().is_none();
+        };
+
+        let mut core: Vec<usize> = (0..self.axioms.len()).filter(|i| self.is_enabled(*i)).collect();
+        if !unsat(&core) {
+            return None;
+        }
+
+        let mut i = 0;
+        while i < core.len() {
+            let removed = core.remove(i);
+            if !unsat(&core) {
+                core.insert(i, removed);
+                i += 1;
+            }
+        }
+
+        return Some(core);
+    }
+
+    /// Finds a resolution proof that the given statement follows from the enabled axioms: a
+    /// refutation of the clauses of a [core](Self::core) of axioms along with the negation of
+    /// the statement, see [Proof::refute], within the limits on resolution (see
+    /// [Self::set_limits]). The premises are labelled by the axiom they are a clause of (see
+    /// [Axiom::label]), `fixed` for the values of fixed symbols, or `negated question` for the
+    /// negation of the statement.
+    ///
+    /// Returns `None` if the statement does not follow, or if no proof is found within the
+    /// limits.
+    pub fn proof(&self, stmt: &Stmt) -> Option<Proof> {
+        let negation = stmt.clone().not().cnf();
+        let core = self.core(&negation)?;
+
+        let mut premises = Vec::new();
+        for disj in self.units().sorted() {
+            premises.push((disj.clone(), String::from("fixed")));
+        }
+        for axiom in core.into_iter().map(|i| &self.axioms[i]) {
+            premises.extend(axiom.clauses.sorted().into_iter().map(|d| (d.clone(), axiom.label())));
+        }
+        for disj in negation.sorted() {
+            premises.push((disj.clone(), String::from("negated question")));
+        }
+
+        return Proof::refute(&premises, &self.limits);
+    }
+
+    /// Replaces the enabled axioms by a smaller set of clauses with the same models, as far as
+    /// symbols that are not auxiliary are concerned (see [compact::compact]). Every clause
+    /// becomes an axiom by itself: the names and tags of the enabled axioms are dropped.
+    /// Disabled axioms are kept as they are. So are the soft statements, which are not
+    /// axioms, the states saved by [Self::push] and the [Self::stats].
+    pub fn compact(&mut self) {
+        let mut enabled = Cnf::new();
+        for i in (0..self.axioms.len()).filter(|i| self.is_enabled(*i)) {
+            enabled.insert_all(&self.axioms[i].clauses);
+        }
+        let clauses = compact::compact(&enabled);
+
+        let disabled: Vec<Axiom> = (0..self.axioms.len())
+            .filter(|i| !self.is_enabled(*i))
+            .map(|i| self.axioms[i].clone())
+            .collect();
+        let tags = std::mem::take(&mut self.disabled);
+        let fixed = std::mem::take(&mut self.fixed);
+        let scopes = std::mem::take(&mut self.scopes);
+        let soft = std::mem::take(&mut self.soft);
+        let stats = std::mem::take(&mut self.stats);
+
+        self.clear();
+        self.disabled = tags;
+        self.fixed = fixed;
+        self.scopes = scopes;
+        self.soft = soft;
+        self.stats = stats;
+        self.rebuild();
+
+        for stmt in clauses.sorted().into_iter().map(Stmt::clause) {
+            self.assert(stmt).unwrap();
+        }
+
+        for axiom in disabled {
+            self.assert_labelled(axiom.name.as_deref(), &axiom.tags, axiom.stmt).unwrap();
+        }
+    }
+
+    /// Compares this knowledge base to an older version of it, at the level of axioms and at the
+    /// level of clauses. All axioms are compared, including disabled ones.
+    pub fn diff(&self, old: &KnowledgeBase) -> Diff {
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        let mut changed = Vec::new();
+
+        for axiom in self.axioms.iter() {
+            let other = match &axiom.name {
+                Some(name) => old.find(name).map(|i| &old.axioms[i]),
+                None => old.axioms.iter().find(|a| a.name.is_none() && a.to_string() == axiom.to_string()),
+            };
+
+            match other {
+                None => added.push(axiom.clone()),
+                Some(other) if other.to_string() != axiom.to_string() => changed.push((other.clone(), axiom.clone())),
+                Some(_) => {},
+            }
+        }
+
+        for axiom in old.axioms.iter() {
+            let other = match &axiom.name {
+                Some(name) => self.find(name),
+                None => self.axioms.iter().position(|a| a.name.is_none() && a.to_string() == axiom.to_string()),
+            };
+
+            if other.is_none() {
+                removed.push(axiom.clone());
+            }
+        }
+
+        let mut new_clauses = Cnf::new();
+        for axiom in self.axioms.iter() {
+            new_clauses.insert_all(&axiom.clauses);
+        }
+
+        let mut old_clauses = Cnf::new();
+        for axiom in old.axioms.iter() {
+            old_clauses.insert_all(&axiom.clauses);
+        }
+
+        let mut added_clauses = Cnf::new();
+        for disj in new_clauses.terms.iter().filter(|d| !old_clauses.contains(d)) {
+            added_clauses.insert(disj.clone());
+        }
+
+        let mut removed_clauses = Cnf::new();
+        for disj in old_clauses.terms.iter().filter(|d| !new_clauses.contains(d)) {
+            removed_clauses.insert(disj.clone());
+        }
+
+        return Diff { added, removed, changed, added_clauses, removed_clauses };
+    }
+
+    /// Compares this knowledge base to another by entailment, i.e. by their models rather than
+    /// by their axioms. Only enabled axioms are compared.
+    pub fn compare(&self, other: &KnowledgeBase) -> Entailment {
+        let weaker = counter_model(&self.clauses, &other.clauses);
+        let stronger = counter_model(&other.clauses, &self.clauses);
+
+        return match (weaker, stronger) {
+            (None, None) => Entailment::Equivalent,
+            (None, Some(m)) => Entailment::Stronger(m),
+            (Some(m), None) => Entailment::Weaker(m),
+            (Some(m), Some(n)) => Entailment::Neither(m, n),
+        };
+    }
+
+    /// Writes all axioms in the input syntax of the REPL, one per line, along with their names
+    /// and tags. The result can be read back by [Self::read]. Fixed symbols are written as
+    /// axioms too.
+    pub fn write(&self) -> String {
+        let mut out = String::new();
+        for (sym, value) in self.fixed.iter() {
+            out.push_str(&format!("{}{sym}\n", if *value { "" } else { "!" }));
+        }
+        for axiom in self.axioms.iter() {
+            out.push_str(&format!("{axiom}\n"));
+        }
+        return out;
+    }
+
+    /// Reads a knowledge base from the input syntax of the REPL: every line is an axiom,
+    /// possibly named and tagged, or an enum declaration. Empty lines and lines starting with
+    /// `#` are skipped. All tags are enabled.
+    ///
+    /// Text in the DIMACS CNF format and SMT-LIB 2 scripts are recognized, see
+    /// [Format::detect], and read as by [Self::read_as].
+    pub fn read(text: &str) -> Result<KnowledgeBase, String> {
+        return Self::read_as(text, Format::detect(text));
+    }
+
+    /// Reads a knowledge base from text in the given format. Text in the DIMACS CNF format is
+    /// read by [dimacs::read], with every clause as an axiom, and SMT-LIB 2 scripts are read
+    /// by [smtlib::read], with every assertion as an axiom. Text in the input syntax of the
+    /// REPL is read as by [Self::read].
+    pub fn read_as(text: &str, format: Format) -> Result<KnowledgeBase, String> {
+        let mut kb = KnowledgeBase::new();
+
+        if format == Format::SmtLib {
+            for stmt in smtlib::read(text)? {
+                kb.assert(stmt)?;
+            }
+            return Ok(kb);
+        }
+
+        if format == Format::Dimacs {
+            for stmt in dimacs::read(text)?.sorted().into_iter().map(Stmt::clause) {
+                kb.assert(stmt)?;
+            }
+            return Ok(kb);
+        }
+
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            let result = match Parser::new(line.to_string()).expr() {
+                ParsedStatement::Axiom(s) => kb.assert_labelled(None, &[], s).map(|_| ()),
+                ParsedStatement::Labelled(name, tags, s) => kb.assert_labelled(name.as_deref(), &tags, s).map(|_| ()),
+                ParsedStatement::Enum(name, values) => {
+                    let atoms: Vec<Stmt> = values.iter()
+                        .map(|v| Stmt::symbol(&format!("{name}.{v}")))
+                        .collect();
+                    kb.assert_labelled(None, &[], Stmt::exactly_one(&atoms)).map(|_| ())
+                },
+                ParsedStatement::Error(msg, idx) => Err(format!("{msg} at column {}", idx + 1)),
+                _ => Err(String::from("Expected axiom")),
+            };
+
+            if let Err(msg) = result {
+                return Err(format!("Line {}: {msg}", i + 1));
+            }
+        }
+
+        return Ok(kb);
+    }
+
+    /// Writes all axioms to the given file, see [Self::write].
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        return std::fs::write(path, self.write()).map_err(|e| e.to_string());
+    }
+
+    /// Reads a knowledge base from the given file, see [Self::read]. This can be a file in the
+    /// DIMACS CNF format.
+    pub fn load(path: impl AsRef<Path>) -> Result<KnowledgeBase, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        return Self::read(&text);
+    }
+
+    /// Returns the room for clauses that is allocated but not used, across all clause sets of
+    /// the knowledge base. Clause sets keep their memory when clauses are removed, which adds
+    /// up after axioms are retracted, as all resolvents are computed again then.
+    pub fn garbage(&self) -> usize {
+        let sets = [&self.guarded, &self.pending, &self.clauses, &self.resolved, &self.lemmas];
+        let axioms = self.axioms.iter().map(|a| &a.clauses);
+        return sets.into_iter().chain(axioms).map(|c| c.capacity() - c.terms.len()).sum();
+    }
+
+    /// Compacts the knowledge base: frees the memory that its clause sets and axioms do not
+    /// need, see [Self::garbage]. This is done automatically when axioms are retracted or
+    /// changed and much memory is unused. Returns the room for clauses that was freed.
+    pub fn gc(&mut self) -> usize {
+        let before = self.garbage();
+
+        for cnf in [&mut self.guarded, &mut self.pending, &mut self.clauses, &mut self.resolved, &mut self.lemmas] {
+            cnf.shrink_to_fit();
+        }
+        for axiom in self.axioms.iter_mut() {
+            axiom.clauses.shrink_to_fit();
+        }
+        self.axioms.shrink_to_fit();
+        self.names.shrink_to_fit();
+
+        return before - self.garbage();
+    }
+
+    /// Removes all axioms and soft statements, and forgets the states saved by [Self::push].
+    /// The limits on resolution are kept.
+    pub fn clear(&mut self) {
+        let limits = std::mem::take(&mut self.limits);
+        *self = Self::new();
+        self.limits = limits;
+    }
+
+    /// Saves the current state of the knowledge base, so that everything that changes after,
+    /// like asserting, retracting or fixing, can be undone by [Self::pop]. This allows to
+    /// assert hypotheses and explore what follows from them, like the `push` command of SMT
+    /// solvers. Contexts nest: every push is undone by its own pop. Saved states survive
+    /// [Self::compact], which is undone by a pop like any other change, but not
+    /// [Self::clear]. Returns the number of saved contexts, including the new one.
+    pub fn push(&mut self) -> usize {
+        let scopes = std::mem::take(&mut self.scopes);
+        let saved = self.clone();
+        self.scopes = scopes;
+        self.scopes.push(saved);
+        return self.scopes.len();
+    }
+
+    /// Returns to the state saved by the last [Self::push], forgetting all axioms, and all
+    /// clauses derived, since. The limits are kept as they are, and so are the [Self::stats].
+    /// Returns `false` if there is no saved state, in which case nothing changes.
+    pub fn pop(&mut self) -> bool {
+        let Some(saved) = self.scopes.pop() else {
+            return false;
+        };
+
+        let scopes = std::mem::take(&mut self.scopes);
+        let limits = std::mem::take(&mut self.limits);
+        let stats = std::mem::take(&mut self.stats);
+        *self = saved;
+        self.scopes = scopes;
+        self.limits = limits;
+        self.stats = stats;
+        return true;
+    }
+
+    /// Returns the number of states saved by [Self::push] that are not popped yet.
+    pub fn depth(&self) -> usize {
+        return self.scopes.len();
+    }
+
+    /// Returns the limits on computing resolvents, see [Self::set_limits].
+    pub fn limits(&self) -> &SaturationConfig {
+        return &self.limits;
+    }
+
+    /// Sets the limits on computing resolvents in [Self::resolve]. Without limits, resolution
+    /// can take very long, as the number of resolvents can grow exponentially. A new knowledge
+    /// base allows at most 10000 clauses, and at most 5 seconds every time resolvents are
+    /// computed. When resolution stopped at a limit before, it continues under the new limits
+    /// the next time resolvents are needed.
+    pub fn set_limits(&mut self, limits: SaturationConfig) {
+        self.limits = limits;
+        if self.limited {
+            self.saturated = false;
+        }
+    }
+
+    /// Tests whether computing resolvents stopped at a limit the last time, or discarded
+    /// resolvents longer than the maximum length, so that the clauses returned by
+    /// [Self::resolve] do not include all resolvents. They still have the
+    /// same models as the axioms, so questions are answered correctly, but a contradiction
+    /// is not necessarily visible as the empty clause.
+    pub fn reached_limit(&self) -> bool {
+        return self.limited;
+    }
+
+    /// Returns the work done computing resolvents in [Self::resolve] so far, added up over
+    /// every time they were computed. This counts from when the knowledge base was created or
+    /// cleared, and is not undone by [Self::pop], nor reset by [Self::compact].
+    pub fn stats(&self) -> &SolveStats {
+        return &self.stats;
+    }
+
+    /// Computes all resolvents of the axioms, if not done yet, and returns the clauses of all
+    /// enabled axioms along with all resolvents that follow from them. Clauses that are
+    /// subsumed by other clauses are left out, see [Cnf::saturate]. Resolution stops at the
+    /// limits of [Self::set_limits], in which case only the resolvents found so far are
+    /// returned, see [Self::reached_limit].
+    pub fn resolve(&mut self) -> &Cnf {
+        if !self.saturated {
+            let pending = std::mem::take(&mut self.pending);
+            let (outcome, stats) = if self.limited {
+                // The clauses were not saturated last time, so they are all resolved again
+                self.guarded.insert_all(&pending);
+                self.guarded.saturate(&self.limits)
+            } else {
+                self.guarded.saturate_with(pending, &self.limits)
+            };
+            self.stats += &stats;
+            self.limited = outcome == SaturationOutcome::LimitReached || stats.is_incomplete();
+            self.saturated = true;
+            self.resolved = self.assume(&self.guarded);
+            self.resolved.remove_subsumed();
+        }
+
+        return &self.resolved;
+    }
+}
+
+impl Default for KnowledgeBase {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
@@ -0,0 +1,44 @@
+#![allow(clippy::needless_return)]
+#![allow(clippy::should_implement_trait)]
+
+#[cfg(feature = "bdd")]
+pub mod bdd;
+pub mod bitset;
+pub mod cnf;
+pub mod compact;
+pub mod decode;
+pub mod dimacs;
+pub mod dnf;
+pub mod encode;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "fol")]
+pub mod fol;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+pub mod generate;
+pub mod horn;
+pub mod kb;
+pub mod lint;
+pub mod literal;
+pub mod ltl;
+pub mod minimize;
+pub mod modal;
+pub mod stmt;
+pub mod parser;
+pub mod qbf;
+pub mod proof;
+#[cfg(feature = "repl")]
+pub mod prove;
+#[cfg(feature = "repl")]
+pub mod repl;
+#[cfg(feature = "repl")]
+pub mod replay;
+#[cfg(feature = "repl")]
+pub mod saturate;
+pub mod smtlib;
+pub mod solver;
+pub mod step;
+pub mod symbol;
+#[cfg(feature = "wasm")]
+pub mod wasm;
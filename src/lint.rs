@@ -0,0 +1,359 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Display;
+
+use crate::cnf::{Cnf, Disj};
+use crate::kb::KnowledgeBase;
+use crate::literal::Literal;
+use crate::solver::{Queries, Solver};
+use crate::stmt::Stmt;
+
+/// The severity of a lint: how likely it is that the encoding is wrong.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Severity {
+    /// Not reported at all.
+    Off,
+
+    /// Harmless, but worth knowing.
+    Info,
+
+    /// Probably a mistake.
+    Warning,
+
+    /// Certainly a mistake.
+    Error
+}
+
+impl Severity {
+    /// Parses a severity from its name, as it is displayed.
+    pub fn parse(name: &str) -> Option<Severity> {
+        return match name {
+            "off" => Some(Severity::Off),
+            "info" => Some(Severity::Info),
+            "warning" => Some(Severity::Warning),
+            "error" => Some(Severity::Error),
+            _ => None,
+        };
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Severity::Off => write!(f, "off"),
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        };
+    }
+}
+
+/// A rule of the [Linter]: a kind of suspicious encoding it looks for.
+pub struct Rule {
+    /// The ID of the rule, by which it is configured.
+    pub id: &'static str,
+
+    /// The severity of the rule, unless configured otherwise.
+    pub severity: Severity,
+
+    /// A short description of what the rule looks for.
+    pub description: &'static str,
+
+    /// Finds the subjects and messages of the findings of the rule in a knowledge base.
+    check: fn(&KnowledgeBase) -> Vec<(String, String)>
+}
+
+/// All rules of the [Linter].
+pub const RULES: &[Rule] = &[
+    Rule {
+        id: "single-polarity",
+        severity: Severity::Warning,
+        description: "A symbol occurs only positively or only negatively, so its clauses do not constrain other symbols",
+        check: single_polarity
+    },
+    Rule {
+        id: "satisfied-clause",
+        severity: Severity::Info,
+        description: "A clause of an axiom is always satisfied, since one of its literals is a fact",
+        check: satisfied_clause
+    },
+    Rule {
+        id: "redundant-axiom",
+        severity: Severity::Warning,
+        description: "An axiom follows from the other axioms, so it adds no information",
+        check: redundant_axiom
+    },
+    Rule {
+        id: "unused-definition",
+        severity: Severity::Info,
+        description: "A definition X <-> A defines a symbol X that no other axiom uses",
+        check: unused_definition
+    },
+    Rule {
+        id: "contradictory-tag",
+        severity: Severity::Error,
+        description: "The axioms with a tag contradict each other or the untagged axioms, so the tag cannot be enabled",
+        check: contradictory_tag
+    },
+];
+
+/// A finding of the [Linter]: a suspicious part of the encoding of a knowledge base. Such
+/// parts are not necessarily wrong, but often point at a mistake, like a misspelled symbol
+/// or a forgotten axiom.
+#[derive(Debug)]
+pub struct Lint {
+    /// The ID of the rule that found this lint.
+    pub rule: &'static str,
+
+    /// The severity of the rule that found this lint, as configured.
+    pub severity: Severity,
+
+    /// What this lint is about: a symbol, the label of an axiom, or a tag, by which it can be
+    /// suppressed.
+    pub subject: String,
+
+    /// A description of the finding.
+    pub message: String
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}[{}]: {}", self.severity, self.rule, self.message);
+    }
+}
+
+/// Counts the occurrences of every symbol in the given clauses, positively and negatively.
+pub fn polarities(cnf: &Cnf) -> BTreeMap<String, (usize, usize)> {
+    let mut out = BTreeMap::new();
+
+    for disj in cnf.terms.iter() {
+        for l in disj.literals().iter() {
+            let counts = out.entry(l.atom.to_string()).or_insert((0, 0));
+            if l.polarity {
+                counts.0 += 1;
+            } else {
+                counts.1 += 1;
+            }
+        }
+    }
+
+    return out;
+}
+
+/// Returns the symbol defined by the given statement, if it is a definition like `X <-> A`.
+fn defined(stmt: &Stmt) -> Option<&str> {
+    return stmt.as_equiv().and_then(|(l, _)| l.as_symbol());
+}
+
+/// Tests whether the given clauses mention the given symbol.
+fn mentions(cnf: &Cnf, symbol: &str) -> bool {
+    return cnf.terms.iter().any(|d| !d.is_unknown(symbol));
+}
+
+/// Returns the unit clauses of the fixed symbols of a knowledge base.
+fn units(kb: &KnowledgeBase) -> Cnf {
+    let mut out = Cnf::new();
+    for (sym, value) in kb.fixed().iter() {
+        out.insert(if *value { Disj::axiom(sym) } else { Disj::axiom_not(sym) });
+    }
+    return out;
+}
+
+/// Looks for suspicious parts of the encoding of a knowledge base, by the [RULES]. Rules can
+/// be configured to another severity, or turned off, and single findings can be suppressed
+/// by their subject.
+pub struct Linter {
+    /// The configured severity of rules, by ID, for those that differ from their default.
+    levels: HashMap<&'static str, Severity>,
+
+    /// The suppressed findings, by rule ID and subject.
+    allowed: HashSet<(&'static str, String)>
+}
+
+impl Linter {
+    pub fn new() -> Linter {
+        return Linter {
+            levels: HashMap::new(),
+            allowed: HashSet::new()
+        };
+    }
+
+    /// Finds the rule with the given ID, failing if there is none.
+    fn rule(id: &str) -> Result<&'static Rule, String> {
+        return RULES.iter()
+            .find(|r| r.id == id)
+            .ok_or_else(|| format!("Unknown lint rule '{id}'"));
+    }
+
+    /// Returns the severity of the given rule, as configured.
+    pub fn level(&self, rule: &Rule) -> Severity {
+        return self.levels.get(rule.id).copied().unwrap_or(rule.severity);
+    }
+
+    /// Configures the severity of the rule with the given ID. The severity [Severity::Off]
+    /// turns the rule off. Fails if there is no such rule.
+    pub fn set_level(&mut self, id: &str, severity: Severity) -> Result<(), String> {
+        let rule = Self::rule(id)?;
+        self.levels.insert(rule.id, severity);
+        return Ok(());
+    }
+
+    /// Suppresses the findings of the rule with the given ID about the given subject. Fails
+    /// if there is no such rule.
+    pub fn allow(&mut self, id: &str, subject: &str) -> Result<(), String> {
+        let rule = Self::rule(id)?;
+        self.allowed.insert((rule.id, subject.to_string()));
+        return Ok(());
+    }
+
+    /// Lints the enabled axioms of a knowledge base, and returns all findings of rules that
+    /// are not turned off, except for suppressed ones. Findings are ordered by rule, in the
+    /// order of [RULES].
+    pub fn lint(&self, kb: &KnowledgeBase) -> Vec<Lint> {
+        let mut out = Vec::new();
+
+        for rule in RULES.iter() {
+            let severity = self.level(rule);
+            if severity == Severity::Off {
+                continue;
+            }
+
+            for (subject, message) in (rule.check)(kb) {
+                if !self.allowed.contains(&(rule.id, subject.clone())) {
+                    out.push(Lint { rule: rule.id, severity, subject, message });
+                }
+            }
+        }
+
+        return out;
+    }
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+/// Returns the literals that are facts of the given clauses: their unit clauses.
+fn facts(cnf: &Cnf) -> HashSet<Literal> {
+    let mut out = HashSet::new();
+    for disj in cnf.terms.iter() {
+        if disj.len() == 1 {
+            out.extend(disj.literals().iter().cloned());
+        }
+    }
+    return out;
+}
+
+/// Finds the symbols that occur in one polarity only. Their clauses can always be satisfied by
+/// giving them that value. Symbols that are facts are left out.
+fn single_polarity(kb: &KnowledgeBase) -> Vec<(String, String)> {
+    let facts = facts(kb.clauses());
+    let mut out = Vec::new();
+
+    for (symbol, (pos, neg)) in polarities(kb.clauses()) {
+        if facts.contains(&Literal::pos(&symbol)) || facts.contains(&Literal::neg(&symbol)) {
+            continue;
+        }
+        if pos != 0 && neg != 0 {
+            continue;
+        }
+
+        let (polarity, value) = if neg == 0 { ("positively", "true") } else { ("negatively", "false") };
+        let clauses = if pos + neg == 1 { "1 clause".to_string() } else { format!("{} clauses", pos + neg) };
+        let message = format!("{symbol} only occurs {polarity}, in {clauses}: making it {value} satisfies them, so they do not constrain other symbols");
+        out.push((symbol, message));
+    }
+
+    return out;
+}
+
+/// Finds the clauses of enabled axioms that are always satisfied by a fact.
+fn satisfied_clause(kb: &KnowledgeBase) -> Vec<(String, String)> {
+    let facts = facts(kb.clauses());
+    let mut out = Vec::new();
+
+    for axiom in (0..kb.axioms().len()).filter(|i| kb.is_enabled(*i)).map(|i| &kb.axioms()[i]) {
+        for disj in axiom.clauses.sorted() {
+            if disj.len() <= 1 {
+                continue;
+            }
+
+            if let Some(literal) = disj.literals().iter().find(|l| facts.contains(*l)) {
+                let message = format!("The clause ({disj}) of {} is always satisfied, since {literal} is a fact", axiom.label());
+                out.push((axiom.label(), message));
+            }
+        }
+    }
+
+    return out;
+}
+
+/// Finds the enabled axioms that follow from the other enabled axioms.
+fn redundant_axiom(kb: &KnowledgeBase) -> Vec<(String, String)> {
+    let enabled: Vec<usize> = (0..kb.axioms().len()).filter(|i| kb.is_enabled(*i)).collect();
+    let mut out = Vec::new();
+
+    for i in enabled.iter() {
+        let mut others = units(kb);
+        for j in enabled.iter().filter(|j| *j != i) {
+            others.insert_all(&kb.axioms()[*j].clauses);
+        }
+
+        let axiom = &kb.axioms()[*i];
+        if Queries::new(&others).entails(&axiom.clauses) {
+            let message = format!("{} follows from the other axioms, so it adds no information", axiom.label());
+            out.push((axiom.label(), message));
+        }
+    }
+
+    return out;
+}
+
+/// Finds the definitions `X <-> A` among the enabled axioms of which the defined symbol is
+/// not used by any other enabled axiom.
+fn unused_definition(kb: &KnowledgeBase) -> Vec<(String, String)> {
+    let enabled: Vec<usize> = (0..kb.axioms().len()).filter(|i| kb.is_enabled(*i)).collect();
+    let mut out = Vec::new();
+
+    for i in enabled.iter() {
+        let axiom = &kb.axioms()[*i];
+        let symbol = match defined(&axiom.stmt) {
+            Some(s) => s,
+            None => continue,
+        };
+
+        let used = enabled.iter()
+            .filter(|j| *j != i)
+            .any(|j| mentions(&kb.axioms()[*j].clauses, symbol));
+
+        if !used {
+            let message = format!("{symbol} is defined by {}, but not used by any other axiom", axiom.label());
+            out.push((symbol.to_string(), message));
+        }
+    }
+
+    return out;
+}
+
+/// Finds the tags of which the axioms contradict each other, or the untagged axioms. These
+/// tags cannot be enabled, or are disabled right after.
+fn contradictory_tag(kb: &KnowledgeBase) -> Vec<(String, String)> {
+    let tags: BTreeSet<&String> = kb.axioms().iter().flat_map(|a| a.tags.iter()).collect();
+    let mut out = Vec::new();
+
+    for tag in tags {
+        let mut solver = Solver::new();
+        solver.add_cnf(&units(kb));
+        for axiom in kb.axioms().iter().filter(|a| a.tags.iter().all(|t| t == tag)) {
+            solver.add_cnf(&axiom.clauses);
+        }
+
+        if solver.solve().is_none() {
+            let message = format!("The axioms tagged @{tag} contradict each other or the untagged axioms");
+            out.push((format!("@{tag}"), message));
+        }
+    }
+
+    return out;
+}
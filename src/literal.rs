@@ -0,0 +1,72 @@
+use std::fmt::Display;
+use std::ops::Not;
+
+use crate::symbol::Symbol;
+
+/// A literal: a symbol, either inverted or not. Literals are the parts of clauses, see
+/// [Disj](crate::cnf::Disj).
+///
+/// Literals are ordered by the name of their symbol first, so that the literals of a symbol
+/// are next to each other, with the inverted literal before the non-inverted one.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Literal {
+    /// The symbol.
+    pub atom: Symbol,
+
+    /// Whether the symbol is not inverted: true for `P`, false for `!P`.
+    pub polarity: bool
+}
+
+impl Literal {
+    pub fn new(atom: &str, polarity: bool) -> Literal {
+        return Literal { atom: Symbol::intern(atom), polarity };
+    }
+
+    /// Creates the non-inverted literal of a symbol, e.g. `P`.
+    pub fn pos(atom: &str) -> Literal {
+        return Self::new(atom, true);
+    }
+
+    /// Creates the inverted literal of a symbol, e.g. `!P`.
+    pub fn neg(atom: &str) -> Literal {
+        return Self::new(atom, false);
+    }
+
+    /// Writes this literal for LaTeX math mode, like `\lnot P`.
+    pub fn to_latex(&self) -> String {
+        let atom = Symbol::latex(&self.atom);
+        return if self.polarity { atom } else { format!("\\lnot {atom}") };
+    }
+
+    /// Tests whether the given value of the symbol of this literal makes it true.
+    pub fn is_satisfied_by(&self, value: bool) -> bool {
+        return self.polarity == value;
+    }
+}
+
+impl Not for Literal {
+    type Output = Literal;
+
+    fn not(self) -> Literal {
+        return Literal { atom: self.atom, polarity: !self.polarity };
+    }
+}
+
+impl Not for &Literal {
+    type Output = Literal;
+
+    fn not(self) -> Literal {
+        return Literal { atom: self.atom, polarity: !self.polarity };
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return if self.polarity {
+            write!(f, "{}", self.atom)
+        } else {
+            write!(f, "!{}", self.atom)
+        };
+    }
+}
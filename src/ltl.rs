@@ -0,0 +1,594 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Display;
+use std::fs;
+use std::str::FromStr;
+
+use crate::cnf::{Cnf, Disj};
+use crate::literal::Literal;
+use crate::solver::{Model, Solver};
+
+/// The number of steps that the `bmc` command unrolls, unless given by `--bound`.
+const BOUND: usize = 10;
+
+/// A formula of linear temporal logic, which holds or fails on an infinite sequence of states
+/// of a [TransitionSystem], each of which gives every symbol a value.
+///
+/// The syntax is like that of statements, with `*`, `~`, `!`, `&`, `|`, `->` and `<->`, and the
+/// temporal operators `X A`, `F A`, `G A` and `A U B`, where `U` binds stronger than `&`. The
+/// operators are words, so `X`, `F`, `G` and `U` cannot be used as symbols, and must be
+/// separated from the symbols next to them, as in `G F a`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Ltl {
+    True,
+    False,
+
+    /// A symbol, which is `a'` for the value of `a` in the next state, in the transitions of a
+    /// [TransitionSystem].
+    Symbol(String),
+
+    Not(Box<Ltl>),
+    And(Box<Ltl>, Box<Ltl>),
+    Or(Box<Ltl>, Box<Ltl>),
+    Implies(Box<Ltl>, Box<Ltl>),
+    Equiv(Box<Ltl>, Box<Ltl>),
+
+    /// `X A`: `A` holds in the next state.
+    Next(Box<Ltl>),
+
+    /// `F A`: `A` holds now or in some later state.
+    Finally(Box<Ltl>),
+
+    /// `G A`: `A` holds now and in every later state.
+    Globally(Box<Ltl>),
+
+    /// `A U B`: `B` holds now or in some later state, and `A` holds in every state before it.
+    Until(Box<Ltl>, Box<Ltl>)
+}
+
+impl Ltl {
+    /// Tests whether this formula uses any temporal operator.
+    pub fn is_temporal(&self) -> bool {
+        return match self {
+            Ltl::True | Ltl::False | Ltl::Symbol(_) => false,
+            Ltl::Not(o) => o.is_temporal(),
+            Ltl::And(l, r) | Ltl::Or(l, r) | Ltl::Implies(l, r) | Ltl::Equiv(l, r) => l.is_temporal() || r.is_temporal(),
+            Ltl::Next(_) | Ltl::Finally(_) | Ltl::Globally(_) | Ltl::Until(..) => true,
+        };
+    }
+
+    /// Returns the symbols of this formula, as written, so `a'` is a different symbol than `a`.
+    pub fn symbols(&self) -> BTreeSet<String> {
+        let mut symbols = BTreeSet::new();
+        self.collect_symbols(&mut symbols);
+        return symbols;
+    }
+
+    fn collect_symbols(&self, symbols: &mut BTreeSet<String>) {
+        match self {
+            Ltl::True | Ltl::False => {},
+            Ltl::Symbol(s) => {
+                symbols.insert(s.clone());
+            },
+            Ltl::Not(o) | Ltl::Next(o) | Ltl::Finally(o) | Ltl::Globally(o) => o.collect_symbols(symbols),
+            Ltl::And(l, r) | Ltl::Or(l, r) | Ltl::Implies(l, r) | Ltl::Equiv(l, r) | Ltl::Until(l, r) => {
+                l.collect_symbols(symbols);
+                r.collect_symbols(symbols);
+            },
+        }
+    }
+}
+
+impl Display for Ltl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Ltl::True => write!(f, "*"),
+            Ltl::False => write!(f, "~"),
+            Ltl::Symbol(s) => write!(f, "{s}"),
+            Ltl::Not(o) => write!(f, "!{o}"),
+            Ltl::And(l, r) => write!(f, "({l} & {r})"),
+            Ltl::Or(l, r) => write!(f, "({l} | {r})"),
+            Ltl::Implies(l, r) => write!(f, "({l} -> {r})"),
+            Ltl::Equiv(l, r) => write!(f, "({l} <-> {r})"),
+            Ltl::Next(o) => write!(f, "X {o}"),
+            Ltl::Finally(o) => write!(f, "F {o}"),
+            Ltl::Globally(o) => write!(f, "G {o}"),
+            Ltl::Until(l, r) => write!(f, "({l} U {r})"),
+        };
+    }
+}
+
+impl FromStr for Ltl {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Ltl, String> {
+        let mut parser = LtlParser { tokens: tokenize(s)?, index: 0 };
+        let ltl = parser.equiv()?;
+        if let Some(token) = parser.tokens.get(parser.index) {
+            return Err(format!("Expected end, found \"{token}\""));
+        }
+        return Ok(ltl);
+    }
+}
+
+/// Splits a formula into names, optionally primed as in `a'`, and operators.
+fn tokenize(input: &str) -> Result<Vec<String>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        if c.is_alphanumeric() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            if i < chars.len() && chars[i] == '\'' {
+                i += 1;
+            }
+        } else if chars[i..].starts_with(&['<', '-', '>']) {
+            i += 3;
+        } else if chars[i..].starts_with(&['-', '>']) {
+            i += 2;
+        } else if "!&|()*~".contains(c) {
+            i += 1;
+        } else {
+            return Err(format!("Unexpected character '{c}' at {i}"));
+        }
+        tokens.push(chars[start..i].iter().collect());
+    }
+
+    return Ok(tokens);
+}
+
+/// Recursive-descent parser for [Ltl] formulas, over the tokens of [tokenize].
+struct LtlParser {
+    tokens: Vec<String>,
+    index: usize
+}
+
+impl LtlParser {
+    /// Skips the next token if it is the given one.
+    fn eat(&mut self, token: &str) -> bool {
+        if self.tokens.get(self.index).is_some_and(|t| t == token) {
+            self.index += 1;
+            return true;
+        }
+        return false;
+    }
+
+    fn equiv(&mut self) -> Result<Ltl, String> {
+        let l = self.implies()?;
+        if self.eat("<->") {
+            return Ok(Ltl::Equiv(Box::new(l), Box::new(self.equiv()?)));
+        }
+        return Ok(l);
+    }
+
+    fn implies(&mut self) -> Result<Ltl, String> {
+        let l = self.or()?;
+        if self.eat("->") {
+            return Ok(Ltl::Implies(Box::new(l), Box::new(self.implies()?)));
+        }
+        return Ok(l);
+    }
+
+    fn or(&mut self) -> Result<Ltl, String> {
+        let mut l = self.and()?;
+        while self.eat("|") {
+            l = Ltl::Or(Box::new(l), Box::new(self.and()?));
+        }
+        return Ok(l);
+    }
+
+    fn and(&mut self) -> Result<Ltl, String> {
+        let mut l = self.until()?;
+        while self.eat("&") {
+            l = Ltl::And(Box::new(l), Box::new(self.until()?));
+        }
+        return Ok(l);
+    }
+
+    fn until(&mut self) -> Result<Ltl, String> {
+        let l = self.unary()?;
+        if self.eat("U") {
+            return Ok(Ltl::Until(Box::new(l), Box::new(self.until()?)));
+        }
+        return Ok(l);
+    }
+
+    fn unary(&mut self) -> Result<Ltl, String> {
+        let Some(token) = self.tokens.get(self.index).cloned() else {
+            return Err(String::from("Expected expression, found end"));
+        };
+        self.index += 1;
+
+        return Ok(match token.as_str() {
+            "!" => Ltl::Not(Box::new(self.unary()?)),
+            "X" => Ltl::Next(Box::new(self.unary()?)),
+            "F" => Ltl::Finally(Box::new(self.unary()?)),
+            "G" => Ltl::Globally(Box::new(self.unary()?)),
+            "*" => Ltl::True,
+            "~" => Ltl::False,
+            "(" => {
+                let inner = self.equiv()?;
+                if !self.eat(")") {
+                    return Err(String::from("Expected ')'"));
+                }
+                inner
+            },
+            _ if token.starts_with(|c: char| c.is_alphanumeric() || c == '_') && token != "U" => Ltl::Symbol(token),
+            _ => return Err(format!("Expected expression, found \"{token}\"")),
+        });
+    }
+}
+
+/// A system that moves from state to state, where a state gives every symbol a value. It
+/// starts in any state in which `init` holds, and moves from a state to any state such that
+/// `trans` holds, where `a` is the value of `a` before the move and `a'` its value after.
+///
+/// Systems are described by lines `init: A` and `trans: A`, where the formulas of lines of
+/// the same kind are conjoined, and lines starting with `#` are comments. For example, a
+/// counter of two bits:
+///
+/// ```text
+/// init: !a & !b
+/// trans: (a' <-> !a) & (b' <-> !(a <-> b))
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct TransitionSystem {
+    /// The formula that holds in the first state.
+    pub init: Ltl,
+
+    /// The formula that holds between every state and the next.
+    pub trans: Ltl
+}
+
+impl TransitionSystem {
+    /// Parses the description of a transition system.
+    pub fn parse(text: &str) -> Result<TransitionSystem, String> {
+        let mut init = None;
+        let mut trans = None;
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (target, formula) = match line.split_once(':') {
+                Some(("init", formula)) => (&mut init, formula),
+                Some(("trans", formula)) => (&mut trans, formula),
+                _ => return Err(format!("Line {}: Expected 'init:' or 'trans:'", i + 1)),
+            };
+
+            let formula: Ltl = formula.parse().map_err(|e| format!("Line {}: {e}", i + 1))?;
+            if formula.is_temporal() {
+                return Err(format!("Line {}: Temporal operators are not allowed in a transition system", i + 1));
+            }
+
+            *target = Some(match target.take() {
+                Some(prev) => Ltl::And(Box::new(prev), Box::new(formula)),
+                None => formula,
+            });
+        }
+
+        let init = init.unwrap_or(Ltl::True);
+        if let Some(s) = init.symbols().into_iter().find(|s| s.ends_with('\'')) {
+            return Err(format!("Next-state symbol {s} is only allowed in 'trans:'"));
+        }
+
+        return Ok(TransitionSystem { init, trans: trans.unwrap_or(Ltl::True) });
+    }
+
+    /// Reads and parses the description of a transition system from a file.
+    pub fn load(path: &str) -> Result<TransitionSystem, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Cannot read {path}: {e}"))?;
+        return TransitionSystem::parse(&text);
+    }
+
+    /// Returns the symbols that make up a state, without primes.
+    pub fn symbols(&self) -> BTreeSet<String> {
+        let mut symbols = self.init.symbols();
+        for s in self.trans.symbols() {
+            symbols.insert(s.strip_suffix('\'').unwrap_or(&s).to_string());
+        }
+        return symbols;
+    }
+}
+
+/// A run of a [TransitionSystem] on which a formula fails, as found by [check].
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Trace {
+    /// The states of the run, in order.
+    pub states: Vec<BTreeMap<String, bool>>,
+
+    /// The state that follows the last state, if the run loops back. Without a loop, the
+    /// formula fails on every way the run continues.
+    pub loop_start: Option<usize>
+}
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut lines = Vec::new();
+        for (i, state) in self.states.iter().enumerate() {
+            let values: Vec<String> = state.iter().map(|(s, v)| if *v { s.clone() } else { format!("!{s}") }).collect();
+            lines.push(format!("{i}: {}", values.join(", ")));
+        }
+        if let Some(l) = self.loop_start {
+            lines.push(format!("then back to {l}"));
+        }
+        return write!(f, "{}", lines.join("\n"));
+    }
+}
+
+/// A formula in negation normal form, as the unrolling translates it.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+enum Nnf {
+    True,
+    False,
+    Literal(String, bool),
+    And(Box<Nnf>, Box<Nnf>),
+    Or(Box<Nnf>, Box<Nnf>),
+    Next(Box<Nnf>),
+    Finally(Box<Nnf>),
+    Globally(Box<Nnf>),
+    Until(Box<Nnf>, Box<Nnf>),
+
+    /// `A R B`, the negation of `!A U !B`: `B` holds up to and including the first state in
+    /// which `A` holds, or forever if there is none.
+    Release(Box<Nnf>, Box<Nnf>)
+}
+
+impl Nnf {
+    /// Converts a formula, or its negation if `polarity` is false, to negation normal form.
+    fn of(ltl: &Ltl, polarity: bool) -> Nnf {
+        let of = |o: &Ltl, p: bool| Box::new(Nnf::of(o, p));
+        let both = |l: &Ltl, lp: bool, r: &Ltl, rp: bool| Nnf::And(of(l, lp), of(r, rp));
+
+        return match ltl {
+            Ltl::True => if polarity { Nnf::True } else { Nnf::False },
+            Ltl::False => if polarity { Nnf::False } else { Nnf::True },
+            Ltl::Symbol(s) => Nnf::Literal(s.clone(), polarity),
+            Ltl::Not(o) => Nnf::of(o, !polarity),
+            Ltl::And(l, r) if polarity => Nnf::And(of(l, true), of(r, true)),
+            Ltl::And(l, r) => Nnf::Or(of(l, false), of(r, false)),
+            Ltl::Or(l, r) if polarity => Nnf::Or(of(l, true), of(r, true)),
+            Ltl::Or(l, r) => Nnf::And(of(l, false), of(r, false)),
+            Ltl::Implies(l, r) if polarity => Nnf::Or(of(l, false), of(r, true)),
+            Ltl::Implies(l, r) => both(l, true, r, false),
+            Ltl::Equiv(l, r) => Nnf::Or(Box::new(both(l, true, r, polarity)), Box::new(both(l, false, r, !polarity))),
+            Ltl::Next(o) => Nnf::Next(of(o, polarity)),
+            Ltl::Finally(o) if polarity => Nnf::Finally(of(o, true)),
+            Ltl::Finally(o) => Nnf::Globally(of(o, false)),
+            Ltl::Globally(o) if polarity => Nnf::Globally(of(o, true)),
+            Ltl::Globally(o) => Nnf::Finally(of(o, false)),
+            Ltl::Until(l, r) if polarity => Nnf::Until(of(l, true), of(r, true)),
+            Ltl::Until(l, r) => Nnf::Release(of(l, false), of(r, false)),
+        };
+    }
+}
+
+/// The clauses of a run of `bound + 1` states, in which a symbol `a` in state `i` is the
+/// symbol `a@i`. Every subformula gets a gate symbol `@n` that implies it, so that the clauses
+/// grow with the size of the formula rather than blowing up as a conversion to CNF would.
+/// Formulas only occur positively, so gates need not be implied by their formulas.
+struct Unrolling<'a> {
+    bound: usize,
+    cnf: Cnf,
+    gates: usize,
+
+    /// The gates of the temporal formulas translated so far, by state and loop.
+    memo: HashMap<(&'a Nnf, usize, Option<usize>), Literal>
+}
+
+impl<'a> Unrolling<'a> {
+    fn new(bound: usize) -> Unrolling<'a> {
+        return Unrolling { bound, cnf: Cnf::new(), gates: 0, memo: HashMap::new() };
+    }
+
+    fn clause(&mut self, lits: impl IntoIterator<Item = Literal>) {
+        // A tautology holds anyway
+        if let Some(disj) = Disj::of_literals(lits) {
+            self.cnf.insert(disj);
+        }
+    }
+
+    /// Returns a gate that implies the conjunction or disjunction of the given literals. The
+    /// empty conjunction is true and the empty disjunction false.
+    fn gate(&mut self, lits: Vec<Literal>, conjunction: bool) -> Literal {
+        if lits.len() == 1 {
+            return lits[0].clone();
+        }
+
+        self.gates += 1;
+        let gate = Literal::pos(&format!("@{}", self.gates));
+        if conjunction {
+            for lit in lits {
+                self.clause([!&gate, lit]);
+            }
+        } else {
+            self.clause([!&gate].into_iter().chain(lits));
+        }
+        return gate;
+    }
+
+    /// Translates a formula without temporal operators, between state `now` and the state
+    /// `next` that primed symbols refer to.
+    fn state(&mut self, f: &Nnf, now: usize, next: usize) -> Literal {
+        return match f {
+            Nnf::True => self.gate(vec![], true),
+            Nnf::False => self.gate(vec![], false),
+            Nnf::Literal(s, p) => match s.strip_suffix('\'') {
+                Some(s) => Literal::new(&format!("{s}@{next}"), *p),
+                None => Literal::new(&format!("{s}@{now}"), *p),
+            },
+            Nnf::And(l, r) | Nnf::Or(l, r) => {
+                let lits = vec![self.state(l, now, next), self.state(r, now, next)];
+                self.gate(lits, matches!(f, Nnf::And(..)))
+            },
+            _ => unreachable!("Temporal operator in a transition system"),
+        };
+    }
+
+    /// Returns the states from state `i` on, in order, each state once: up to the last state,
+    /// and then from the loop state, if any, up to state `i`.
+    fn future(&self, i: usize, l: Option<usize>) -> Vec<usize> {
+        let back = l.map_or(0..0, |l| l..i);
+        return (i..=self.bound).chain(back).collect();
+    }
+
+    /// Translates a formula in state `i` of a run that loops back from the last state to state
+    /// `l`, or that does not loop if `l` is `None`, in which case the formula must hold on
+    /// every way the run continues.
+    fn path(&mut self, f: &'a Nnf, i: usize, l: Option<usize>) -> Literal {
+        if let Some(lit) = self.memo.get(&(f, i, l)) {
+            return lit.clone();
+        }
+
+        let lit = match f {
+            Nnf::True | Nnf::False | Nnf::Literal(..) => self.state(f, i, i),
+            Nnf::And(a, b) | Nnf::Or(a, b) => {
+                let lits = vec![self.path(a, i, l), self.path(b, i, l)];
+                self.gate(lits, matches!(f, Nnf::And(..)))
+            },
+            Nnf::Next(a) => match if i < self.bound { Some(i + 1) } else { l } {
+                Some(j) => self.path(a, j, l),
+                None => self.gate(vec![], false),
+            },
+            Nnf::Finally(a) => {
+                let lits = self.future(i, l).into_iter().map(|j| self.path(a, j, l)).collect();
+                self.gate(lits, false)
+            },
+            Nnf::Globally(a) if l.is_some() => {
+                let lits = self.future(i, l).into_iter().map(|j| self.path(a, j, l)).collect();
+                self.gate(lits, true)
+            },
+            Nnf::Globally(_) => self.gate(vec![], false),
+            Nnf::Until(a, b) => {
+                // b holds in some state, and a in every state before it
+                let future = self.future(i, l);
+                let mut cases = Vec::new();
+                for (n, &j) in future.iter().enumerate() {
+                    let mut lits = vec![self.path(b, j, l)];
+                    lits.extend(future[..n].iter().map(|&m| self.path(a, m, l)));
+                    cases.push(self.gate(lits, true));
+                }
+                self.gate(cases, false)
+            },
+            Nnf::Release(a, b) => {
+                // a holds in some state, and b up to and including it, or b holds forever
+                let future = self.future(i, l);
+                let mut cases = Vec::new();
+                if l.is_some() {
+                    let lits = future.iter().map(|&j| self.path(b, j, l)).collect();
+                    cases.push(self.gate(lits, true));
+                }
+                for (n, &j) in future.iter().enumerate() {
+                    let mut lits = vec![self.path(a, j, l)];
+                    lits.extend(future[..=n].iter().map(|&m| self.path(b, m, l)));
+                    cases.push(self.gate(lits, true));
+                }
+                self.gate(cases, false)
+            },
+        };
+
+        self.memo.insert((f, i, l), lit.clone());
+        return lit;
+    }
+}
+
+/// Searches for a run of the given [TransitionSystem] on which the given formula fails, by
+/// bounded model checking: for every number of steps `k` up to `bound`, the runs of `k + 1`
+/// states on which the negation of the formula holds are unrolled into clauses, which the
+/// [Solver] solves. Such a run either loops back from its last state to an earlier state,
+/// which makes it infinite, or is a prefix on which the formula fails however it continues.
+/// Returns the shortest such run, or `None` if there is none within the bound, in which case
+/// the formula may still fail on longer runs.
+///
+/// This assumes that every state has a next state, so that every prefix continues. Fails if
+/// the formula has primed symbols.
+///
+/// For example, a counter of two bits that starts at zero passes through every value, so
+/// `G F (a & b)` holds, and `G !(a & b)` fails after three steps.
+pub fn check(system: &TransitionSystem, property: &Ltl, bound: usize) -> Result<Option<Trace>, String> {
+    if let Some(s) = property.symbols().into_iter().find(|s| s.ends_with('\'')) {
+        return Err(format!("Next-state symbol {s} is only allowed in transition systems"));
+    }
+
+    let init = Nnf::of(&system.init, true);
+    let trans = Nnf::of(&system.trans, true);
+    let negation = Nnf::of(property, false);
+
+    let mut symbols = system.symbols();
+    symbols.extend(property.symbols());
+
+    for k in 0..=bound {
+        let mut unrolling = Unrolling::new(k);
+        let lit = unrolling.state(&init, 0, 0);
+        unrolling.clause([lit]);
+        for i in 0..k {
+            let lit = unrolling.state(&trans, i, i + 1);
+            unrolling.clause([lit]);
+        }
+
+        // Without a loop, or with a loop back to any state
+        let mut cases = vec![unrolling.path(&negation, 0, None)];
+        for l in 0..=k {
+            let lits = vec![unrolling.state(&trans, k, l), unrolling.path(&negation, 0, Some(l))];
+            cases.push(unrolling.gate(lits, true));
+        }
+        let lit = unrolling.gate(cases.clone(), false);
+        unrolling.clause([lit]);
+
+        let mut solver = Solver::new();
+        solver.add_cnf(&unrolling.cnf);
+        let Some(model) = solver.solve() else { continue };
+
+        let holds = |lit: &Literal, model: &Model| model.get(lit.atom.name()).is_some_and(|v| lit.is_satisfied_by(v));
+        let states = (0..=k)
+            .map(|i| symbols.iter().map(|s| (s.clone(), model.get(&format!("{s}@{i}")).unwrap_or(false))).collect())
+            .collect();
+        let loop_start = match holds(&cases[0], &model) {
+            true => None,
+            false => (0..=k).find(|&l| holds(&cases[l + 1], &model)),
+        };
+
+        return Ok(Some(Trace { states, loop_start }));
+    }
+
+    return Ok(None);
+}
+
+/// Runs the `bmc` command: `bmc <file> <formula>`, optionally followed by `--bound <steps>`.
+/// Checks the formula against the transition system described in the file, see [check], and
+/// prints a run on which it fails, if any.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let usage = "Usage: bmc <file> <formula> [--bound <steps>]";
+
+    let (file, formula, bound) = match args {
+        [file, formula] => (file, formula, BOUND),
+        [file, formula, flag, steps] if flag == "--bound" => {
+            let bound = steps.parse().map_err(|_| format!("Expected a number of steps, found \"{steps}\""))?;
+            (file, formula, bound)
+        },
+        _ => return Err(String::from(usage)),
+    };
+
+    let system = TransitionSystem::load(file)?;
+    let property: Ltl = formula.parse()?;
+
+    match check(&system, &property, bound)? {
+        Some(trace) => {
+            println!("Counterexample of {} states:", trace.states.len());
+            println!("{trace}");
+        },
+        None => println!("No counterexample up to {bound} steps"),
+    }
+    return Ok(());
+}
@@ -1,8 +1,189 @@
-mod cnf;
-mod stmt;
-mod parser;
-mod repl;
+#![allow(clippy::needless_return)]
+
+use std::time::Duration;
+
+use clap::Parser;
+
+use rustyproof::cnf::SaturationConfig;
+use rustyproof::kb::Format;
+use rustyproof::{encode, ltl, prove, repl, replay, saturate, smtlib};
+
+/// The subcommands, which read their own arguments, shown below the options of the REPL.
+const SUBCOMMANDS: &str = "\
+Subcommands:
+  rustyproof prove -a <axiom> ... -q <question> [--proof]
+  rustyproof encode ...
+  rustyproof replay <dir> [--update]
+  rustyproof bmc <file> <formula> [--bound <steps>]
+  rustyproof smt [<file>]
+  rustyproof saturate <file> [--checkpoint <file>] [--every <seconds>] [--strategy full|unit|input]";
+
+/// A propositional logic prover. Without arguments, it reads axioms, questions and commands
+/// that are typed in.
+#[derive(Parser)]
+#[command(name = "rustyproof", version, after_help = SUBCOMMANDS)]
+struct Cli {
+    /// A file of axioms and commands to evaluate first, or a DIMACS CNF file or SMT-LIB 2
+    /// script to load
+    #[arg(value_name = "FILE")]
+    file: Option<String>,
+
+    /// The same as FILE
+    #[arg(long = "file", value_name = "FILE", conflicts_with = "file")]
+    file_flag: Option<String>,
+
+    /// Evaluates an input after the file, as if it were typed in, and stops rather than
+    /// reading input; can be given more than once, and implies --batch
+    #[arg(short, long, value_name = "INPUT", allow_hyphen_values = true)]
+    eval: Vec<String>,
+
+    /// Shows only answers, errors, warnings and contradictions, like ':verbosity quiet'
+    #[arg(short, long, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Shows all resolved clauses after every axiom, like ':verbosity verbose'
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// The format of the file or the input: rp, dimacs or smtlib; detected if not given
+    #[arg(long, value_name = "FORMAT")]
+    format: Option<Format>,
+
+    /// A limit on resolution: clauses=<n>, inferences=<n>, length=<n> or time=<seconds>, or none instead
+    /// of a number to lift it; can be given more than once
+    #[arg(long, value_name = "LIMIT")]
+    limit: Vec<String>,
+
+    /// Reads input without interaction, and exits with 0 if all questions follow, 1 if some
+    /// do not, and 2 on syntax errors; the default when input is piped in
+    #[arg(long)]
+    batch: bool,
+
+    /// Leaves out the banner
+    #[arg(long)]
+    no_banner: bool,
+
+    /// Leaves out colors, which are also left out when output is not a terminal
+    #[arg(long)]
+    no_color: bool
+}
+
+/// Sets a limit on resolution, given as `clauses=<n>`, `inferences=<n>`, `length=<n>` or
+/// `time=<seconds>`, where `none` lifts the limit.
+fn set_limit(config: &mut SaturationConfig, spec: &str) -> Result<(), String> {
+    let Some((name, value)) = spec.split_once('=') else {
+        return Err(format!("Expected a limit like clauses=10000 or time=5, found '{spec}'"));
+    };
+
+    let count = |unit: &str| -> Result<Option<usize>, String> {
+        if value == "none" {
+            return Ok(None);
+        }
+        return value.parse().map(Some).map_err(|_| format!("Expected a number of {unit}, found '{value}'"));
+    };
+
+    match name {
+        "clauses" => config.max_clauses = count("clauses")?,
+        "inferences" => config.max_inferences = count("inferences")?,
+        "length" => config.max_length = count("literals")?,
+        "time" if value == "none" => config.max_time = None,
+        "time" => match value.parse::<f64>() {
+            Ok(secs) if secs > 0.0 => config.max_time = Some(Duration::from_secs_f64(secs)),
+            _ => return Err(format!("Expected a number of seconds, found '{value}'")),
+        },
+        _ => return Err(format!("Expected a limit on clauses, inferences, length or time, found '{name}'")),
+    }
+    return Ok(());
+}
 
 fn main() {
-    repl::repl();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if args.first().map(String::as_str) == Some("encode") {
+        if let Err(msg) = encode::run(&args[1..]) {
+            eprintln!("Error! {msg}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.first().map(String::as_str) == Some("replay") {
+        if let Err(msg) = replay::run(&args[1..]) {
+            eprintln!("Error! {msg}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.first().map(String::as_str) == Some("saturate") {
+        if let Err(msg) = saturate::run(&args[1..]) {
+            eprintln!("Error! {msg}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.first().map(String::as_str) == Some("bmc") {
+        if let Err(msg) = ltl::run(&args[1..]) {
+            eprintln!("Error! {msg}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.first().map(String::as_str) == Some("smt") {
+        if let Err(msg) = smtlib::run(&args[1..]) {
+            eprintln!("Error! {msg}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // The answer is the exit status, so that scripts can test it: 0 if the question follows
+    // from the axioms, 1 if it does not, and 2 if the arguments are not valid.
+    if args.first().map(String::as_str) == Some("prove") {
+        match prove::run(&args[1..]) {
+            Ok(true) => return,
+            Ok(false) => std::process::exit(1),
+            Err(msg) => {
+                eprintln!("Error! {msg}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let cli = Cli::parse_from(std::iter::once(String::from("rustyproof")).chain(args));
+
+    let mut options = repl::Options::new();
+    options.banner = !cli.no_banner;
+    options.color &= !cli.no_color;
+    if cli.quiet {
+        options.verbosity = repl::Verbosity::Quiet;
+    }
+    if cli.verbose {
+        options.verbosity = repl::Verbosity::Verbose;
+    }
+    options.batch |= cli.batch || !cli.eval.is_empty();
+    options.format = cli.format;
+    for limit in cli.limit.iter() {
+        if let Err(msg) = set_limit(&mut options.limits, limit) {
+            eprintln!("Error! {msg}");
+            std::process::exit(2);
+        }
+    }
+    options.eval = cli.eval;
+
+    let file = cli.file.or(cli.file_flag);
+    let file = file.as_deref();
+
+    // In batch mode, the outcome is the exit status, so that scripts can test it: 0 if all
+    // questions follow from the axioms, 1 if some do not, and 2 if the input is not valid
+    match repl::repl(file, &options) {
+        Ok(outcome) if options.batch => std::process::exit(outcome.exit_code()),
+        Ok(_) => {},
+        Err(msg) => {
+            eprintln!("Error! {msg}");
+            std::process::exit(if options.batch { 2 } else { 1 });
+        }
+    }
 }
@@ -0,0 +1,236 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::cnf::{Cnf, Disj};
+use crate::dnf::{Conj, Dnf};
+use crate::literal::Literal;
+use crate::stmt::Stmt;
+
+/// The maximum number of symbols of a statement that can be minimized. Minimization goes
+/// through all assignments of the symbols, of which there are exponentially many.
+pub const MAX_SYMBOLS: usize = 12;
+
+/// An implicant: a conjunction of literals, as a bit pattern over the symbols, along with a
+/// mask of the symbols that do not occur in it. Symbol `i` occurs positively if bit `i` of
+/// the pattern is set, and negatively otherwise.
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+struct Implicant {
+    bits: u32,
+    mask: u32
+}
+
+impl Implicant {
+    /// Tests whether this implicant covers the given assignment.
+    fn covers(&self, m: u32) -> bool {
+        return m & !self.mask == self.bits;
+    }
+
+    /// Returns the number of literals of this implicant.
+    fn literals(&self, n: usize) -> usize {
+        return n - self.mask.count_ones() as usize;
+    }
+
+    fn conj(&self, symbols: &[String]) -> Conj {
+        let mut pos = HashSet::new();
+        let mut neg = HashSet::new();
+
+        for (i, s) in symbols.iter().enumerate() {
+            if self.mask >> i & 1 == 1 {
+                continue;
+            }
+            if self.bits >> i & 1 == 1 {
+                pos.insert(s.clone());
+            } else {
+                neg.insert(s.clone());
+            }
+        }
+
+        return Conj::new(pos, neg);
+    }
+
+    /// Returns the clause that is false exactly where this implicant is true.
+    fn negated(&self, symbols: &[String]) -> Disj {
+        let conj = self.conj(symbols);
+        let pos = conj.neg().iter().map(|s| Literal::pos(s));
+        let neg = conj.pos().iter().map(|s| Literal::neg(s));
+        return Disj::of_literals(pos.chain(neg)).unwrap();
+    }
+}
+
+/// Evaluates a statement under the assignment `m`, where symbol `i` is true if bit `i` is set.
+fn eval(stmt: &Stmt, symbols: &[String], m: u32) -> bool {
+    return match stmt {
+        Stmt::Cont => false,
+        Stmt::Taut => true,
+        Stmt::Symbol(s) => {
+            let i = symbols.iter().position(|t| t == s).unwrap();
+            m >> i & 1 == 1
+        },
+        Stmt::Necessarily(_) | Stmt::Possibly(_) => {
+            let i = symbols.iter().position(|t| *t == stmt.to_string()).unwrap();
+            m >> i & 1 == 1
+        },
+        Stmt::Not(o) => !eval(o, symbols, m),
+        Stmt::And(v) => v.iter().all(|s| eval(s, symbols, m)),
+        Stmt::Or(v) => v.iter().any(|s| eval(s, symbols, m)),
+        Stmt::Implies(l, r) => !eval(l, symbols, m) || eval(r, symbols, m),
+        Stmt::Equiv(l, r) => eval(l, symbols, m) == eval(r, symbols, m),
+        Stmt::Xor(l, r) => eval(l, symbols, m) != eval(r, symbols, m),
+        Stmt::Ite(c, t, e) => if eval(c, symbols, m) { eval(t, symbols, m) } else { eval(e, symbols, m) },
+        Stmt::Forall(..) | Stmt::Exists(..) => eval(&stmt.expand(), symbols, m),
+    };
+}
+
+/// Returns the symbols of a statement, failing if there are too many to minimize.
+fn symbols_of(stmt: &Stmt) -> Result<Vec<String>, String> {
+    return check(stmt.symbols());
+}
+
+/// Returns the symbols of the given clauses, failing if there are too many to minimize.
+fn symbols_of_cnf(cnf: &Cnf) -> Result<Vec<String>, String> {
+    return check(cnf.symbols());
+}
+
+fn check(symbols: BTreeSet<String>) -> Result<Vec<String>, String> {
+    if symbols.len() > MAX_SYMBOLS {
+        return Err(format!("Cannot minimize more than {MAX_SYMBOLS} symbols, found {}", symbols.len()));
+    }
+    return Ok(symbols.into_iter().collect());
+}
+
+/// Finds all prime implicants of the given minterms by the Quine-McCluskey method: minterms
+/// are merged pairwise into implicants that differ in one symbol, until no more implicants
+/// merge. The implicants that never merged are the prime implicants.
+fn primes(minterms: &[u32]) -> Vec<Implicant> {
+    let mut current: HashSet<Implicant> = minterms.iter().map(|m| Implicant { bits: *m, mask: 0 }).collect();
+    let mut primes = Vec::new();
+
+    while !current.is_empty() {
+        let list = Vec::from_iter(current.iter().copied());
+        let mut merged = HashSet::new();
+        let mut next = HashSet::new();
+
+        for i in 0..list.len() {
+            for j in (i + 1)..list.len() {
+                let (a, b) = (list[i], list[j]);
+                let diff = a.bits ^ b.bits;
+                if a.mask == b.mask && diff.count_ones() == 1 {
+                    next.insert(Implicant { bits: a.bits & !diff, mask: a.mask | diff });
+                    merged.insert(a);
+                    merged.insert(b);
+                }
+            }
+        }
+
+        primes.extend(list.into_iter().filter(|p| !merged.contains(p)));
+        current = next;
+    }
+
+    primes.sort_by_key(|p| (p.mask, p.bits));
+    return primes;
+}
+
+/// Finds a smallest set of prime implicants covering all minterms: with the fewest
+/// implicants, and among those, the fewest literals. Essential prime implicants are picked
+/// first, after which the remaining minterms are covered by branch and bound.
+fn cover(primes: &[Implicant], minterms: &[u32], n: usize) -> Vec<Implicant> {
+    let mut picked = Vec::new();
+    let mut left: Vec<u32> = minterms.to_vec();
+
+    // Essential prime implicants are the only ones to cover some minterm
+    for m in minterms.iter() {
+        let covering: Vec<&Implicant> = primes.iter().filter(|p| p.covers(*m)).collect();
+        if covering.len() == 1 && !picked.contains(covering[0]) {
+            picked.push(*covering[0]);
+        }
+    }
+    left.retain(|m| !picked.iter().any(|p| p.covers(*m)));
+
+    let mut best: Option<Vec<Implicant>> = None;
+    search(primes, &left, &mut Vec::new(), &mut best, n);
+
+    picked.extend(best.unwrap_or_default());
+    return picked;
+}
+
+/// Covers the given minterms with as few implicants as possible, branching on the minterm
+/// that is covered by the fewest implicants.
+fn search(primes: &[Implicant], left: &[u32], picked: &mut Vec<Implicant>, best: &mut Option<Vec<Implicant>>, n: usize) {
+    let cost = |c: &[Implicant]| (c.len(), c.iter().map(|p| p.literals(n)).sum::<usize>());
+
+    if let Some(b) = best {
+        // Another implicant is needed if minterms are left, so this cannot become better
+        if !left.is_empty() && picked.len() + 1 > b.len() {
+            return;
+        }
+        if cost(picked) >= cost(b) {
+            return;
+        }
+    }
+
+    let m = match left.iter().min_by_key(|m| primes.iter().filter(|p| p.covers(**m)).count()) {
+        Some(m) => *m,
+        None => {
+            *best = Some(picked.clone());
+            return;
+        }
+    };
+
+    for p in primes.iter().filter(|p| p.covers(m)) {
+        let rest: Vec<u32> = left.iter().copied().filter(|m| !p.covers(*m)).collect();
+        picked.push(*p);
+        search(primes, &rest, picked, best, n);
+        picked.pop();
+    }
+}
+
+/// Finds a minimal cover of the assignments for which `f` holds.
+fn minimal(symbols: &[String], f: impl Fn(u32) -> bool) -> Vec<Implicant> {
+    let minterms: Vec<u32> = (0..1u32 << symbols.len()).filter(|m| f(*m)).collect();
+    return cover(&primes(&minterms), &minterms, symbols.len());
+}
+
+/// Finds all prime implicants of the given statement: the conjunctions of literals that imply
+/// the statement, of which no literal can be left out. Fails if the statement has more than
+/// [MAX_SYMBOLS] symbols.
+pub fn prime_implicants(stmt: &Stmt) -> Result<Dnf, String> {
+    let symbols = symbols_of(stmt)?;
+    let minterms: Vec<u32> = (0..1u32 << symbols.len()).filter(|m| eval(stmt, &symbols, *m)).collect();
+
+    return Ok(Dnf::of_vec(&primes(&minterms).iter().map(|p| p.conj(&symbols)).collect::<Vec<_>>()));
+}
+
+/// Finds a minimal sum of products of the given statement: an equivalent statement in
+/// disjunctive normal form, with as few terms as possible, and among those, as few literals
+/// as possible. Fails if the statement has more than [MAX_SYMBOLS] symbols.
+///
+/// For example, `(A & B) | (A & !B) | (B & C)` minimizes to `A | (B & C)`.
+pub fn minimal_dnf(stmt: &Stmt) -> Result<Dnf, String> {
+    let symbols = symbols_of(stmt)?;
+    let cover = minimal(&symbols, |m| eval(stmt, &symbols, m));
+
+    return Ok(Dnf::of_vec(&cover.iter().map(|p| p.conj(&symbols)).collect::<Vec<_>>()));
+}
+
+/// Finds a minimal product of sums of the given statement: an equivalent set of clauses, as
+/// few as possible, and among those, with as few literals as possible. This is the minimal
+/// sum of products of the negated statement, negated. Fails if the statement has more than
+/// [MAX_SYMBOLS] symbols.
+pub fn minimal_cnf(stmt: &Stmt) -> Result<Cnf, String> {
+    let symbols = symbols_of(stmt)?;
+    let cover = minimal(&symbols, |m| !eval(stmt, &symbols, m));
+
+    return Ok(Cnf::of_vec(&cover.iter().map(|p| p.negated(&symbols)).collect::<Vec<_>>()));
+}
+
+/// Finds a minimal set of clauses that is equivalent to the given clauses, see
+/// [minimal_cnf]. Fails if the clauses have more than [MAX_SYMBOLS] symbols.
+pub fn minimize_cnf(cnf: &Cnf) -> Result<Cnf, String> {
+    let symbols = symbols_of_cnf(cnf)?;
+    let holds = |m: u32| {
+        let value = |s: &str| m >> symbols.iter().position(|t| t == s).unwrap() & 1 == 1;
+        return cnf.terms.iter().all(|d| d.literals().iter().any(|l| value(l.atom.name()) == l.polarity));
+    };
+    let cover = minimal(&symbols, |m| !holds(m));
+
+    return Ok(Cnf::of_vec(&cover.iter().map(|p| p.negated(&symbols)).collect::<Vec<_>>()));
+}
@@ -0,0 +1,264 @@
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::stmt::Stmt;
+
+/// A normal modal logic, given by the conditions on the accessibility relation between
+/// worlds of its frames. Each system proves everything the systems before it prove.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum System {
+    /// Any relation.
+    K,
+
+    /// Reflexive relations: every world is accessible from itself, so `[]A -> A`.
+    T,
+
+    /// Reflexive and transitive relations, so also `[]A -> [][]A`.
+    S4,
+
+    /// Equivalence relations, so also `<>A -> []<>A`.
+    S5
+}
+
+impl System {
+    pub fn is_reflexive(&self) -> bool {
+        return *self != System::K;
+    }
+
+    pub fn is_transitive(&self) -> bool {
+        return matches!(self, System::S4 | System::S5);
+    }
+
+    pub fn is_symmetric(&self) -> bool {
+        return *self == System::S5;
+    }
+}
+
+impl Display for System {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            System::K => write!(f, "K"),
+            System::T => write!(f, "T"),
+            System::S4 => write!(f, "S4"),
+            System::S5 => write!(f, "S5"),
+        };
+    }
+}
+
+impl FromStr for System {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<System, String> {
+        return match s {
+            "K" => Ok(System::K),
+            "T" => Ok(System::T),
+            "S4" => Ok(System::S4),
+            "S5" => Ok(System::S5),
+            _ => Err(format!("Expected modal logic K, T, S4 or S5, found \"{s}\"")),
+        };
+    }
+}
+
+/// A modal statement in negation normal form, as the tableau expands it.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+enum Nnf {
+    False,
+    True,
+    Literal(String, bool),
+    And(Vec<Nnf>),
+    Or(Vec<Nnf>),
+    Necessarily(Box<Nnf>),
+    Possibly(Box<Nnf>)
+}
+
+impl Nnf {
+    /// Converts a statement, or its negation if `polarity` is false, to negation normal form.
+    /// Fails on quantifiers, which are not part of modal logic.
+    fn of(stmt: &Stmt, polarity: bool) -> Result<Nnf, String> {
+        let both = |l: &Stmt, lp: bool, r: &Stmt, rp: bool| -> Result<Nnf, String> {
+            return Ok(Nnf::And(vec![Nnf::of(l, lp)?, Nnf::of(r, rp)?]));
+        };
+
+        return Ok(match stmt {
+            Stmt::Cont => if polarity { Nnf::False } else { Nnf::True },
+            Stmt::Taut => if polarity { Nnf::True } else { Nnf::False },
+            Stmt::Symbol(s) => Nnf::Literal(s.clone(), polarity),
+            Stmt::Not(o) => Nnf::of(o, !polarity)?,
+            Stmt::And(v) | Stmt::Or(v) => {
+                let v = v.iter().map(|o| Nnf::of(o, polarity)).collect::<Result<_, _>>()?;
+                if matches!(stmt, Stmt::And(_)) == polarity { Nnf::And(v) } else { Nnf::Or(v) }
+            },
+            Stmt::Implies(l, r) if polarity => Nnf::Or(vec![Nnf::of(l, false)?, Nnf::of(r, true)?]),
+            Stmt::Implies(l, r) => both(l, true, r, false)?,
+            Stmt::Equiv(l, r) | Stmt::Xor(l, r) => {
+                let same = matches!(stmt, Stmt::Equiv(..)) == polarity;
+                Nnf::Or(vec![both(l, true, r, same)?, both(l, false, r, !same)?])
+            },
+            Stmt::Ite(c, t, e) => Nnf::Or(vec![
+                Nnf::And(vec![Nnf::of(c, true)?, Nnf::of(t, polarity)?]),
+                Nnf::And(vec![Nnf::of(c, false)?, Nnf::of(e, polarity)?])
+            ]),
+            Stmt::Forall(..) | Stmt::Exists(..) => return Err(format!("Quantifiers are not supported in modal logic, found {stmt}")),
+            Stmt::Necessarily(o) if polarity => Nnf::Necessarily(Box::new(Nnf::of(o, true)?)),
+            Stmt::Necessarily(o) => Nnf::Possibly(Box::new(Nnf::of(o, false)?)),
+            Stmt::Possibly(o) if polarity => Nnf::Possibly(Box::new(Nnf::of(o, true)?)),
+            Stmt::Possibly(o) => Nnf::Necessarily(Box::new(Nnf::of(o, false)?)),
+        });
+    }
+}
+
+/// Tests whether the given statement is true in some world of some model of the given
+/// [System], by a tableau: the statement is expanded into the worlds of a model, branching on
+/// disjunctions, until every branch closes on a contradiction, or one branch stays open, from
+/// which a model can be read.
+///
+/// For K, T and S4, every `<>A` in a world starts a new world with `A` and the `B` of every
+/// `[]B`, where S4 also carries over `[]B` itself, as worlds accessible from there are
+/// accessible from here. In S4, a world whose statements are all in a world above it is not
+/// expanded, since it can loop back to that world, which makes the tableau terminate. For S5,
+/// every world is accessible from every world, so `[]B` holds in all worlds, and `<>A` is
+/// only given a new world if no world has `A` yet.
+///
+/// Fails on quantifiers, which are not part of modal logic.
+pub fn is_satisfiable(stmt: &Stmt, system: System) -> Result<bool, String> {
+    let nnf = Nnf::of(stmt, true)?;
+    if system.is_symmetric() {
+        return Ok(Cluster { worlds: vec![(BTreeSet::new(), vec![nnf])], boxes: Vec::new() }.expand());
+    }
+    return Ok(is_open(system, BTreeSet::new(), vec![nnf], &mut Vec::new()));
+}
+
+/// Tests whether the given statement is true in every world of every model of the given
+/// [System], which is the case when its negation is not satisfiable, see [is_satisfiable].
+///
+/// For example, `[]A -> A` is valid in T, S4 and S5, but not in K.
+pub fn is_valid(stmt: &Stmt, system: System) -> Result<bool, String> {
+    return Ok(!is_satisfiable(&stmt.clone().not(), system)?);
+}
+
+/// Expands the statements of a world in a model of K, T or S4, along with the worlds that
+/// are accessible from it. Returns whether some branch stays open.
+///
+/// `world` holds the statements expanded so far, and `todo` those still to be expanded.
+/// `above` holds the expanded statements of the worlds from which this world is accessible,
+/// which S4 uses to detect loops.
+fn is_open(system: System, mut world: BTreeSet<Nnf>, mut todo: Vec<Nnf>, above: &mut Vec<BTreeSet<Nnf>>) -> bool {
+    while let Some(f) = todo.pop() {
+        if world.contains(&f) {
+            continue;
+        }
+        world.insert(f.clone());
+
+        match f {
+            Nnf::False => return false,
+            Nnf::Literal(s, p) if world.contains(&Nnf::Literal(s.clone(), !p)) => return false,
+            Nnf::And(v) => todo.extend(v),
+            Nnf::Or(v) => {
+                return v.into_iter().any(|d| {
+                    let mut todo = todo.clone();
+                    todo.push(d);
+                    is_open(system, world.clone(), todo, above)
+                });
+            },
+            Nnf::Necessarily(o) if system.is_reflexive() => todo.push(*o),
+            _ => {}
+        }
+    }
+
+    let mut carried = Vec::new();
+    for f in world.iter() {
+        if let Nnf::Necessarily(o) = f {
+            carried.push((**o).clone());
+            if system.is_transitive() {
+                carried.push(f.clone());
+            }
+        }
+    }
+
+    for f in world.iter() {
+        let Nnf::Possibly(o) = f else { continue };
+
+        let mut todo = carried.clone();
+        todo.push((**o).clone());
+
+        // In S4, a world that is accessible from here can reach every world that this one can
+        let looped = system.is_transitive() && above.iter().chain([&world]).any(|w| todo.iter().all(|f| w.contains(f)));
+        if looped {
+            continue;
+        }
+
+        above.push(world.clone());
+        let open = is_open(system, BTreeSet::new(), todo, above);
+        above.pop();
+        if !open {
+            return false;
+        }
+    }
+
+    return true;
+}
+
+/// The worlds of a model of S5, in which every world is accessible from every world.
+#[derive(Clone)]
+struct Cluster {
+    /// Every world, with the statements expanded so far and those still to be expanded.
+    worlds: Vec<(BTreeSet<Nnf>, Vec<Nnf>)>,
+
+    /// The statements `B` of every `[]B` found so far, which hold in every world.
+    boxes: Vec<Nnf>
+}
+
+impl Cluster {
+    /// Expands the statements of all worlds, adding worlds as needed. Returns whether some
+    /// branch stays open.
+    fn expand(mut self) -> bool {
+        loop {
+            let Some(i) = self.worlds.iter().position(|(_, todo)| !todo.is_empty()) else {
+                // Every world is expanded, so only possibilities without a world are left
+                let missing = self.worlds.iter()
+                    .flat_map(|(w, _)| w.iter())
+                    .filter_map(|f| if let Nnf::Possibly(o) = f { Some(&**o) } else { None })
+                    .find(|o| !self.worlds.iter().any(|(w, _)| w.contains(o)))
+                    .cloned();
+
+                match missing {
+                    Some(o) => {
+                        let mut todo = self.boxes.clone();
+                        todo.push(o);
+                        self.worlds.push((BTreeSet::new(), todo));
+                        continue;
+                    },
+                    None => return true,
+                }
+            };
+
+            let f = self.worlds[i].1.pop().unwrap();
+            let world = &mut self.worlds[i].0;
+            if world.contains(&f) {
+                continue;
+            }
+            world.insert(f.clone());
+
+            match f {
+                Nnf::False => return false,
+                Nnf::Literal(s, p) if world.contains(&Nnf::Literal(s.clone(), !p)) => return false,
+                Nnf::And(v) => self.worlds[i].1.extend(v),
+                Nnf::Or(v) => {
+                    return v.into_iter().any(|d| {
+                        let mut branch = self.clone();
+                        branch.worlds[i].1.push(d);
+                        branch.expand()
+                    });
+                },
+                Nnf::Necessarily(o) if !self.boxes.contains(&o) => {
+                    self.boxes.push((*o).clone());
+                    for (_, todo) in self.worlds.iter_mut() {
+                        todo.push((*o).clone());
+                    }
+                },
+                _ => {}
+            }
+        }
+    }
+}
@@ -1,10 +1,97 @@
+use std::fmt::Display;
+
+#[cfg(feature = "fol")]
+use crate::fol::{Formula, Term};
 use crate::stmt::Stmt;
 use crate::parser::ParseResult::*;
 
+/// A syntax error, found by [parse_stmt] or [parse_statement].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+
+    /// The index of the character in the input where the error was found.
+    pub position: usize
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{} at position {}", self.message, self.position);
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A statement parsed by [parse_statement].
+pub enum Statement {
+    /// An axiom: `A`.
+    Axiom(Stmt),
+
+    /// A question: `A?`.
+    Question(Stmt),
+
+    /// An empty input.
+    Stop
+}
+
+/// Parses an expression, like `A & B -> C`.
+pub fn parse_stmt(input: &str) -> Result<Stmt, ParseError> {
+    let mut parser = Parser::new(input.to_string());
+
+    let stmt = match parser.conditional() {
+        Ok(s) => s,
+        Absent(idx) => return Err(ParseError { message: String::from("Expected expression"), position: idx }),
+        Error(msg, idx) => return Err(ParseError { message: msg, position: idx })
+    };
+
+    parser.ws();
+    if parser.cur().is_some() {
+        return Err(ParseError { message: String::from("Expected end"), position: parser.index });
+    }
+
+    return Result::Ok(stmt);
+}
+
+/// Splits an expression into the tokens the parser reads: names, operators like `&` and `<->`,
+/// the constants `*` and `~`, parentheses and other punctuation. Keywords like `xor` and `ite`
+/// are names as far as tokens are concerned. Fails at the first character that cannot start
+/// a token.
+pub fn tokenize(input: &str) -> Result<Vec<String>, ParseError> {
+    let mut parser = Parser::new(input.to_string());
+    let mut tokens = Vec::new();
+
+    loop {
+        match parser.token() {
+            Ok(token) => tokens.push(token),
+            Absent(_) => return Result::Ok(tokens),
+            Error(msg, idx) => return Err(ParseError { message: msg, position: idx })
+        }
+    }
+}
+
+/// Parses an axiom `A`, a question `A?`, or an empty input. Unlike the REPL, this does not
+/// accept named or tagged axioms, declarations or first-order formulas.
+pub fn parse_statement(input: &str) -> Result<Statement, ParseError> {
+    return match Parser::new(input.to_string()).expr() {
+        ParsedStatement::Axiom(s) => Result::Ok(Statement::Axiom(s)),
+        ParsedStatement::Question(s) => Result::Ok(Statement::Question(s)),
+        ParsedStatement::Stop => Result::Ok(Statement::Stop),
+        ParsedStatement::Error(msg, idx) => Err(ParseError { message: msg, position: idx }),
+        _ => Err(ParseError { message: String::from("Expected axiom or question"), position: 0 }),
+    };
+}
+
 /// Recursive-descent parser for [Stmt]s.
 pub struct Parser {
     index: usize,
     input: Vec<char>,
+
+    /// The variables bound by the quantifiers currently being parsed.
+    #[cfg(feature = "fol")]
+    bound: Vec<String>,
+
+    /// The errors found so far, when recovering from errors. See [Parser::with_recovery].
+    errors: Option<Vec<(String, usize)>>,
 }
 
 enum ParseResult<T> {
@@ -28,8 +115,31 @@ pub enum ParsedStatement {
     /// Stop signal: end the REPL.
     Stop,
 
+    /// A first-order formula was parsed: it is to be clausified.
+    #[cfg(feature = "fol")]
+    FirstOrder(Formula),
+
+    /// A named or tagged axiom was parsed: `@tag name: A`. Like [Self::Axiom], but the axiom
+    /// can be referred to by its name, and toggled along with other axioms by its tags.
+    Labelled(Option<String>, Vec<String>, Stmt),
+
+    /// A conjecture was parsed: `conjecture: A`. Like a question, but it is answered at the end
+    /// of the input, when all axioms are known.
+    Conjecture(Stmt),
+
+    /// A chain of proof obligations was parsed: `derive A => B => C`. Every step must follow
+    /// from the knowledge base along with the previous step.
+    Derive(Vec<Stmt>),
+
+    /// An enumeration was declared: exactly one of its values must be assumed to be true.
+    Enum(String, Vec<String>),
+
     /// Some error was found in the input
-    Error(String, usize)
+    Error(String, usize),
+
+    /// Multiple errors were found in the input, when recovering from errors. See
+    /// [Parser::with_recovery].
+    Errors(Vec<(String, usize)>)
 }
 
 impl<T> ParseResult<T> {
@@ -39,19 +149,27 @@ impl<T> ParseResult<T> {
             r => r
         };
     }
-
-    fn is_not_present(&self) -> bool {
-        return match self {
-            Ok(_) => false,
-            Absent(_) => true,
-            Error(_, _) => true,
-        }
-    }
 }
 
 impl Parser {
     pub fn new(line: String) -> Parser {
-        return Parser { index: 0, input: Vec::from_iter(line.chars()) };
+        return Parser {
+            index: 0,
+            input: Vec::from_iter(line.chars()),
+            #[cfg(feature = "fol")]
+            bound: Vec::new(),
+            errors: None
+        };
+    }
+
+    /// Creates a parser that does not stop at the first syntax error in an expression, but
+    /// skips to the next operator or parenthesis and continues, so that all errors are found
+    /// at once. When more than one error is found, they are returned as
+    /// [ParsedStatement::Errors].
+    pub fn with_recovery(line: String) -> Parser {
+        let mut parser = Self::new(line);
+        parser.errors = Some(Vec::new());
+        return parser;
     }
 
     fn has(&self, c: char) -> bool {
@@ -78,6 +196,16 @@ impl Parser {
         self.index += 1;
     }
 
+    /// Tests whether the given character can start a name.
+    fn is_name_start(c: char) -> bool {
+        return c.is_ascii_alphabetic() || c == '_';
+    }
+
+    /// Tests whether the given character can continue a name.
+    fn is_name_part(c: char) -> bool {
+        return c.is_ascii_alphanumeric() || c == '_';
+    }
+
     /// Skips over whitespace.
     fn ws(&mut self) {
         while self.has(' ') || self.has('\t') || self.has('\n') || self.has('\r') {
@@ -85,15 +213,154 @@ impl Parser {
         }
     }
 
+    /// Skips over the given keyword, if present. The keyword must not be followed by
+    /// another name character, i.e. `enum` is a keyword in `enum x` but not in `enums`.
+    fn keyword(&mut self, kw: &str) -> bool {
+        self.ws();
+
+        let start = self.index;
+        for c in kw.chars() {
+            if !self.has(c) {
+                self.index = start;
+                return false;
+            }
+            self.shift();
+        }
+
+        if let Some(cur) = self.cur() {
+            if Self::is_name_part(cur) || cur == '.' {
+                self.index = start;
+                return false;
+            }
+        }
+
+        return true;
+    }
+
     /// Reads an expression.
     pub fn expr(&mut self) -> ParsedStatement {
-        return match self.or() {
+        let result = self.line();
+
+        let mut errors = match self.errors.take() {
+            Some(errors) if !errors.is_empty() => errors,
+            _ => return result,
+        };
+
+        if let ParsedStatement::Error(msg, idx) = result {
+            errors.push((msg, idx));
+        }
+
+        if errors.len() == 1 {
+            let (msg, idx) = errors.remove(0);
+            return ParsedStatement::Error(msg, idx);
+        }
+
+        errors.sort_by_key(|(_, idx)| *idx);
+        return ParsedStatement::Errors(errors);
+    }
+
+    /// Reads a declaration or statement.
+    fn line(&mut self) -> ParsedStatement {
+        if self.keyword("enum") {
+            return self.enumeration();
+        }
+
+        if self.keyword("derive") {
+            return self.derivation();
+        }
+
+        let start = self.index;
+
+        if self.keyword("conjecture") {
+            self.ws();
+            if self.has(':') {
+                self.shift();
+                return self.conjecture();
+            }
+            self.index = start;
+        }
+
+        let tags = match self.tags() {
+            Ok(tags) => tags,
+            Absent(_) => Vec::new(),
+            Error(msg, idx) => return ParsedStatement::Error(msg, idx)
+        };
+        let label = self.label();
+
+        if label.is_some() || !tags.is_empty() {
+            self.ws();
+            if self.cur().is_none() {
+                return ParsedStatement::Error(String::from("Expected axiom"), self.index);
+            }
+
+            return match self.statement() {
+                ParsedStatement::Axiom(s) => ParsedStatement::Labelled(label, tags, s),
+                ParsedStatement::Stop => ParsedStatement::Error(String::from("Expected axiom"), self.index),
+                ParsedStatement::Error(msg, idx) => ParsedStatement::Error(msg, idx),
+                _ => ParsedStatement::Error(String::from("Only axioms can be named or tagged"), start),
+            };
+        }
+
+        return self.statement();
+    }
+
+    /// Parses the tags of an axiom, if present: `@a @b` for any names a, b
+    fn tags(&mut self) -> ParseResult<Vec<String>> {
+        let mut tags = Vec::new();
+
+        loop {
+            self.ws();
+            if !self.has('@') {
+                break;
+            }
+            self.shift();
+
+            match self.name() {
+                Ok(tag) if !tags.contains(&tag) => tags.push(tag),
+                Ok(_) => {},
+                Absent(idx) => return Error(String::from("Expected tag name"), idx),
+                Error(msg, idx) => return Error(msg, idx)
+            }
+        }
+
+        return if tags.is_empty() {
+            Absent(self.index)
+        } else {
+            Ok(tags)
+        };
+    }
+
+    /// Parses the label of a named axiom, if present: `name:` for any name
+    fn label(&mut self) -> Option<String> {
+        let start = self.index;
+
+        if let Ok(name) = self.name() {
+            self.ws();
+            if self.has(':') {
+                self.shift();
+                return Some(name);
+            }
+        }
+
+        self.index = start;
+        return None;
+    }
+
+    /// Reads an axiom, question or first-order formula.
+    fn statement(&mut self) -> ParsedStatement {
+        #[cfg(feature = "fol")]
+        if self.is_first_order() {
+            return self.first_order();
+        }
+
+        return match self.conditional() {
             Ok(s) => {
+                self.resume('?');
                 self.ws();
                 match self.cur() {
                     Some('?') => {
                         self.shift();
-                        if let Some(_) = self.cur() {
+                        if self.cur().is_some() {
                             return ParsedStatement::Error(String::from("Expected end"), self.index)
                         }
 
@@ -112,35 +379,197 @@ impl Parser {
         }
     }
 
-    /// Parses a symbol: `P` for any character P
-    fn symbol(&mut self) -> ParseResult<Stmt> {
+    /// Parses a conjecture, after `conjecture:`: `a` for any expression a
+    fn conjecture(&mut self) -> ParsedStatement {
+        let start = self.index;
+
         self.ws();
-        if let Some(cur) = self.cur() {
-            if cur >= 'A' && cur <= 'Z' || cur >= 'a' && cur <= 'z' {
-                self.shift();
-                return Ok(Stmt::symbol(cur));
+        if self.cur().is_none() {
+            return ParsedStatement::Error(String::from("Expected conjecture"), self.index);
+        }
+
+        return match self.statement() {
+            ParsedStatement::Axiom(s) => ParsedStatement::Conjecture(s),
+            ParsedStatement::Error(msg, idx) => ParsedStatement::Error(msg, idx),
+            _ => ParsedStatement::Error(String::from("Expected expression as conjecture"), start),
+        };
+    }
+
+    /// Parses a chain of proof obligations, after the `derive` keyword: `a => b => c` for any
+    /// expressions a, b, c
+    fn derivation(&mut self) -> ParsedStatement {
+        let mut steps = Vec::new();
+
+        loop {
+            match self.conditional() {
+                Ok(s) => steps.push(s),
+                Absent(idx) => return ParsedStatement::Error(String::from("Expected expression"), idx),
+                Error(msg, idx) => return ParsedStatement::Error(msg, idx)
+            }
+
+            self.ws();
+            if self.cur().is_none() {
+                break;
+            }
+
+            if !self.has('=') || self.off(1) != Some('>') {
+                return ParsedStatement::Error(String::from("Expected '=>' or end"), self.index);
             }
+            self.shift();
+            self.shift();
         }
-        
-        return Absent(self.index);
+
+        if steps.len() < 2 {
+            return ParsedStatement::Error(String::from("Expected '=>'"), self.index);
+        }
+
+        return ParsedStatement::Derive(steps);
     }
 
-    /// Parses a not expression: `!x` for any atomic expression x
-    fn not(&mut self) -> ParseResult<Stmt> {
+    /// Parses an enumeration declaration, after the `enum` keyword: `e in {a, b, c}` for any names e, a, b, c
+    fn enumeration(&mut self) -> ParsedStatement {
+        let name = match self.name() {
+            Ok(s) => s,
+            _ => return ParsedStatement::Error(String::from("Expected enum name"), self.index)
+        };
+
+        if !self.keyword("in") {
+            return ParsedStatement::Error(String::from("Expected 'in'"), self.index);
+        }
+
         self.ws();
 
-        if let Some(cur) = self.cur() {
-            if cur != '!' {
-                return Absent(self.index);
+        if !self.has('{') {
+            return ParsedStatement::Error(String::from("Expected '{'"), self.index);
+        }
+        self.shift();
+
+        let mut values = Vec::new();
+        loop {
+            self.ws();
+
+            let start = self.index;
+            let value = match self.name() {
+                Ok(s) => s,
+                _ => return ParsedStatement::Error(String::from("Expected value"), self.index)
+            };
+
+            if values.contains(&value) {
+                return ParsedStatement::Error(String::from("Duplicate value"), start);
+            }
+            values.push(value);
+
+            self.ws();
+            match self.cur() {
+                Some(',') => self.shift(),
+                Some('}') => {
+                    self.shift();
+                    break;
+                },
+                _ => return ParsedStatement::Error(String::from("Expected ',' or '}'"), self.index)
+            }
+        }
+
+        self.ws();
+        if self.cur().is_some() {
+            return ParsedStatement::Error(String::from("Expected end"), self.index);
+        }
+
+        return ParsedStatement::Enum(name, values);
+    }
+
+    /// Parses a name: a letter or `_`, followed by any letters, digits or `_`, optionally
+    /// continued by `.`-separated parts, e.g. `P`, `rain`, `color.red`
+    fn name(&mut self) -> ParseResult<String> {
+        self.ws();
+
+        match self.cur() {
+            Some(cur) if Self::is_name_start(cur) => {},
+            _ => return Absent(self.index)
+        }
+
+        let mut name = String::new();
+        loop {
+            match (self.cur(), self.off(1)) {
+                (Some(cur), _) if Self::is_name_part(cur) => name.push(cur),
+                (Some('.'), Some(next)) if Self::is_name_part(next) => name.push('.'),
+                _ => break
             }
             self.shift();
         }
+
+        return Ok(name);
+    }
+
+    /// Reads a single token, see [tokenize].
+    fn token(&mut self) -> ParseResult<String> {
+        self.ws();
+
+        let cur = match self.cur() {
+            Some(cur) => cur,
+            None => return Absent(self.index)
+        };
+
+        if Self::is_name_start(cur) {
+            return self.name();
+        }
+
+        let len = match self.operator() {
+            0 if self.off(1).is_some_and(|next| (cur, next) == ('[', ']') || (cur, next) == ('<', '>')) => 2,
+            0 if "!~*(),?:.".contains(cur) => 1,
+            0 => return Error(format!("Unexpected character '{cur}'"), self.index),
+            len => len
+        };
+
+        let token = String::from_iter(&self.input[self.index..self.index + len]);
+        self.index += len;
+        return Ok(token);
+    }
+
+    /// Parses a symbol: `P` for any name P
+    fn symbol(&mut self) -> ParseResult<Stmt> {
+        return match self.name() {
+            Ok(name) => Ok(Stmt::symbol(&name)),
+            Absent(idx) => Absent(idx),
+            Error(msg, idx) => Error(msg, idx)
+        };
+    }
+
+    /// Parses a modal expression: `[]x`, `<>x` for any atomic expression x
+    fn modal(&mut self) -> ParseResult<Stmt> {
+        self.ws();
+
+        let necessarily = match (self.cur(), self.off(1)) {
+            (Some('['), Some(']')) => true,
+            (Some('<'), Some('>')) => false,
+            _ => return Absent(self.index),
+        };
+        self.shift();
+        self.shift();
+
+        self.ws();
+
+        return match self.base() {
+            Ok(s) if necessarily => Ok(s.necessarily()),
+            Ok(s) => Ok(s.possibly()),
+            o => self.recover(o.error_if_absent("Expected expression"))
+        };
+    }
+
+    /// Parses a not expression: `!x` for any atomic expression x
+    fn not(&mut self) -> ParseResult<Stmt> {
+        self.ws();
+
+        if !self.has('!') {
+            return Absent(self.index);
+        }
+        self.shift();
         
         self.ws();
 
         return match self.base() {
             Ok(s) => Ok(s.not()),
-            o => o.error_if_absent("Expected expression")
+            o => self.recover(o.error_if_absent("Expected expression"))
         };
     }
 
@@ -148,12 +577,10 @@ impl Parser {
     fn cont(&mut self) -> ParseResult<Stmt> {
         self.ws();
 
-        if let Some(cur) = self.cur() {
-            if cur != '~' {
-                return Absent(self.index);
-            }
-            self.shift();
+        if !self.has('~') {
+            return Absent(self.index);
         }
+        self.shift();
 
         return Ok(Stmt::cont())
     }
@@ -162,24 +589,34 @@ impl Parser {
     fn taut(&mut self) -> ParseResult<Stmt> {
         self.ws();
 
-        if let Some(cur) = self.cur() {
-            if cur != '*' {
-                return Absent(self.index);
-            }
-            self.shift();
+        if !self.has('*') {
+            return Absent(self.index);
         }
+        self.shift();
 
         return Ok(Stmt::taut())
     }
     
-    /// Parses an atomic expression: `*`, `~`, `(x)`, `!a`, `P` for any expression x, any atomic expression a, any character P
+    /// Parses an atomic expression: `*`, `~`, `(x)`, `!a`, `[]a`, `<>a`, `ite(x, y, z)`, `forall P. x`, `exists P. x`, `P` for any expression x, y, z, any atomic expression a, any character P
     fn base(&mut self) -> ParseResult<Stmt> {
         self.ws();
 
+        match self.quantified() {
+            Absent(_) => {},
+            o => return o
+        };
+        match self.ite() {
+            Absent(_) => {},
+            o => return o
+        };
         match self.not() {
             Absent(_) => {},
             o => return o
         };
+        match self.modal() {
+            Absent(_) => {},
+            o => return o
+        };
         match self.taut() {
             Absent(_) => {},
             o => return o
@@ -200,37 +637,133 @@ impl Parser {
         return Absent(self.index);
     }
 
-    /// Parses a parenthesized expression: `(x)` for any expression x
-    fn par(&mut self) -> ParseResult<Stmt> {
+    /// Parses an if-then-else expression in function form: `ite(x, y, z)` for any expressions
+    /// x, y, z
+    fn ite(&mut self) -> ParseResult<Stmt> {
+        let start = self.index;
+
+        if !self.keyword("ite") {
+            return Absent(start);
+        }
         self.ws();
+        if !self.has('(') {
+            self.index = start;
+            return Absent(start);
+        }
+        self.shift();
 
-        if let Some(cur) = self.cur() {
-            if cur != '(' {
-                return Absent(self.index);
+        let mut args = Vec::new();
+        for i in 0..3 {
+            let arg = match self.conditional() {
+                Ok(s) => s,
+                o => match self.recover(o.error_if_absent("Expected expression")) {
+                    Ok(s) => s,
+                    o => return o
+                }
+            };
+            args.push(arg);
+
+            self.ws();
+            let (sep, msg) = if i < 2 { (',', "Expected ','") } else { (')', "Expected ')'") };
+            if !self.has(sep) {
+                return match self.recover(Error(String::from(msg), self.index)) {
+                    Ok(_) => Ok(Stmt::taut()),
+                    o => o
+                };
             }
             self.shift();
         }
 
-        self.ws();
+        let e = args.pop().unwrap();
+        let t = args.pop().unwrap();
+        let c = args.pop().unwrap();
+        return Ok(c.ite(t, e));
+    }
+
+    /// Parses a quantified expression: `forall P. x`, `exists P, Q. x` for any symbols P, Q and
+    /// any expression x. The expression extends as far to the right as possible. Without a
+    /// symbol after it, `forall` or `exists` is a symbol itself.
+    fn quantified(&mut self) -> ParseResult<Stmt> {
+        let start = self.index;
 
-        let i = match self.or() {
+        let universal = if self.keyword("forall") {
+            true
+        } else if self.keyword("exists") {
+            false
+        } else {
+            return Absent(start);
+        };
+
+        let mut symbols = Vec::new();
+        loop {
+            match self.name() {
+                Ok(name) => symbols.push(name),
+                _ if symbols.is_empty() => {
+                    self.index = start;
+                    return Absent(start);
+                },
+                _ => return self.recover(Error(String::from("Expected symbol"), self.index))
+            }
+
+            self.ws();
+            match self.cur() {
+                Some(',') => self.shift(),
+                Some('.') => {
+                    self.shift();
+                    break;
+                },
+                _ => return self.recover(Error(String::from("Expected ',' or '.'"), self.index))
+            }
+        }
+
+        let body = match self.conditional() {
             Ok(s) => s,
-            o => return o.error_if_absent("Expected expression")
+            o => return self.recover(o.error_if_absent("Expected expression"))
         };
 
+        return Ok(symbols.iter().rev().fold(body, |s, sym| {
+            if universal {
+                Stmt::forall(sym, s)
+            } else {
+                Stmt::exists(sym, s)
+            }
+        }));
+    }
+
+    /// Parses a parenthesized expression: `(x)` for any expression x
+    fn par(&mut self) -> ParseResult<Stmt> {
         self.ws();
 
-        if let Some(cur) = self.cur() {
-            if cur != ')' {
-                return Absent(self.index).error_if_absent("Expected ')'");
+        if !self.has('(') {
+            return Absent(self.index);
+        }
+        self.shift();
+
+        self.ws();
+
+        let i = match self.conditional() {
+            Ok(s) => s,
+            o => match self.recover(o.error_if_absent("Expected expression")) {
+                Ok(s) => s,
+                o => return o
             }
-            self.shift();
+        };
+
+        self.resume(')');
+        self.ws();
+
+        if !self.has(')') {
+            return match self.recover(Error(String::from("Expected ')'"), self.index)) {
+                Ok(_) => Ok(i),
+                o => o
+            };
         }
+        self.shift();
 
         return Ok(i)
     }
 
-    /// Parses an implication or bi-implication, or reverse implication: `a <- b`, `a -> b`, `a <-> b` for any atomic expression a, b
+    /// Parses an implication or bi-implication, or reverse implication, or exclusive disjunction: `a <- b`, `a -> b`, `a <-> b`, `a ^ b`, `a xor b` for any atomic expression a, b
     fn implication(&mut self) -> ParseResult<Stmt> {
         self.ws();
 
@@ -258,6 +791,11 @@ impl Parser {
                 self.shift();
                 2
             }
+            (Some('^'), _, _) => {
+                self.shift();
+                3
+            }
+            _ if self.keyword("xor") => 3,
             _ => return Ok(l)
         };
 
@@ -265,15 +803,20 @@ impl Parser {
 
         let r = match self.implication() {
             Ok(s) => s,
-            o => return o.error_if_absent("Expected expression")
+            o => match self.recover(o.error_if_absent("Expected expression")) {
+                Ok(s) => s,
+                o => return o
+            }
         };
 
         return if c == 0 {
             Ok(l.implies(r))
         } else if c == 1 {
             Ok(l.equiv(r))
-        } else {
+        } else if c == 2 {
             Ok(r.implies(l))
+        } else {
+            Ok(l.xor(r))
         }
     }
 
@@ -301,7 +844,10 @@ impl Parser {
 
         let r = match self.and() {
             Ok(s) => s,
-            o => return o.error_if_absent("Expected expression")
+            o => match self.recover(o.error_if_absent("Expected expression")) {
+                Ok(s) => s,
+                o => return o
+            }
         };
 
         return Ok(l.and(r))
@@ -331,9 +877,472 @@ impl Parser {
 
         let r = match self.or() {
             Ok(s) => s,
-            o => return o.error_if_absent("Expected expression")
+            o => match self.recover(o.error_if_absent("Expected expression")) {
+                Ok(s) => s,
+                o => return o
+            }
+        };
+
+        return Ok(l.or(r))
+    }
+
+    /// Parses an if-then-else expression: `c ? a : b` for any disjunction c and any expressions
+    /// a, b. A `?` at the end of the input is not part of the expression, as it makes the
+    /// input a question.
+    fn conditional(&mut self) -> ParseResult<Stmt> {
+        self.ws();
+
+        let c = match self.or() {
+            Ok(s) => s,
+            o => return o
+        };
+
+        self.ws();
+
+        let start = self.index;
+        if !self.has('?') {
+            return Ok(c);
+        }
+        self.shift();
+        self.ws();
+        if self.cur().is_none() {
+            self.index = start;
+            return Ok(c);
+        }
+
+        let t = match self.conditional() {
+            Ok(s) => s,
+            o => match self.recover(o.error_if_absent("Expected expression")) {
+                Ok(s) => s,
+                o => return o
+            }
+        };
+
+        self.ws();
+        if !self.has(':') {
+            return match self.recover(Error(String::from("Expected ':'"), self.index)) {
+                Ok(_) => Ok(c.ite(t, Stmt::taut())),
+                o => o
+            };
+        }
+        self.shift();
+
+        let e = match self.conditional() {
+            Ok(s) => s,
+            o => match self.recover(o.error_if_absent("Expected expression")) {
+                Ok(s) => s,
+                o => return o
+            }
+        };
+
+        return Ok(c.ite(t, e));
+    }
+
+    /// Handles the result of parsing an expression. When recovering from errors, an error is
+    /// recorded and replaced by a tautology, after skipping any input up to the next operator
+    /// or parenthesis, so that parsing can continue. Otherwise, the result is returned as is.
+    fn recover(&mut self, r: ParseResult<Stmt>) -> ParseResult<Stmt> {
+        return match r {
+            Error(msg, idx) if self.errors.is_some() => {
+                self.errors.as_mut().unwrap().push((msg, idx));
+                self.sync();
+                Ok(Stmt::taut())
+            }
+            r => r
+        };
+    }
+
+    /// Returns the length of the binary operator the input continues with, or 0 if there is
+    /// no binary operator.
+    fn operator(&self) -> usize {
+        return match (self.cur(), self.off(1), self.off(2)) {
+            (Some('&'), _, _) | (Some('|'), _, _) | (Some('^'), _, _) => 1,
+            (Some('x'), Some('o'), Some('r')) if self.is_xor() => 3,
+            (Some('<'), Some('-'), Some('>')) => 3,
+            (Some('-'), Some('>'), _) | (Some('<'), Some('-'), _) => 2,
+            _ => 0
+        };
+    }
+
+    /// Tests whether the input continues with the `xor` keyword, as a word by itself.
+    fn is_xor(&self) -> bool {
+        let before = self.index > 0 && Self::is_name_part(self.input[self.index - 1]);
+        let after = self.off(3).is_some_and(|c| Self::is_name_part(c) || c == '.');
+        return !before && !after;
+    }
+
+    /// Skips over input up to the next binary operator, `)`, `?`, `:`, `,` or the end of the input.
+    /// Parenthesized parts of the input are skipped entirely.
+    fn sync(&mut self) {
+        let mut depth = 0;
+
+        while let Some(cur) = self.cur() {
+            if depth == 0 && (self.operator() > 0 || cur == ')' || cur == '?' || cur == ':' || cur == ',') {
+                return;
+            }
+
+            match cur {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            self.shift();
+        }
+    }
+
+    /// When recovering from errors, continues after an expression that does not end at the
+    /// given closing character (or the end of the input): any binary operator is followed by
+    /// another expression, and any other input is recorded as error and skipped.
+    fn resume(&mut self, close: char) {
+        if self.errors.is_none() {
+            return;
+        }
+
+        loop {
+            self.ws();
+
+            match self.cur() {
+                None => return,
+                Some(cur) if cur == close => return,
+                Some(_) if self.operator() > 0 => {
+                    self.index += self.operator();
+
+                    let r = self.conditional();
+                    let _ = self.recover(r.error_if_absent("Expected expression"));
+                }
+                Some(_) => {
+                    let msg = if close == ')' { "Expected operator or ')'" } else { "Expected operator, '?' or end" };
+                    self.errors.as_mut().unwrap().push((String::from(msg), self.index));
+                    self.shift();
+                    self.sync();
+                }
+            }
+        }
+    }
+}
+
+// Parsing of first-order formulas, which are clausified by the fol module.
+#[cfg(feature = "fol")]
+impl Parser {
+    /// Tests whether the rest of the input is a first-order formula, i.e. whether it applies a
+    /// predicate or function to arguments. Quantifiers alone do not make a formula first-order:
+    /// without predicates, they quantify over symbols, see [Stmt::Forall].
+    fn is_first_order(&self) -> bool {
+        let mut i = self.index;
+        while i < self.input.len() {
+            if !Self::is_name_start(self.input[i]) || (i > 0 && Self::is_name_part(self.input[i - 1])) {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            while i < self.input.len() && (Self::is_name_part(self.input[i]) || self.input[i] == '.') {
+                i += 1;
+            }
+
+            let word: String = self.input[start..i].iter().collect();
+            // The symbols of a quantifier end in `.`, e.g. `P.` in `forall P. (P | Q)`
+            if word == "xor" || word == "ite" || word.ends_with('.') {
+                continue;
+            }
+
+            while i < self.input.len() && self.input[i].is_whitespace() {
+                i += 1;
+            }
+            if i < self.input.len() && self.input[i] == '(' {
+                return true;
+            }
+        }
+
+        return false;
+    }
+
+    /// Reads a first-order formula.
+    fn first_order(&mut self) -> ParsedStatement {
+        return match self.fo_or() {
+            Ok(f) => {
+                self.ws();
+                if self.cur().is_some() {
+                    return ParsedStatement::Error(String::from("Expected end"), self.index);
+                }
+
+                ParsedStatement::FirstOrder(f)
+            }
+            Absent(idx) => ParsedStatement::Error(String::from("Expected formula"), idx),
+            Error(msg, idx) => ParsedStatement::Error(msg, idx)
+        }
+    }
+
+    /// Parses a variable: a name without `.`-separated parts, e.g. `x`
+    fn variable(&mut self) -> ParseResult<String> {
+        self.ws();
+
+        match self.cur() {
+            Some(cur) if Self::is_name_start(cur) => {},
+            _ => return Absent(self.index)
+        }
+
+        let mut name = String::new();
+        while let Some(cur) = self.cur() {
+            if !Self::is_name_part(cur) {
+                break;
+            }
+            name.push(cur);
+            self.shift();
+        }
+
+        return Ok(name);
+    }
+
+    /// Parses an argument list: `(t, u)` for any terms t, u
+    fn fo_args(&mut self) -> ParseResult<Vec<Term>> {
+        self.ws();
+
+        if !self.has('(') {
+            return Absent(self.index);
+        }
+        self.shift();
+
+        let mut args = Vec::new();
+        loop {
+            match self.fo_term() {
+                Ok(t) => args.push(t),
+                Absent(idx) => return Error(String::from("Expected term"), idx),
+                Error(msg, idx) => return Error(msg, idx)
+            }
+
+            self.ws();
+            match self.cur() {
+                Some(',') => self.shift(),
+                Some(')') => {
+                    self.shift();
+                    return Ok(args);
+                },
+                _ => return Error(String::from("Expected ',' or ')'"), self.index)
+            }
+        }
+    }
+
+    /// Parses a term: `x`, `c`, `f(t, u)` for any variable x, any constant c, any function f and any terms t, u.
+    /// A name is a variable when it is bound by a surrounding quantifier, and a constant otherwise.
+    fn fo_term(&mut self) -> ParseResult<Term> {
+        let name = match self.name() {
+            Ok(n) => n,
+            Absent(idx) => return Absent(idx),
+            Error(msg, idx) => return Error(msg, idx)
+        };
+
+        return match self.fo_args() {
+            Ok(args) => Ok(Term::Func(name, args)),
+            Absent(_) if self.bound.contains(&name) => Ok(Term::Var(name)),
+            Absent(_) => Ok(Term::Func(name, Vec::new())),
+            Error(msg, idx) => Error(msg, idx)
+        };
+    }
+
+    /// Parses a predicate: `P`, `P(t, u)` for any predicate P and any terms t, u
+    fn fo_pred(&mut self) -> ParseResult<Formula> {
+        let name = match self.name() {
+            Ok(n) => n,
+            Absent(idx) => return Absent(idx),
+            Error(msg, idx) => return Error(msg, idx)
+        };
+
+        return match self.fo_args() {
+            Ok(args) => Ok(Formula::pred(&name, args)),
+            Absent(_) => Ok(Formula::pred(&name, Vec::new())),
+            Error(msg, idx) => Error(msg, idx)
+        };
+    }
+
+    /// Parses a quantified formula: `forall x. f`, `exists x, y. f` for any variables x, y and any formula f.
+    /// The formula extends as far to the right as possible.
+    fn fo_quantifier(&mut self) -> ParseResult<Formula> {
+        let universal = if self.keyword("forall") {
+            true
+        } else if self.keyword("exists") {
+            false
+        } else {
+            return Absent(self.index);
+        };
+
+        let mut vars = Vec::new();
+        loop {
+            match self.variable() {
+                Ok(v) => vars.push(v),
+                _ => return Error(String::from("Expected variable"), self.index)
+            }
+
+            self.ws();
+            match self.cur() {
+                Some(',') => self.shift(),
+                Some('.') => {
+                    self.shift();
+                    break;
+                },
+                _ => return Error(String::from("Expected ',' or '.'"), self.index)
+            }
+        }
+
+        let depth = self.bound.len();
+        self.bound.extend(vars.iter().cloned());
+        let body = self.fo_or();
+        self.bound.truncate(depth);
+
+        let body = match body {
+            Ok(f) => f,
+            o => return o.error_if_absent("Expected formula")
+        };
+
+        return Ok(vars.iter().rev().fold(body, |f, v| {
+            if universal {
+                Formula::forall(v, f)
+            } else {
+                Formula::exists(v, f)
+            }
+        }));
+    }
+
+    /// Parses an atomic first-order formula: `*`, `~`, `(f)`, `!a`, `forall x. f`, `exists x. f`, `P(t)`
+    /// for any formula f, any atomic formula a, any variable x, any predicate P and any terms t
+    fn fo_base(&mut self) -> ParseResult<Formula> {
+        self.ws();
+
+        match self.cur() {
+            Some('!') => {
+                self.shift();
+                return match self.fo_base() {
+                    Ok(f) => Ok(f.not()),
+                    o => o.error_if_absent("Expected formula")
+                };
+            },
+            Some('*') => {
+                self.shift();
+                return Ok(Formula::taut());
+            },
+            Some('~') => {
+                self.shift();
+                return Ok(Formula::cont());
+            },
+            Some('(') => {
+                self.shift();
+
+                let f = match self.fo_or() {
+                    Ok(f) => f,
+                    o => return o.error_if_absent("Expected formula")
+                };
+
+                self.ws();
+                if !self.has(')') {
+                    return Error(String::from("Expected ')'"), self.index);
+                }
+                self.shift();
+
+                return Ok(f);
+            },
+            _ => {}
+        }
+
+        match self.fo_quantifier() {
+            Absent(_) => {},
+            o => return o
+        };
+
+        return self.fo_pred();
+    }
+
+    /// Parses a first-order implication, bi-implication, reverse implication or exclusive disjunction: `a <- b`, `a -> b`, `a <-> b`, `a ^ b`, `a xor b`; the latter are read as `!(a <-> b)`
+    /// for any atomic formulas a, b
+    fn fo_implication(&mut self) -> ParseResult<Formula> {
+        let l = match self.fo_base() {
+            Ok(f) => f,
+            o => return o
+        };
+
+        self.ws();
+
+        let c = match (self.cur(), self.off(1), self.off(2)) {
+            (Some('-'), Some('>'), _) => {
+                self.shift();
+                self.shift();
+                0
+            }
+            (Some('<'), Some('-'), Some('>')) => {
+                self.shift();
+                self.shift();
+                self.shift();
+                1
+            }
+            (Some('<'), Some('-'), _) => {
+                self.shift();
+                self.shift();
+                2
+            }
+            (Some('^'), _, _) => {
+                self.shift();
+                3
+            }
+            _ if self.keyword("xor") => 3,
+            _ => return Ok(l)
+        };
+
+        let r = match self.fo_implication() {
+            Ok(f) => f,
+            o => return o.error_if_absent("Expected formula")
+        };
+
+        return if c == 0 {
+            Ok(l.implies(r))
+        } else if c == 1 {
+            Ok(l.equiv(r))
+        } else if c == 2 {
+            Ok(r.implies(l))
+        } else {
+            Ok(l.equiv(r).not())
+        }
+    }
+
+    /// Parses a first-order conjunction: `a & b` for any implications a, b
+    fn fo_and(&mut self) -> ParseResult<Formula> {
+        let l = match self.fo_implication() {
+            Ok(f) => f,
+            o => return o
+        };
+
+        self.ws();
+
+        if !self.has('&') {
+            return Ok(l);
+        }
+        self.shift();
+
+        let r = match self.fo_and() {
+            Ok(f) => f,
+            o => return o.error_if_absent("Expected formula")
+        };
+
+        return Ok(l.and(r))
+    }
+
+    /// Parses a first-order disjunction: `a | b` for any conjunctions a, b
+    fn fo_or(&mut self) -> ParseResult<Formula> {
+        let l = match self.fo_and() {
+            Ok(f) => f,
+            o => return o
+        };
+
+        self.ws();
+
+        if !self.has('|') {
+            return Ok(l);
+        }
+        self.shift();
+
+        let r = match self.fo_or() {
+            Ok(f) => f,
+            o => return o.error_if_absent("Expected formula")
         };
 
         return Ok(l.or(r))
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,212 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::Display;
+use std::time::Instant;
+
+use crate::cnf::{Disj, SaturationConfig};
+use crate::symbol::Symbol;
+
+/// How the clause of a step of a [Proof] is derived.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Rule {
+    /// The clause is a premise, with a label that tells where it comes from, like the name of
+    /// the axiom it is a clause of.
+    Premise(String),
+
+    /// The clause is the resolvent of the clauses of two earlier steps, given by their
+    /// index, on the given pivot symbol: the first clause has the pivot non-inverted, and the
+    /// second inverted.
+    Resolution(usize, usize, Symbol)
+}
+
+/// A step of a [Proof]: a clause, and how it is derived.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Step {
+    pub clause: Disj,
+    pub rule: Rule
+}
+
+/// A resolution refutation: steps that derive the empty clause from premises by resolution.
+/// Every step only refers to earlier steps, every step is used by a later one, and the last
+/// step derives the empty clause. See [Proof::refute].
+#[derive(Clone, Debug)]
+pub struct Proof {
+    steps: Vec<Step>
+}
+
+impl Proof {
+    /// Searches for a resolution refutation of the given premises, each with a label, which
+    /// shows that the premises contradict each other. Returns `None` if the premises are
+    /// satisfiable, or if no refutation is found within the limits of the given configuration
+    /// on the number of clauses, the number of inferences and the time. Its strategy, number
+    /// of rounds and maximum length of resolvents are not used.
+    ///
+    /// Clauses are resolved by the given-clause algorithm: the shortest clause that is not
+    /// resolved yet is resolved with all clauses that are, which makes short clauses, and
+    /// the empty clause in particular, come up early. Clauses that an earlier clause
+    /// subsumes are left out.
+    pub fn refute(premises: &[(Disj, String)], config: &SaturationConfig) -> Option<Proof> {
+        let start = Instant::now();
+        let mut steps: Vec<Step> = Vec::new();
+        let mut queue = BinaryHeap::new();
+        let mut resolved: Vec<usize> = Vec::new();
+        let mut inferences = 0;
+
+        // Steps are only kept when no earlier step subsumes them, so all steps are compared
+        let subsumed = |steps: &[Step], disj: &Disj| steps.iter().any(|s| s.clause.subsumes(disj));
+
+        for (disj, label) in premises {
+            if subsumed(&steps, disj) {
+                continue;
+            }
+            steps.push(Step { clause: disj.clone(), rule: Rule::Premise(label.clone()) });
+            if disj.is_contradiction() {
+                return Some(Proof::extract(steps));
+            }
+            queue.push(Reverse((disj.len(), steps.len() - 1)));
+        }
+
+        while let Some(Reverse((_, given))) = queue.pop() {
+            let lits = Vec::from_iter(steps[given].clause.literals().iter().cloned());
+
+            for &other in resolved.iter() {
+                for lit in lits.iter() {
+                    if !steps[other].clause.contains(&!lit) {
+                        continue;
+                    }
+                    inferences += 1;
+                    let Some(resolvent) = steps[given].clause.resolve(&steps[other].clause, &lit.atom) else { continue };
+                    if subsumed(&steps, &resolvent) {
+                        continue;
+                    }
+
+                    let (pos, neg) = if lit.polarity { (given, other) } else { (other, given) };
+                    let len = resolvent.len();
+                    steps.push(Step { clause: resolvent, rule: Rule::Resolution(pos, neg, lit.atom) });
+                    if len == 0 {
+                        return Some(Proof::extract(steps));
+                    }
+                    queue.push(Reverse((len, steps.len() - 1)));
+                }
+
+                let exceeded = config.max_clauses.is_some_and(|max| steps.len() > max)
+                    || config.max_inferences.is_some_and(|max| inferences > max)
+                    || config.max_time.is_some_and(|max| start.elapsed() > max);
+                if exceeded {
+                    return None;
+                }
+            }
+
+            resolved.push(given);
+        }
+
+        return None;
+    }
+
+    /// Keeps only the steps that the last step is derived from, and numbers them again.
+    fn extract(steps: Vec<Step>) -> Proof {
+        let mut used = vec![false; steps.len()];
+        used[steps.len() - 1] = true;
+        for i in (0..steps.len()).rev() {
+            if let (true, Rule::Resolution(l, r, _)) = (used[i], &steps[i].rule) {
+                used[*l] = true;
+                used[*r] = true;
+            }
+        }
+
+        let mut index = vec![0; steps.len()];
+        let mut out = Vec::new();
+        for (i, mut step) in steps.into_iter().enumerate() {
+            if !used[i] {
+                continue;
+            }
+            if let Rule::Resolution(l, r, _) = &mut step.rule {
+                *l = index[*l];
+                *r = index[*r];
+            }
+            index[i] = out.len();
+            out.push(step);
+        }
+
+        return Proof { steps: out };
+    }
+
+    /// Returns the steps of this proof, in order. The last step derives the empty clause.
+    pub fn steps(&self) -> &[Step] {
+        return &self.steps;
+    }
+
+    /// Writes this proof as a proof tree of the LaTeX package `bussproofs`, in a `prooftree`
+    /// environment, with the empty clause at the root and the premises at the leaves. Every
+    /// resolution is labelled with its pivot. A step that is used more than once is written
+    /// as often as it is used, since a tree cannot share it.
+    pub fn to_latex(&self) -> String {
+        let mut lines = vec![String::from("\\begin{prooftree}")];
+        self.latex_into(self.steps.len() - 1, &mut lines);
+        lines.push(String::from("\\end{prooftree}"));
+        return lines.join("\n");
+    }
+
+    fn latex_into(&self, index: usize, lines: &mut Vec<String>) {
+        let step = &self.steps[index];
+        match &step.rule {
+            Rule::Premise(_) => lines.push(format!("\\AxiomC{{${}$}}", step.clause.to_latex())),
+            Rule::Resolution(l, r, pivot) => {
+                self.latex_into(*l, lines);
+                self.latex_into(*r, lines);
+                lines.push(format!("\\RightLabel{{\\scriptsize ${}$}}", Symbol::latex(pivot)));
+                lines.push(format!("\\BinaryInfC{{${}$}}", step.clause.to_latex()));
+            },
+        }
+    }
+
+    /// Writes this proof as a graph in the DOT format of Graphviz: every step is a node,
+    /// labelled with its clause, and every resolution has an edge from both clauses it resolves
+    /// to the resolvent, labelled with the pivot. Premises are boxes, also labelled with where
+    /// they come from, and the empty clause has a double border. Unlike [Self::to_latex], steps
+    /// that are used more than once are shared.
+    pub fn to_dot(&self) -> String {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+
+        let mut lines = vec![String::from("digraph proof {")];
+        for (i, step) in self.steps.iter().enumerate() {
+            let clause = escape(&step.clause.to_string());
+            match &step.rule {
+                Rule::Premise(label) => {
+                    lines.push(format!("  s{i} [shape=box, label=\"{clause}\\nfrom {}\"];", escape(label)));
+                },
+                Rule::Resolution(l, r, pivot) => {
+                    let border = if step.clause.is_contradiction() { ", peripheries=2" } else { "" };
+                    lines.push(format!("  s{i} [shape=ellipse{border}, label=\"{clause}\"];"));
+                    lines.push(format!("  s{l} -> s{i} [label=\"{}\"];", escape(pivot)));
+                    lines.push(format!("  s{r} -> s{i} [label=\"{}\"];", escape(pivot)));
+                },
+            }
+        }
+        lines.push(String::from("}"));
+        return lines.join("\n");
+    }
+}
+
+/// Shows the steps of a proof, one per line, numbered from 1: the clause of every step, and
+/// where it comes from, like `from a` for a premise, or `1, 3 on P` for the resolvent of
+/// steps 1 and 3 on `P`.
+impl Display for Proof {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let clauses: Vec<String> = self.steps.iter().map(|s| s.clause.to_string()).collect();
+        let width = clauses.iter().map(|c| c.chars().count()).max().unwrap_or(0);
+        let digits = self.steps.len().to_string().len();
+
+        for (i, (step, clause)) in self.steps.iter().zip(clauses.iter()).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let source = match &step.rule {
+                Rule::Premise(label) => format!("from {label}"),
+                Rule::Resolution(l, r, pivot) => format!("{}, {} on {pivot}", l + 1, r + 1),
+            };
+            write!(f, "{:>digits$}. {clause:<width$}  {source}", i + 1)?;
+        }
+        return Ok(());
+    }
+}
@@ -0,0 +1,65 @@
+use crate::kb::KnowledgeBase;
+use crate::parser::parse_stmt;
+use crate::stmt::Stmt;
+
+/// Parses an expression given on the command line, naming the flag it was given with in the
+/// error message.
+fn parse_arg(flag: &str, input: &str) -> Result<Stmt, String> {
+    return parse_stmt(input).map_err(|e| format!("Cannot parse {flag} \"{input}\": {e}"));
+}
+
+/// Runs the `prove` command: `prove -a <axiom> ... -q <question>`, optionally followed by
+/// `--proof`. The axioms are asserted into an empty knowledge base, after which the question is
+/// answered, as in the REPL. With `--proof`, the axioms used to prove the question are shown,
+/// or a model of the axioms in which the question is false if it does not follow.
+///
+/// Returns whether the question follows from the axioms, so that the answer can be turned into
+/// an exit status.
+pub fn run(args: &[String]) -> Result<bool, String> {
+    let usage = "Usage: prove -a <axiom> ... -q <question> [--proof]";
+
+    let mut kb = KnowledgeBase::new();
+    let mut question = None;
+    let mut proof = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-a" | "--axiom" => {
+                let axiom = args.next().ok_or(usage)?;
+                kb.assert(parse_arg("axiom", axiom)?)?;
+            },
+            "-q" | "--question" if question.is_none() => {
+                let q = args.next().ok_or(usage)?;
+                question = Some(parse_arg("question", q)?);
+            },
+            "--proof" => proof = true,
+            _ => return Err(String::from(usage)),
+        }
+    }
+
+    let question = question.ok_or(usage)?;
+
+    return match kb.counter_model(&question) {
+        None => {
+            println!("Satisfied!");
+            if proof {
+                match kb.core(&question.clone().not().cnf()) {
+                    Some(core) if !core.is_empty() => {
+                        let labels: Vec<String> = core.iter().map(|i| kb.axioms()[*i].label()).collect();
+                        println!("Using: {}", labels.join(", "));
+                    },
+                    _ => {},
+                }
+            }
+            Ok(true)
+        },
+        Some(model) => {
+            println!("Not satisfied!");
+            if proof {
+                println!("Counter-model: {model}");
+            }
+            Ok(false)
+        },
+    };
+}
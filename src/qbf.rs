@@ -0,0 +1,59 @@
+use crate::stmt::Stmt;
+
+/// Decides a quantified Boolean formula: tests whether the given statement is true, where
+/// symbols that no quantifier binds are quantified existentially around the whole statement.
+/// Without quantifiers, this tests whether the statement is satisfiable.
+///
+/// Quantifiers are expanded from the outside in, one symbol at a time: `forall P. A` holds when
+/// `A` holds with `P` true and with `P` false, and `exists P. A` when either does, so the
+/// second value is only tried when the first does not decide the quantifier already. Once only
+/// a block of quantifiers of the same kind is left, over a statement without quantifiers, the
+/// solver decides the block at once: by satisfiability for `exists`, and by validity for
+/// `forall`.
+///
+/// For example, `forall P. exists Q. P <-> Q` is true, and `exists Q. forall P. P <-> Q` is
+/// false.
+pub fn decide(stmt: &Stmt) -> bool {
+    let closed = stmt.symbols().iter().rev().fold(stmt.clone(), |s, sym| Stmt::exists(sym, s));
+    return eval(&closed);
+}
+
+/// Evaluates a statement without free symbols.
+fn eval(stmt: &Stmt) -> bool {
+    return match stmt {
+        Stmt::Cont => false,
+        Stmt::Taut => true,
+        Stmt::Symbol(_) | Stmt::Necessarily(_) | Stmt::Possibly(_) => panic!("Unbound symbol {stmt}"),
+        Stmt::Not(o) => !eval(o),
+        Stmt::And(v) => v.iter().all(eval),
+        Stmt::Or(v) => v.iter().any(eval),
+        Stmt::Implies(l, r) => !eval(l) || eval(r),
+        Stmt::Equiv(l, r) => eval(l) == eval(r),
+        Stmt::Xor(l, r) => eval(l) != eval(r),
+        Stmt::Ite(c, t, e) => if eval(c) { eval(t) } else { eval(e) },
+        Stmt::Forall(sym, o) => {
+            let body = innermost(o, true);
+            if !body.has_quantifiers() {
+                return body.is_tautology();
+            }
+            eval(&o.fix(sym, true)) && eval(&o.fix(sym, false))
+        },
+        Stmt::Exists(sym, o) => {
+            let body = innermost(o, false);
+            if !body.has_quantifiers() {
+                return body.is_satisfiable();
+            }
+            eval(&o.fix(sym, true)) || eval(&o.fix(sym, false))
+        },
+    };
+}
+
+/// Returns the body of the block of universal or existential quantifiers the given statement
+/// starts with, or the statement itself if it does not start with such a quantifier.
+fn innermost(stmt: &Stmt, universal: bool) -> &Stmt {
+    return match stmt {
+        Stmt::Forall(_, o) if universal => innermost(o, universal),
+        Stmt::Exists(_, o) if !universal => innermost(o, universal),
+        s => s,
+    };
+}
@@ -1,68 +1,2556 @@
-use std::fs::read;
-use std::io::{self, BufRead};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
-use crate::cnf::{Cnf, Disj};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+use crate::cnf::{Cnf, Disj, SaturationConfig};
+use crate::decode::{Decoders, OneHot};
+use crate::horn::{self, Rules, Sld};
+use crate::kb::{Axiom, Entailment, Format, KnowledgeBase};
+use crate::lint::{Linter, Severity, RULES};
+use crate::minimize;
+use crate::modal::{self, System};
 use crate::parser::*;
+use crate::solver::{self, Model, Models, Solver};
+use crate::step::{Breakpoint, Stepper};
+use crate::stmt::Stmt;
+
+/// Whether output is colored with ANSI escape codes, see [Options::color].
+static COLOR: AtomicBool = AtomicBool::new(false);
+
+/// The ANSI styles of output: errors and contradictions in red, warnings in yellow, and what
+/// was newly derived in green.
+const RED: &str = "31";
+const BOLD_RED: &str = "1;31";
+const YELLOW: &str = "33";
+const GREEN: &str = "32";
+
+/// Shows text in the given ANSI style, if output is colored.
+fn paint(text: &str, style: &str) -> String {
+    if !COLOR.load(Ordering::Relaxed) {
+        return text.to_string();
+    }
+    return format!("\x1b[{style}m{text}\x1b[0m");
+}
+
+/// Prints an error message, like `println!`, as `> Error! <message>`.
+macro_rules! error {
+    ($($arg:tt)*) => {
+        println!("> {}", paint(&format!("Error! {}", format_args!($($arg)*)), RED))
+    };
+}
+
+/// The maximum number of rule applications in a single branch of backward chaining.
+const SLD_DEPTH: usize = 64;
+
+/// The budget of `:auto on`, unless given.
+const AUTO_BUDGET: Duration = Duration::from_secs(1);
+
+/// The cargo features of rustyproof, and whether this build has them.
+const FEATURES: &[(&str, bool)] = &[
+    ("repl", cfg!(feature = "repl")),
+    ("fol", cfg!(feature = "fol")),
+    ("serde", cfg!(feature = "serde")),
+    ("parallel", cfg!(feature = "parallel")),
+    ("bdd", cfg!(feature = "bdd")),
+    ("wasm", cfg!(feature = "wasm")),
+    ("ffi", cfg!(feature = "ffi")),
+    ("fuzz", cfg!(feature = "fuzz")),
+];
+
+/// The methods by which questions are answered.
+const BACKEND: &str = "resolution and DPLL";
+
+/// A command of the REPL, i.e. an input starting with `:`.
+struct Command {
+    name: &'static str,
+
+    /// The syntax of the command, as shown by `:help`.
+    usage: &'static [&'static str],
+
+    /// A short description of the command, as shown by `:help`.
+    help: &'static str,
+
+    /// Runs the command, given the arguments after the command name.
+    run: fn(&mut Repl, &str)
+}
+
+/// All commands of the REPL.
+const COMMANDS: &[Command] = &[
+    Command {
+        name: "help",
+        usage: &[":help", ":help <command>"],
+        help: "Shows the input syntax and all commands, or the usage of a single command",
+        run: Repl::help
+    },
+    Command {
+        name: "version",
+        usage: &[":version"],
+        help: "Shows the version, backend, features and limits of this build as JSON, like :capabilities",
+        run: Repl::capabilities
+    },
+    Command {
+        name: "capabilities",
+        usage: &[":capabilities"],
+        help: "Shows the version, backend, features and limits of this build as JSON, for tools to find out what it supports",
+        run: Repl::capabilities
+    },
+    Command {
+        name: "list",
+        usage: &[":list", ":list @<tag>"],
+        help: "Shows all axioms, and the clauses of the knowledge base, or only the axioms with the given tag",
+        run: Repl::list
+    },
+    Command {
+        name: "clear",
+        usage: &[":clear"],
+        help: "Removes all axioms from the knowledge base",
+        run: Repl::clear
+    },
+    Command {
+        name: "retract",
+        usage: &[":retract <n>", ":retract <name>"],
+        help: "Removes the n-th axiom, as numbered by ':list', or the axiom with the given name, and everything derived from it",
+        run: Repl::retract
+    },
+    Command {
+        name: "push",
+        usage: &[":push"],
+        help: "Saves the state of the knowledge base, so that hypotheses asserted after can be undone by ':pop'",
+        run: Repl::push
+    },
+    Command {
+        name: "pop",
+        usage: &[":pop", ":pop <n>"],
+        help: "Returns to the state saved by the last ':push', or by the n-th last",
+        run: Repl::pop
+    },
+    Command {
+        name: "kb",
+        usage: &[":kb create <name>", ":kb switch <name>", ":kb copy <name>", ":kb list", ":kb delete <name>"],
+        help: "Creates an empty knowledge base, switches to another, copies the current one, lists or deletes knowledge bases",
+        run: Repl::knowledge_base
+    },
+    Command {
+        name: "undo",
+        usage: &[":undo"],
+        help: "Undoes the last change to the axioms, like an assertion, retraction or ':fix'",
+        run: Repl::undo
+    },
+    Command {
+        name: "redo",
+        usage: &[":redo"],
+        help: "Does the last change that ':undo' undid again",
+        run: Repl::redo
+    },
+    Command {
+        name: "disable",
+        usage: &[":disable @<tag>"],
+        help: "Leaves the axioms with the given tag out of the knowledge base",
+        run: Repl::disable
+    },
+    Command {
+        name: "enable",
+        usage: &[":enable @<tag>"],
+        help: "Takes the axioms with the given tag back into the knowledge base",
+        run: Repl::enable
+    },
+    Command {
+        name: "fix",
+        usage: &[":fix P=true", ":fix P=false, Q=true"],
+        help: "Fixes symbols to a value for good, simplifying the axioms accordingly",
+        run: Repl::fix
+    },
+    Command {
+        name: "assume",
+        usage: &[":assume P=true", ":assume P=false, Q=true"],
+        help: "Shows the clauses that remain when symbols have the given values, without changing the axioms",
+        run: Repl::assume
+    },
+    Command {
+        name: "deduce",
+        usage: &[":deduce A |- B", ":deduce A |- B as <name>"],
+        help: "Proves B under the hypothesis A and shows the implication A -> B, optionally asserting it as an axiom named name",
+        run: Repl::deduce
+    },
+    Command {
+        name: "step",
+        usage: &[":step", ":step <n>", ":step stop"],
+        help: "Steps through the resolvents of the clauses one at a time, or n at a time, or fast-forwards to the next breakpoint set by ':break'",
+        run: Repl::step
+    },
+    Command {
+        name: "break",
+        usage: &[":break on <atom>", ":break on <clause>", ":break list", ":break clear"],
+        help: "Sets a breakpoint on resolvents with the given atom, or containing the given clause, lists or removes breakpoints",
+        run: Repl::breakpoint
+    },
+    Command {
+        name: "dnf",
+        usage: &[":dnf <expression>"],
+        help: "Shows the disjunctive normal form of an expression: the ways in which it can be true",
+        run: Repl::dnf
+    },
+    Command {
+        name: "dot",
+        usage: &[":dot <file>", ":dot <file> <expression>"],
+        help: "Writes a resolution proof of the statement proved last, or of an expression, as a Graphviz DOT graph",
+        run: Repl::dot
+    },
+    Command {
+        name: "latex",
+        usage: &[":latex <expression>"],
+        help: "Shows an expression and its clauses in LaTeX, and a resolution proof of it as a bussproofs tree if it follows from the axioms",
+        run: Repl::latex
+    },
+    Command {
+        name: "modal",
+        usage: &[":modal K|T|S4|S5 <expression>"],
+        help: "Tests whether a modal expression is valid or satisfiable in the modal logic K, T, S4 or S5",
+        run: Repl::modal
+    },
+    Command {
+        name: "why",
+        usage: &[":why <expression>"],
+        help: "Explains why an expression follows from the axioms, step by step from the axioms to a contradiction with its negation",
+        run: Repl::why
+    },
+    Command {
+        name: "parse",
+        usage: &[":parse <expression>"],
+        help: "Shows how an expression is parsed: its tokens, its structure, and its fully parenthesized form",
+        run: Repl::parse
+    },
+    Command {
+        name: "simplify",
+        usage: &[":simplify <expression>"],
+        help: "Shows the smallest equivalent sum of products and product of sums of an expression",
+        run: Repl::simplify
+    },
+    Command {
+        name: "lemmas",
+        usage: &[":lemmas", ":lemmas clear"],
+        help: "Shows the unit and binary clauses that were proved by earlier questions, or forgets them",
+        run: Repl::lemmas
+    },
+    Command {
+        name: "queries",
+        usage: &[":queries { A? ; B? ; C? }"],
+        help: "Answers a batch of questions at once, sharing work between them",
+        run: Repl::queries
+    },
+    Command {
+        name: "auto",
+        usage: &[":auto on", ":auto on <seconds>", ":auto off"],
+        help: "Answers questions by trying cheap methods first and escalating to resolution and the solver within a budget, showing which method answered",
+        run: Repl::auto
+    },
+    Command {
+        name: "verbosity",
+        usage: &[":verbosity", ":verbosity quiet|normal|verbose"],
+        help: "Shows or sets how much is shown after every axiom: only answers, also its clauses and what is newly derived, or also all resolved clauses",
+        run: Repl::verbosity
+    },
+    Command {
+        name: "scenario",
+        usage: &[":scenario create <name> {P=true, Q=false}", ":scenario run <name> <input>", ":scenario list", ":scenario delete <name>"],
+        help: "Creates a scenario in which symbols have the given values, runs input in it without changing the knowledge base, lists or deletes scenarios",
+        run: Repl::scenario
+    },
+    Command {
+        name: "compact",
+        usage: &[":compact"],
+        help: "Replaces the axioms by a smaller set of clauses with the same models, eliminating auxiliary symbols like _x",
+        run: Repl::compact
+    },
+    Command {
+        name: "gc",
+        usage: &[":gc"],
+        help: "Frees the memory the knowledge base no longer needs, e.g. after retracting axioms",
+        run: Repl::gc
+    },
+    Command {
+        name: "stats",
+        usage: &[":stats"],
+        help: "Shows how large the knowledge base is, and how much resolution it took so far",
+        run: Repl::stats
+    },
+    Command {
+        name: "lint",
+        usage: &[":lint", ":lint rules", ":lint level <rule> off|info|warning|error", ":lint allow <rule> <subject>"],
+        help: "Points out suspicious parts of the encoding, lists the lint rules, configures their severity, or suppresses a finding",
+        run: Repl::lint
+    },
+    Command {
+        name: "reset",
+        usage: &[":reset"],
+        help: "Removes all axioms and enum declarations, starting over entirely",
+        run: Repl::reset
+    },
+    Command {
+        name: "save",
+        usage: &[":save <path>"],
+        help: "Writes all axioms to a file",
+        run: Repl::save
+    },
+    Command {
+        name: "load",
+        usage: &[":load <path>"],
+        help: "Replaces all axioms by the axioms in a file",
+        run: Repl::load
+    },
+    Command {
+        name: "diff",
+        usage: &[":diff <path>"],
+        help: "Shows the axioms and clauses that were added, removed or changed since the given file was saved",
+        run: Repl::diff
+    },
+    Command {
+        name: "compare",
+        usage: &[":compare <path>"],
+        help: "Shows whether the axioms entail the axioms in the given file, or the other way around",
+        run: Repl::compare
+    },
+    Command {
+        name: "model",
+        usage: &[":model", ":model next", ":model where P=true, Q=false", ":model save <name>"],
+        help: "Shows a model of the knowledge base, the next model, or a model where the given symbols have the given values, or saves the last shown model",
+        run: Repl::model
+    },
+    Command {
+        name: "solve",
+        usage: &[":solve", ":solve hint <name>"],
+        help: "Shows a model of the knowledge base that assigns every symbol, preferring the values of a saved model",
+        run: Repl::solve
+    },
+    Command {
+        name: "models",
+        usage: &[":models diverse <k>"],
+        help: "Shows k models that differ from each other as much as possible",
+        run: Repl::models
+    },
+    Command {
+        name: "soft",
+        usage: &[":soft <weight> <expression>", ":soft", ":soft clear"],
+        help: "Adds a soft statement with the given weight, which ':maxsat' makes true where the axioms allow it, or lists or removes the soft statements",
+        run: Repl::soft
+    },
+    Command {
+        name: "maxsat",
+        usage: &[":maxsat"],
+        help: "Shows a model of the knowledge base that makes soft statements of the largest total weight true, and the soft statements it makes false",
+        run: Repl::maxsat
+    },
+];
+
+/// The input syntax, as shown by `:help`.
+const SYNTAX: &[(&str, &str)] = &[
+    ("P, rain, color.red", "Symbols"),
+    ("~, *", "Contradiction, tautology"),
+    ("!A", "Negation"),
+    ("A -> B, A <- B, A <-> B", "Implication, reverse implication, bi-implication"),
+    ("A ^ B, A xor B", "Exclusive disjunction: exactly one of A and B"),
+    ("A & B", "Conjunction"),
+    ("A | B", "Disjunction"),
+    ("C ? A : B, ite(C, A, B)", "If-then-else: A if C holds, otherwise B"),
+    ("forall P. A, exists P. A", "Quantifiers: A for both values of P, or for some value of P"),
+    ("[]A, <>A", "Necessity and possibility, see ':modal'"),
+    ("(A)", "Parentheses, to alter precedence"),
+    ("A", "Asserts A as an axiom"),
+    ("name: A", "Asserts A as an axiom named name"),
+    ("@tag A", "Asserts A as an axiom tagged tag"),
+    ("A?", "Asks whether A follows from the axioms"),
+    ("derive A => B => C", "Checks that B follows from A, and C from B, given the axioms"),
+    ("enum c in {a, b}", "Declares the symbols c.a and c.b, of which exactly one holds"),
+    ("forall x. P(x)", "Shows the clauses of a first-order formula"),
+];
+
+/// The number of changes to the knowledge base that `:undo` can undo.
+const HISTORY: usize = 100;
+
+/// The state of the REPL.
+struct Repl {
+    /// The axioms, along with the clauses that follow from them.
+    kb: KnowledgeBase,
+
+    /// The name of [Self::kb], which is `main` unless another was switched to by `:kb switch`.
+    current: String,
+
+    /// The knowledge bases other than [Self::kb], by name, see `:kb`.
+    others: BTreeMap<String, KnowledgeBase>,
+
+    /// The resolved clauses shown last after asserting an axiom, so that the clauses that were
+    /// newly derived since can be told apart.
+    resolved: Cnf,
+
+    /// The states of the knowledge base before the last changes to it, oldest first, along
+    /// with a description of the change, which `:undo` returns to. See [Self::checkpoint].
+    undo: Vec<(String, KnowledgeBase)>,
+
+    /// The states undone by `:undo`, along with a description of the change that led to them,
+    /// which `:redo` returns to. A new change forgets them.
+    redo: Vec<(String, KnowledgeBase)>,
+
+    /// The symbols derived by forward chaining, while the knowledge base is Horn.
+    facts: HashSet<String>,
+
+    /// The models of the knowledge base that are being paged through with `:model`.
+    models: Option<Models>,
+
+    /// The model shown last by `:model` or `:solve`, which can be saved by `:model save`.
+    shown: Option<Model>,
+
+    /// The models saved by `:model save`, by name.
+    saved: BTreeMap<String, Model>,
+
+    /// The scenarios created by `:scenario create`, by name: values of symbols under which
+    /// input can be run.
+    scenarios: BTreeMap<String, Vec<(String, bool)>>,
+
+    /// The resolvents being stepped through with `:step`.
+    stepper: Option<Stepper>,
+
+    /// The breakpoints set by `:break`, at which `:step` stops.
+    breakpoints: Vec<Breakpoint>,
+
+    /// The decoders used to present models, e.g. of declared enums.
+    decoders: Decoders,
+
+    /// The linter used by `:lint`, along with its configuration.
+    linter: Linter,
+
+    /// The conjectures to prove at the end of the input, in order of declaration.
+    conjectures: Vec<Stmt>,
+
+    /// The budget within which questions are answered by escalating methods, see `:auto`, or
+    /// `None` to answer them by resolution and the solver.
+    auto: Option<Duration>,
+
+    /// The statement proved last, by a question or otherwise, whose proof `:dot` writes.
+    proved: Option<Stmt>,
+
+    /// How much is shown after every axiom, see `:verbosity`.
+    verbosity: Verbosity,
+
+    /// Whether some question, derivation or conjecture did not follow from the axioms, see
+    /// [Outcome].
+    failed: bool,
+
+    /// Whether some input had syntax errors, see [Outcome].
+    malformed: bool
+}
+
+impl Repl {
+    fn new() -> Repl {
+        return Repl {
+            kb: KnowledgeBase::new(),
+            current: String::from("main"),
+            others: BTreeMap::new(),
+            resolved: Cnf::new(),
+            undo: Vec::new(),
+            redo: Vec::new(),
+            facts: HashSet::new(),
+            models: None,
+            shown: None,
+            saved: BTreeMap::new(),
+            scenarios: BTreeMap::new(),
+            stepper: None,
+            breakpoints: Vec::new(),
+            decoders: Decoders::new(),
+            linter: Linter::new(),
+            conjectures: Vec::new(),
+            auto: None,
+            proved: None,
+            verbosity: Verbosity::Normal,
+            failed: false,
+            malformed: false
+        };
+    }
+
+    /// Adds an axiom to the knowledge base. When the knowledge base is Horn, the symbols that
+    /// follow from it are derived by forward chaining, which is much cheaper than resolution.
+    /// Otherwise, further resolvents are computed from the axiom and existing knowledge.
+    fn assert(&mut self, name: Option<&str>, tags: &[String], o: Stmt) {
+        let present = o.try_cnf().is_ok_and(|c| c.terms.iter().all(|d| self.kb.clauses().contains(d)));
+
+        let before = self.kb.clone();
+        let axiom = match self.kb.assert_labelled(name, tags, o) {
+            Ok(axiom) => axiom.clone(),
+            Err(msg) => {
+                error!("{msg}");
+                return;
+            }
+        };
+        self.checkpoint(format!("asserting {axiom}"), before);
+
+        let normal = self.verbosity >= Verbosity::Normal;
+        if normal {
+            println!("> CNF: {}", axiom.clauses);
+        }
+        if axiom.clauses.terms.is_empty() {
+            if normal {
+                println!("> This axiom adds no information: it is a tautology");
+            }
+            return;
+        }
+        if !self.kb.is_enabled(self.kb.axioms().len() - 1) {
+            if normal {
+                println!("> The axiom has a disabled tag, it is left out until the tag is enabled");
+            }
+            return;
+        }
+        if present {
+            if normal {
+                println!("> This axiom adds no information: its clauses are already present");
+            }
+            return;
+        }
+
+        self.models = None;
+
+        let contradiction = if self.kb.clauses().is_horn() {
+            let facts = Rules::new(self.kb.clauses()).facts();
+
+            let mut derived = Vec::from_iter(facts.difference(&self.facts).cloned());
+            derived.sort();
+            if normal && !derived.is_empty() {
+                println!("> Derived: {}", paint(&derived.join(", "), GREEN));
+            }
+
+            self.facts = facts;
+            horn::contradicts(self.kb.clauses(), &self.facts)
+        } else {
+            self.facts.clear();
+
+            let cnf = self.kb.resolve();
+            match self.verbosity {
+                Verbosity::Verbose => println!("> Resolved: {}", Self::resolved(cnf, &self.resolved)),
+                Verbosity::Normal => {
+                    let mut new = Cnf::new();
+                    for disj in cnf.terms.iter().filter(|d| !self.resolved.contains(d)) {
+                        new.insert(disj.clone());
+                    }
+                    if !new.terms.is_empty() {
+                        println!("> New clauses: {}", Self::resolved(&new, &Cnf::new()));
+                    }
+                },
+                Verbosity::Quiet => {},
+            }
+            self.resolved = cnf.clone();
+            if self.resolved.contains(&Disj::contradiction()) {
+                true
+            } else if self.kb.reached_limit() {
+                // Resolution stopped early, so the empty clause may not have been found yet
+                println!("> Resolution reached its limits, not all resolvents are shown");
+                self.kb.core(&Cnf::new()).is_some()
+            } else {
+                false
+            }
+        };
+
+        if contradiction {
+            if let Some(core) = self.kb.core(&Cnf::new()) {
+                println!("> Conflicting axioms: {}", paint(&self.labels(&core), BOLD_RED));
+            }
+            let depth = self.kb.depth();
+            if depth > 0 {
+                // A hypothesis led to the contradiction, so only the hypotheses are undone
+                println!("> {}", paint(&format!("Contradiction! Popping context {depth}"), BOLD_RED));
+                self.kb.pop();
+                self.refresh();
+                return;
+            }
+            println!("> {}", paint("Contradiction! Resetting statements", BOLD_RED));
+            self.clear_axioms();
+        }
+    }
+
+    /// Shows resolved clauses like [Cnf] does, with the clauses that are not in `old` in
+    /// green, if output is colored.
+    fn resolved(cnf: &Cnf, old: &Cnf) -> String {
+        if cnf.terms.is_empty() {
+            return String::from("*");
+        }
+
+        let clauses: Vec<String> = cnf.sorted().into_iter()
+            .map(|d| if old.contains(d) { format!("({d})") } else { paint(&format!("({d})"), GREEN) })
+            .collect();
+        return clauses.join(" & ");
+    }
+
+    /// Remembers the state of the knowledge base before a change, so that `:undo` can return to
+    /// it. At most [HISTORY] states are remembered. Changes that were undone can no longer be
+    /// redone after a new change.
+    fn checkpoint(&mut self, change: String, before: KnowledgeBase) {
+        if self.undo.len() == HISTORY {
+            self.undo.remove(0);
+        }
+        self.undo.push((change, before));
+        self.redo.clear();
+    }
+
+    /// Lists the given axioms by their label, see [Axiom::label].
+    fn labels(&self, indices: &[usize]) -> String {
+        let labels: Vec<String> = indices.iter().map(|i| self.kb.axioms()[*i].label()).collect();
+        return labels.join(", ");
+    }
+
+    /// Removes all axioms from the knowledge base.
+    fn clear_axioms(&mut self) {
+        self.kb.clear();
+        self.facts.clear();
+        self.models = None;
+        self.resolved = Cnf::new();
+    }
+
+    /// Declares an enum: exactly one of its values holds. Models show the value of the enum.
+    fn declare_enum(&mut self, name: &str, values: &[String]) {
+        let atoms: Vec<Stmt> = values.iter()
+            .map(|v| Stmt::symbol(&format!("{name}.{v}")))
+            .collect();
+
+        self.decoders.register(name, OneHot::new(name));
+        self.assert(None, &[], Stmt::exactly_one(&atoms));
+    }
+
+    /// Runs a command, i.e. an input starting with `:`.
+    fn command(&mut self, cmd: &str) {
+        let (name, args) = match cmd.split_once(char::is_whitespace) {
+            Some((name, args)) => (name, args.trim()),
+            None => (cmd, ""),
+        };
+
+        match COMMANDS.iter().find(|c| c.name == name) {
+            Some(c) => (c.run)(self, args),
+            None => error!("Unknown command ':{name}', use ':help' to list all commands"),
+        }
+    }
+
+    /// Shows the input syntax and all commands: `:help`, or the usage of a single
+    /// command: `:help <command>`.
+    fn help(&mut self, args: &str) {
+        if !args.is_empty() {
+            let name = args.strip_prefix(':').unwrap_or(args);
+            match COMMANDS.iter().find(|c| c.name == name) {
+                Some(c) => {
+                    for u in c.usage.iter() {
+                        println!("> {u}");
+                    }
+                    println!(">   {}", c.help);
+                },
+                None => error!("Unknown command ':{name}'"),
+            }
+            return;
+        }
+
+        println!("> Syntax:");
+        let width = SYNTAX.iter().map(|(s, _)| s.len()).max().unwrap_or(0);
+        for (syntax, help) in SYNTAX.iter() {
+            println!(">   {syntax:width$}  {help}");
+        }
+
+        println!("> Commands:");
+        let width = COMMANDS.iter().map(|c| c.usage[0].len()).max().unwrap_or(0);
+        for c in COMMANDS.iter() {
+            println!(">   {:width$}  {}", c.usage[0], c.help);
+        }
+        println!("> An empty input stops the REPL");
+    }
+
+    /// Returns the enabled features of this build.
+    fn features() -> Vec<&'static str> {
+        return FEATURES.iter().filter(|(_, on)| *on).map(|(name, _)| *name).collect();
+    }
+
+    /// Returns the limits on resolution, as shown in the banner.
+    fn limits(&self) -> String {
+        let limits = self.kb.limits();
+        let mut out = Vec::new();
+        if let Some(max) = limits.max_clauses {
+            out.push(format!("{max} clauses"));
+        }
+        if let Some(max) = limits.max_time {
+            out.push(format!("{}s", max.as_secs_f64()));
+        }
+        if let Some(max) = limits.max_length {
+            out.push(format!("{max} literals per clause"));
+        }
+        return if out.is_empty() { String::from("none") } else { out.join(", ") };
+    }
+
+    /// Prints the banner shown when the REPL starts: the version, backend, enabled features
+    /// and limits of this build, and the file that was loaded, if any.
+    fn banner(&self, file: Option<&str>) {
+        println!("> rustyproof {}", env!("CARGO_PKG_VERSION"));
+        println!("> Backend: {BACKEND}");
+        println!("> Features: {}", Self::features().join(", "));
+        println!("> Limits: {}", self.limits());
+        if let Some(file) = file {
+            println!("> File: {file}");
+        }
+        println!("> Type :help for the syntax and all commands");
+    }
+
+    /// Shows the data of the banner as a single line of JSON, without the usual `> ` before
+    /// it, for tools to find out what this build supports: `:version` or `:capabilities`.
+    fn capabilities(&mut self, _args: &str) {
+        let limits = self.kb.limits();
+        let max_clauses = limits.max_clauses.map_or(String::from("null"), |m| m.to_string());
+        let max_time = limits.max_time.map_or(String::from("null"), |m| m.as_secs_f64().to_string());
+        let max_length = limits.max_length.map_or(String::from("null"), |m| m.to_string());
+        let features: Vec<String> = Self::features().iter().map(|f| format!("\"{f}\"")).collect();
+        let commands: Vec<String> = COMMANDS.iter().map(|c| format!("\"{}\"", c.name)).collect();
+
+        println!(
+            "{{\"name\":\"rustyproof\",\"version\":\"{}\",\"backend\":\"{BACKEND}\",\"features\":[{}],\"limits\":{{\"max_clauses\":{max_clauses},\"max_time\":{max_time},\"max_length\":{max_length}}},\"commands\":[{}]}}",
+            env!("CARGO_PKG_VERSION"),
+            features.join(","),
+            commands.join(",")
+        );
+    }
+
+    /// Shows all axioms, and the clauses of the knowledge base: `:list`, or only the axioms
+    /// with a given tag: `:list @tag`.
+    fn list(&mut self, args: &str) {
+        let tag = if args.is_empty() {
+            None
+        } else {
+            match Self::tag(args) {
+                Some(tag) => Some(tag),
+                None => {
+                    error!("Expected ':list' or ':list @tag'");
+                    return;
+                }
+            }
+        };
+
+        let axioms: Vec<(usize, &Axiom)> = self.kb.axioms().iter()
+            .enumerate()
+            .filter(|(_, a)| tag.is_none_or(|t| a.tags.iter().any(|u| u == t)))
+            .collect();
+
+        if tag.is_none() && !self.kb.fixed().is_empty() {
+            let fixed: Vec<String> = self.kb.fixed().iter().map(|(s, v)| format!("{s}={v}")).collect();
+            println!("> Fixed: {}", fixed.join(", "));
+        }
+
+        if axioms.is_empty() {
+            println!("> No axioms");
+            return;
+        }
+
+        println!("> Axioms:");
+        for (i, axiom) in axioms {
+            if self.kb.is_enabled(i) {
+                println!(">   {}. {axiom}", i + 1);
+            } else {
+                println!(">   {}. {axiom} (disabled)", i + 1);
+            }
+        }
+
+        if tag.is_none() {
+            println!("> Clauses: {}", self.kb.clauses());
+        }
+    }
+
+    /// Reads the tag argument of a command: `@tag`.
+    fn tag(args: &str) -> Option<&str> {
+        let tag = args.strip_prefix('@')?;
+        if tag.is_empty() || tag.contains(char::is_whitespace) {
+            return None;
+        }
+        return Some(tag);
+    }
+
+    /// Leaves all axioms with a given tag out of the knowledge base: `:disable @tag`.
+    fn disable(&mut self, args: &str) {
+        let tag = match Self::tag(args) {
+            Some(tag) => tag,
+            None => {
+                error!("Expected ':disable @tag'");
+                return;
+            }
+        };
+
+        let before = self.kb.clone();
+        if !self.kb.disable(tag) {
+            error!("The tag @{tag} is disabled already");
+            return;
+        }
+        self.checkpoint(format!("disabling @{tag}"), before);
+
+        self.refresh();
+        println!("> Disabled the axioms tagged @{tag}");
+    }
+
+    /// Takes all axioms with a given tag back into the knowledge base: `:enable @tag`.
+    fn enable(&mut self, args: &str) {
+        let tag = match Self::tag(args) {
+            Some(tag) => tag,
+            None => {
+                error!("Expected ':enable @tag'");
+                return;
+            }
+        };
+
+        let before = self.kb.clone();
+        if !self.kb.enable(tag) {
+            error!("The tag @{tag} is not disabled");
+            return;
+        }
+        self.checkpoint(format!("enabling @{tag}"), before);
+
+        self.refresh();
+        println!("> Enabled the axioms tagged @{tag}");
+
+        if let Some(core) = self.kb.core(&Cnf::new()) {
+            println!("> {}", paint(&format!("Warning! The axioms contradict each other: {}", self.labels(&core)), YELLOW));
+        }
+    }
+
+    /// Reads an assignment of values to symbols: `P=true, Q=false`, separated by commas or
+    /// spaces.
+    fn assignment(args: &str) -> Option<Vec<(String, bool)>> {
+        let mut out = Vec::new();
+
+        for part in args.split([',', ' ', '\t']).filter(|p| !p.is_empty()) {
+            let (sym, value) = part.split_once('=')?;
+            let value = match value {
+                "true" => true,
+                "false" => false,
+                _ => return None,
+            };
+
+            match parse_stmt(sym) {
+                Result::Ok(Stmt::Symbol(sym)) => out.push((sym, value)),
+                _ => return None,
+            }
+        }
+
+        if out.is_empty() {
+            return None;
+        }
+        return Some(out);
+    }
+
+    /// Fixes symbols to a value for good, simplifying all axioms: `:fix P=true, Q=false`.
+    /// A symbol cannot be fixed to a value that contradicts the axioms.
+    fn fix(&mut self, args: &str) {
+        let assignment = match Self::assignment(args) {
+            Some(assignment) => assignment,
+            None => {
+                error!("Expected ':fix P=true' or ':fix P=false', for any symbol P");
+                return;
+            }
+        };
+
+        let before = self.kb.clone();
+        let mut fixed = Vec::new();
+        for (sym, value) in assignment {
+            let opposite = if value { Stmt::symbol(&sym).not() } else { Stmt::symbol(&sym) };
+            if self.kb.entails(&opposite) {
+                error!("The axioms entail {opposite}, so {sym} cannot be fixed to {value}");
+                break;
+            }
+
+            if let Err(msg) = self.kb.fix(&sym, value) {
+                error!("{msg}");
+                break;
+            }
+            println!("> Fixed {sym} to {value}");
+            fixed.push(format!("{sym}={value}"));
+        }
+
+        if !fixed.is_empty() {
+            self.checkpoint(format!("fixing {}", fixed.join(", ")), before);
+        }
+
+        self.refresh();
+        println!("> Clauses: {}", self.kb.clauses());
+    }
+
+    /// Shows what remains of the clauses of the knowledge base when symbols have the given
+    /// values: `:assume P=true, Q=false`. Unlike `:fix`, this leaves the axioms unchanged.
+    fn assume(&mut self, args: &str) {
+        let assignment = match Self::assignment(args) {
+            Some(assignment) => assignment,
+            None => {
+                error!("Expected ':assume P=true' or ':assume P=false', for any symbol P");
+                return;
+            }
+        };
+
+        let remaining = self.kb.clauses().assume(&assignment);
+        println!("> Remaining clauses: {remaining}");
+        if remaining.contains(&Disj::contradiction()) {
+            println!("> These values contradict the axioms");
+        }
+    }
+
+    /// Proves a statement under a hypothesis, which holds only during the proof, and shows the
+    /// implication it yields: `:deduce A |- B` proves `B` from the axioms along with `A`, and
+    /// then `A -> B` follows from the axioms alone. With `:deduce A |- B as name`, the
+    /// implication is asserted as an axiom named `name`, to use it as a lemma later on.
+    fn deduce(&mut self, args: &str) {
+        let usage = "Expected ':deduce A |- B' or ':deduce A |- B as name'";
+
+        let (hypothesis, goal) = match args.split_once("|-") {
+            Some(parts) => parts,
+            None => {
+                error!("{usage}");
+                return;
+            }
+        };
+        let (goal, name) = match goal.rsplit_once(" as ") {
+            Some((goal, name)) if !name.trim().is_empty() => (goal, Some(name.trim())),
+            _ => (goal, None),
+        };
+
+        let (hypothesis, goal) = match (parse_stmt(hypothesis), parse_stmt(goal)) {
+            (Result::Ok(h), Result::Ok(g)) => (h, g),
+            (Result::Err(e), _) | (_, Result::Err(e)) => {
+                error!("{e}");
+                return;
+            }
+        };
+
+        let lemma = hypothesis.clone().implies(goal.clone());
+        match self.kb.counter_model(&lemma) {
+            None => {
+                if self.kb.entails(&hypothesis.clone().not()) {
+                    println!("> The hypothesis {hypothesis} contradicts the axioms, so anything follows from it");
+                } else {
+                    println!("> Under the hypothesis {hypothesis}, {goal} follows");
+                }
+                self.print_proof(&lemma);
+                println!("> Deduced: {lemma}");
+            },
+            Some(m) => {
+                println!("> Under the hypothesis {hypothesis}, {goal} does not follow");
+
+                let mut cnf = self.kb.clauses().clone();
+                cnf.insert_all(&lemma.not().cnf());
+                println!("> Counterexample:");
+                self.print_model(&m.minimize(&cnf));
+                return;
+            }
+        }
+
+        if let Some(name) = name {
+            self.assert(Some(name), &[], lemma);
+        }
+    }
+
+    /// Steps through the resolvents of the clauses of the enabled axioms, see [Stepper]: `:step`
+    /// shows the next resolvent, `:step <n>` the next n, and `:step stop` stops stepping. When
+    /// breakpoints are set by `:break`, `:step` fast-forwards to the next resolvent that
+    /// matches one of them instead, or to the n-th next one, each time stepping at most as
+    /// many resolvents as the limit on clauses. Stepping starts over when the clauses changed since the last step.
+    fn step(&mut self, args: &str) {
+        if args == "stop" {
+            match self.stepper.take() {
+                Some(_) => println!("> Stopped stepping"),
+                None => println!("> Not stepping"),
+            }
+            return;
+        }
+
+        let count = if args.is_empty() { Result::Ok(1) } else { args.parse::<usize>() };
+        let count = match count {
+            Result::Ok(n) if n > 0 => n,
+            _ => {
+                error!("Expected ':step', ':step n' or ':step stop'");
+                return;
+            }
+        };
+
+        if self.kb.clauses().terms.is_empty() {
+            self.stepper = None;
+            println!("> There are no clauses to step through");
+            return;
+        }
+
+        let stepper = match self.stepper.take() {
+            Some(stepper) if stepper.premises().sorted() == self.kb.clauses().sorted() => stepper,
+            previous => {
+                let stepper = Stepper::new(self.kb.clauses());
+                let again = if previous.is_some() { "The clauses changed, stepping again from" } else { "Stepping from" };
+                println!("> {again} the clauses:");
+                for i in 0..stepper.steps().len() {
+                    Self::print_step(&stepper, i);
+                }
+                stepper
+            }
+        };
+        let stepper = self.stepper.insert(stepper);
+
+        if !self.breakpoints.is_empty() {
+            let limit = self.kb.limits().max_clauses.unwrap_or(usize::MAX);
+            for _ in 0..count {
+                let before = stepper.steps().len();
+                let found = stepper.run_until(&self.breakpoints, limit);
+                let skipped = stepper.steps().len() - before - usize::from(found.is_some());
+                match skipped {
+                    0 => {},
+                    1 => println!("> Skipped 1 resolvent"),
+                    n => println!("> Skipped {n} resolvents"),
+                }
+
+                let Some(i) = found else {
+                    if !stepper.is_done() {
+                        println!("> No breakpoint was hit within the limit on clauses, ':step' continues");
+                    }
+                    break;
+                };
+                let clause = &stepper.steps()[i].clause;
+                let hit = self.breakpoints.iter().find(|b| b.matches(clause)).unwrap();
+                println!("> Breakpoint {hit}:");
+                Self::print_step(stepper, i);
+            }
+        } else {
+            for _ in 0..count {
+                match stepper.step() {
+                    Some(i) => Self::print_step(stepper, i),
+                    None => break,
+                }
+            }
+        }
+
+        if stepper.is_refuted() {
+            println!("> {}", paint("Contradiction! The empty clause is derived", BOLD_RED));
+        } else if stepper.is_done() {
+            println!("> Saturated: no more resolvents follow");
+        }
+    }
+
+    /// Shows the step at the given position: its number, its clause, and the steps it is
+    /// resolved from.
+    fn print_step(stepper: &Stepper, i: usize) {
+        let step = &stepper.steps()[i];
+        match &step.parents {
+            None => println!(">   {}. {}", i + 1, step.clause),
+            Some((l, r, pivot)) => println!(">   {}. {}  {}, {} on {pivot}", i + 1, step.clause, l + 1, r + 1),
+        }
+    }
+
+    /// Manages the breakpoints of `:step`: `:break on <atom>` stops at resolvents in which the
+    /// atom occurs, `:break on <clause>` at resolvents that contain all literals of the
+    /// clause, like `:break on !Q | R`, and `:break list` and `:break clear` list and remove
+    /// all breakpoints.
+    fn breakpoint(&mut self, args: &str) {
+        let (sub, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        let rest = rest.trim();
+
+        match sub {
+            "on" if !rest.is_empty() => match rest.parse::<Breakpoint>() {
+                Result::Ok(breakpoint) if self.breakpoints.contains(&breakpoint) => {
+                    println!("> There is a breakpoint on {breakpoint} already");
+                },
+                Result::Ok(breakpoint) => {
+                    println!("> Set a breakpoint on {breakpoint}, ':step' fast-forwards to it");
+                    self.breakpoints.push(breakpoint);
+                },
+                Result::Err(msg) => error!("{msg}"),
+            },
+            "list" | "" if rest.is_empty() => {
+                if self.breakpoints.is_empty() {
+                    println!("> No breakpoints");
+                    return;
+                }
+                println!("> Breakpoints:");
+                for breakpoint in self.breakpoints.iter() {
+                    println!(">   {breakpoint}");
+                }
+            },
+            "clear" if rest.is_empty() => {
+                self.breakpoints.clear();
+                println!("> Removed all breakpoints");
+            },
+            _ => error!("Expected ':break on <atom>', ':break on <clause>', ':break list' or ':break clear'"),
+        }
+    }
+
+    /// Shows the disjunctive normal form of an expression, which lists the ways in which the
+    /// expression can be true: `:dnf <expression>`.
+    fn dnf(&mut self, args: &str) {
+        match parse_stmt(args).map_err(|e| e.to_string()).and_then(|s| s.try_dnf().map_err(|e| e.to_string())) {
+            Result::Ok(dnf) => println!("> DNF: {dnf}"),
+            Result::Err(msg) => error!("{msg}"),
+        }
+    }
+
+    /// Explains why an expression follows from the axioms: `:why <expression>`. The
+    /// expression is negated, and every resolution step from the axioms and its negation to
+    /// the empty clause is shown, along with the axiom that every premise comes from. If the
+    /// expression does not follow, a counterexample is shown instead.
+    fn why(&mut self, args: &str) {
+        let stmt = match parse_stmt(args) {
+            Result::Ok(s) => s,
+            Result::Err(e) => {
+                error!("{e}");
+                return;
+            }
+        };
+
+        if let Some(model) = self.kb.counter_model(&stmt) {
+            println!("> Not satisfied! {stmt} does not follow from the axioms");
+            self.failed = true;
+            println!("> Counterexample:");
+            self.print_model(&model);
+            return;
+        }
+
+        let Some(proof) = self.kb.proof(&stmt) else {
+            println!("> Satisfied! But no proof was found within the limits on resolution");
+            return;
+        };
+
+        println!("> Satisfied! Suppose {stmt} is false; then the axioms lead to a contradiction:");
+        for line in proof.to_string().lines() {
+            println!(">   {line}");
+        }
+        println!("> So {stmt} must be true");
+        self.proved = Some(stmt);
+    }
+
+    /// Writes a resolution proof as a graph in the DOT format: `:dot <file>` for the statement
+    /// proved last, or `:dot <file> <expression>` for an expression that follows from the
+    /// axioms.
+    fn dot(&mut self, args: &str) {
+        let (file, expr) = args.split_once(' ').map_or((args, ""), |(f, e)| (f, e.trim()));
+        if file.is_empty() {
+            error!("Expected ':dot <file>'");
+            return;
+        }
+
+        let stmt = match (expr, &self.proved) {
+            ("", Some(stmt)) => stmt.clone(),
+            ("", None) => {
+                error!("Nothing was proved yet");
+                return;
+            },
+            (expr, _) => match parse_stmt(expr) {
+                Result::Ok(s) => s,
+                Result::Err(e) => {
+                    error!("{e}");
+                    return;
+                }
+            },
+        };
+
+        let proof = match self.kb.proof(&stmt) {
+            Some(proof) => proof,
+            None if self.kb.entails(&stmt) => {
+                println!("> No proof of {stmt} found within the limits on resolution");
+                return;
+            },
+            None => {
+                error!("{stmt} does not follow from the axioms");
+                return;
+            },
+        };
+
+        match std::fs::write(file, proof.to_dot() + "\n") {
+            Result::Ok(()) => println!("> Wrote a proof of {stmt} in {} steps to {file}", proof.steps().len()),
+            Result::Err(e) => error!("Could not write to {file}: {e}"),
+        }
+    }
+
+    /// Shows an expression, its clauses and a proof of it in LaTeX: `:latex <expression>`. The
+    /// proof is only shown if the expression follows from the axioms.
+    fn latex(&mut self, args: &str) {
+        let stmt = match parse_stmt(args) {
+            Result::Ok(s) => s,
+            Result::Err(e) => {
+                error!("{e}");
+                return;
+            }
+        };
+
+        println!("> Statement: {}", stmt.to_latex());
+        println!("> CNF: {}", stmt.cnf().to_latex());
+        match self.kb.proof(&stmt) {
+            Some(proof) => {
+                println!("> Proof:");
+                for line in proof.to_latex().lines() {
+                    println!(">   {line}");
+                }
+            },
+            None if self.kb.entails(&stmt) => println!("> No proof found within the limits on resolution"),
+            None => println!("> No proof: it does not follow from the axioms"),
+        }
+    }
+
+    /// Tests whether an expression is valid, and otherwise whether it is satisfiable, in the
+    /// given modal logic: `:modal S4 []A -> [][]A`.
+    fn modal(&mut self, args: &str) {
+        let (system, expr) = args.split_once(' ').unwrap_or((args, ""));
+        let system = match system.parse::<System>() {
+            Result::Ok(system) => system,
+            Result::Err(msg) => {
+                error!("{msg}");
+                return;
+            }
+        };
+        let stmt = match parse_stmt(expr.trim()) {
+            Result::Ok(s) => s,
+            Result::Err(e) => {
+                error!("{e}");
+                return;
+            }
+        };
+
+        let answer = modal::is_valid(&stmt, system).and_then(|valid| {
+            return Result::Ok((valid, valid || modal::is_satisfiable(&stmt, system)?));
+        });
+        match answer {
+            Result::Ok((true, _)) => println!("> Valid in {system}: true in every world of every model"),
+            Result::Ok((false, true)) => println!("> Satisfiable in {system}, but not valid: false in some world of some model"),
+            Result::Ok((false, false)) => println!("> Unsatisfiable in {system}: false in every world of every model"),
+            Result::Err(msg) => error!("{msg}"),
+        }
+    }
+
+    /// Explains how an expression is parsed, to find out why it means something unexpected:
+    /// `:parse <expression>`. Shows the tokens, the tree of operators with their operands, and
+    /// the expression with every operator parenthesized.
+    fn parse(&mut self, args: &str) {
+        let tokens = match tokenize(args) {
+            Result::Ok(tokens) => tokens,
+            Result::Err(e) => {
+                error!("{e}");
+                return;
+            }
+        };
+        let stmt = match parse_stmt(args) {
+            Result::Ok(s) => s,
+            Result::Err(e) => {
+                error!("{e}");
+                return;
+            }
+        };
+
+        let tokens: Vec<String> = tokens.iter().map(|t| format!("[{t}]")).collect();
+        println!("> Tokens: {}", tokens.join(" "));
+        println!("> Tree:");
+        for line in stmt.tree() {
+            println!(">   {line}");
+        }
+        println!("> Parenthesized: {stmt}");
+    }
+
+    /// Simplifies an expression into the smallest equivalent sum of products and product of
+    /// sums: `:simplify <expression>`.
+    fn simplify(&mut self, args: &str) {
+        let stmt = match parse_stmt(args) {
+            Result::Ok(s) => s,
+            Result::Err(e) => {
+                error!("{e}");
+                return;
+            }
+        };
+
+        match (minimize::minimal_dnf(&stmt), minimize::minimal_cnf(&stmt)) {
+            (Result::Ok(dnf), Result::Ok(cnf)) => {
+                println!("> Sum of products: {dnf}");
+                println!("> Product of sums: {cnf}");
+            },
+            (Result::Err(msg), _) | (_, Result::Err(msg)) => error!("{msg}"),
+        }
+    }
+
+    /// Shows the lemmas: unit and binary clauses that were proved by earlier questions, and
+    /// answer later questions without proving them again: `:lemmas`. They are forgotten by
+    /// `:lemmas clear`.
+    fn lemmas(&mut self, args: &str) {
+        match args {
+            "" if self.kb.lemmas().terms.is_empty() => println!("> No lemmas"),
+            "" => println!("> Lemmas: {}", self.kb.lemmas()),
+            "clear" => {
+                self.kb.clear_lemmas();
+                println!("> Forgot all lemmas");
+            },
+            _ => error!("Expected ':lemmas' or ':lemmas clear'"),
+        }
+    }
+
+    /// Answers a batch of questions at once: `:queries { A? ; B? ; C? }`. This is faster than
+    /// asking them one by one, especially for related questions.
+    fn queries(&mut self, args: &str) {
+        let args = args.strip_prefix('{').and_then(|a| a.strip_suffix('}')).unwrap_or(args);
+
+        let mut questions = Vec::new();
+        for part in args.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+            match parse_statement(part) {
+                Result::Ok(Statement::Question(q)) => questions.push(q),
+                Result::Ok(_) => {
+                    error!("Expected a question, found '{part}'");
+                    return;
+                },
+                Result::Err(e) => {
+                    error!("{} in '{part}'", e);
+                    return;
+                }
+            }
+        }
+
+        if questions.is_empty() {
+            error!("Expected ':queries {{ A? ; B? }}'");
+            return;
+        }
+
+        let answers = self.kb.entails_all(&questions);
+        for (q, entailed) in questions.iter().zip(answers.iter()) {
+            if *entailed {
+                println!("> {q}? Satisfied!");
+            } else {
+                println!("> {q}? Not satisfied!");
+                self.failed = true;
+            }
+        }
+
+        let n = answers.iter().filter(|a| **a).count();
+        println!("> {n} of {} questions follow from the axioms", questions.len());
+    }
+
+    /// Turns automatic escalation on or off: `:auto on` answers questions by containment, unit
+    /// propagation, bounded resolution and the solver, in this order, until one of them answers,
+    /// within a budget of a second, or the given number of seconds with `:auto on <seconds>`.
+    /// `:auto off` answers them by resolution and the solver again.
+    fn auto(&mut self, args: &str) {
+        let (sub, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+
+        match (sub, rest.trim()) {
+            ("on", secs) => {
+                let budget = match secs {
+                    "" => AUTO_BUDGET,
+                    _ => match secs.parse().ok().filter(|s: &f64| *s > 0.0) {
+                        Some(secs) => Duration::from_secs_f64(secs),
+                        None => {
+                            error!("Expected a number of seconds, found '{secs}'");
+                            return;
+                        }
+                    },
+                };
+                self.auto = Some(budget);
+                println!("> Questions are answered by escalating methods, with a budget of {}s", budget.as_secs_f64());
+            },
+            ("off", "") => {
+                self.auto = None;
+                println!("> Questions are answered by resolution and the solver");
+            },
+            _ => error!("Expected ':auto on', ':auto on <seconds>' or ':auto off'"),
+        }
+    }
+
+    /// Shows how much is shown after every axiom: `:verbosity`, or sets it:
+    /// `:verbosity quiet`, `:verbosity normal` or `:verbosity verbose`.
+    fn verbosity(&mut self, args: &str) {
+        if !args.is_empty() {
+            match args.parse() {
+                Result::Ok(verbosity) => self.verbosity = verbosity,
+                Result::Err(msg) => {
+                    error!("{msg}");
+                    return;
+                }
+            }
+        }
+
+        let shown = match self.verbosity {
+            Verbosity::Quiet => "only answers, errors and contradictions are shown",
+            Verbosity::Normal => "the clauses of every axiom, and what is newly derived from it, are shown",
+            Verbosity::Verbose => "the clauses of every axiom, and all resolved clauses after it, are shown",
+        };
+        println!("> Verbosity {}: {shown}", self.verbosity);
+    }
+
+    /// Manages scenarios: sets of values of symbols under which input can be run, to see what
+    /// would follow if the symbols had these values. `:scenario create <name> {P=true, Q=false}`
+    /// creates a scenario, `:scenario run <name> <input>` runs any input with the symbols fixed
+    /// to their values, and `:scenario list` and `:scenario delete <name>` list and delete
+    /// scenarios. The knowledge base is left as it was after running input in a scenario. A
+    /// scenario whose values together contradict the axioms is not run.
+    fn scenario(&mut self, args: &str) {
+        let (sub, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        let rest = rest.trim();
+
+        match sub {
+            "create" => {
+                let (name, values) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                let values = values.trim();
+                let values = values.strip_prefix('{').and_then(|v| v.strip_suffix('}')).unwrap_or(values);
+
+                let assignment = match Self::assignment(values) {
+                    Some(assignment) if !name.is_empty() => assignment,
+                    _ => {
+                        error!("Expected ':scenario create name {{P=true, Q=false}}'");
+                        return;
+                    }
+                };
+
+                let shown: Vec<String> = assignment.iter().map(|(s, v)| format!("{s}={v}")).collect();
+                println!("> Created scenario {name}: {{{}}}", shown.join(", "));
+                self.scenarios.insert(name.to_string(), assignment);
+            },
+            "run" => {
+                let (name, input) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                if input.trim().is_empty() {
+                    error!("Expected ':scenario run name input'");
+                    return;
+                }
+
+                let assignment = match self.scenarios.get(name) {
+                    Some(assignment) => assignment.clone(),
+                    None => {
+                        error!("There is no scenario {name}, use ':scenario list' to show all scenarios");
+                        return;
+                    }
+                };
+
+                // The values are checked together, as they can be consistent one by one but not
+                // all at once
+                let values = Stmt::all(assignment.iter().map(|(sym, value)| {
+                    if *value { Stmt::symbol(sym) } else { Stmt::symbol(sym).not() }
+                }).collect());
+                let opposite = values.not();
+                if self.kb.entails(&opposite) {
+                    error!("The axioms entail {opposite}, so scenario {name} cannot hold");
+                    return;
+                }
+
+                // Changes within the scenario are discarded, so they cannot be undone either
+                let kb = self.kb.clone();
+                let undo = std::mem::take(&mut self.undo);
+                let redo = std::mem::take(&mut self.redo);
+                let fixed = assignment.iter().try_for_each(|(sym, value)| self.kb.fix(sym, *value));
+                self.refresh();
+
+                match fixed {
+                    Result::Ok(()) => {
+                        self.eval(input.trim());
+                    },
+                    Result::Err(msg) => error!("Cannot run scenario {name}: {msg}"),
+                }
+
+                self.kb = kb;
+                self.undo = undo;
+                self.redo = redo;
+                self.refresh();
+            },
+            "list" => {
+                if self.scenarios.is_empty() {
+                    println!("> No scenarios");
+                    return;
+                }
+
+                println!("> Scenarios:");
+                for (name, assignment) in self.scenarios.iter() {
+                    let shown: Vec<String> = assignment.iter().map(|(s, v)| format!("{s}={v}")).collect();
+                    println!(">   {name}: {{{}}}", shown.join(", "));
+                }
+            },
+            "delete" => {
+                if self.scenarios.remove(rest).is_none() {
+                    error!("There is no scenario {rest}, use ':scenario list' to show all scenarios");
+                    return;
+                }
+                println!("> Deleted scenario {rest}");
+            },
+            _ => error!("Expected ':scenario create', ':scenario run', ':scenario list' or ':scenario delete'"),
+        }
+    }
+
+    /// Manages several knowledge bases in one session, one of which is the current one that
+    /// input works on: `:kb create <name>` creates an empty one, `:kb switch <name>` makes
+    /// another the current one, `:kb copy <name>` copies the current one under a new name,
+    /// `:kb list` shows all, and `:kb delete <name>` deletes one that is not current.
+    fn knowledge_base(&mut self, args: &str) {
+        let (sub, name) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        let name = name.trim();
+
+        if sub == "list" || sub.is_empty() {
+            println!("> Knowledge bases:");
+            let current = std::iter::once((&self.current, &self.kb));
+            let mut all = Vec::from_iter(current.chain(self.others.iter()));
+            all.sort_by_key(|(name, _)| name.as_str());
+            for (name, kb) in all {
+                let marker = if *name == self.current { " (current)" } else { "" };
+                println!(">   {name}: {} axioms{marker}", kb.axioms().len());
+            }
+            return;
+        }
+
+        if !matches!(sub, "create" | "switch" | "copy" | "delete") {
+            error!("Expected ':kb create', ':kb switch', ':kb copy', ':kb list' or ':kb delete'");
+            return;
+        }
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            error!("Expected ':kb {sub} name'");
+            return;
+        }
+
+        let exists = name == self.current || self.others.contains_key(name);
+        match sub {
+            "create" | "copy" if exists => {
+                error!("There is a knowledge base {name} already, use ':kb delete {name}' first");
+            },
+            "create" => {
+                let mut kb = KnowledgeBase::new();
+                kb.set_limits(self.kb.limits().clone());
+                self.others.insert(name.to_string(), kb);
+                println!("> Created knowledge base {name}, use ':kb switch {name}' to work on it");
+            },
+            "copy" => {
+                self.others.insert(name.to_string(), self.kb.clone());
+                println!("> Copied knowledge base {} to {name}", self.current);
+            },
+            "switch" if name == self.current => println!("> Already working on knowledge base {name}"),
+            "delete" if name == self.current => {
+                error!("Cannot delete the current knowledge base, use ':kb switch' to another one first");
+            },
+            _ if !exists => {
+                error!("There is no knowledge base {name}, use ':kb list' to show all knowledge bases");
+            },
+            "switch" => {
+                let kb = self.others.remove(name).unwrap();
+                let previous = std::mem::replace(&mut self.kb, kb);
+                let previous_name = std::mem::replace(&mut self.current, name.to_string());
+                self.others.insert(previous_name, previous);
+                self.undo.clear();
+                self.redo.clear();
+                self.refresh();
+                self.proved = None;
+                println!("> Switched to knowledge base {name}, with {} axioms", self.kb.axioms().len());
+            },
+            _ => {
+                self.others.remove(name);
+                println!("> Deleted knowledge base {name}");
+            },
+        }
+    }
+
+    /// Returns to the state of the knowledge base before the last change to it: `:undo`.
+    fn undo(&mut self, _args: &str) {
+        let Some((change, before)) = self.undo.pop() else {
+            error!("There is nothing to undo");
+            return;
+        };
+
+        let after = std::mem::replace(&mut self.kb, before);
+        self.redo.push((change.clone(), after));
+        self.refresh();
+        println!("> Undid {change}, back to {} axioms", self.kb.axioms().len());
+    }
+
+    /// Makes the last change that `:undo` undid again: `:redo`.
+    fn redo(&mut self, _args: &str) {
+        let Some((change, after)) = self.redo.pop() else {
+            error!("There is nothing to redo");
+            return;
+        };
+
+        let before = std::mem::replace(&mut self.kb, after);
+        self.undo.push((change.clone(), before));
+        self.refresh();
+        println!("> Redid {change}, now at {} axioms", self.kb.axioms().len());
+    }
+
+    /// Removes all axioms from the knowledge base: `:clear`.
+    fn clear(&mut self, _args: &str) {
+        self.checkpoint(String::from("clearing all axioms"), self.kb.clone());
+        self.clear_axioms();
+        println!("> Cleared all axioms");
+    }
+
+    /// Removes an axiom from the knowledge base, given its number as shown by `:list` or its
+    /// name: `:retract <n>` or `:retract <name>`. Everything that was derived from the axiom
+    /// is retracted along with it.
+    fn retract(&mut self, args: &str) {
+        if args.is_empty() {
+            error!("Expected ':retract n' or ':retract name'");
+            return;
+        }
+
+        let index = match args.parse::<usize>() {
+            Ok(n) => n.checked_sub(1),
+            Err(_) => self.kb.find(args),
+        };
+
+        let before = self.kb.clone();
+        let axiom = match index.and_then(|i| self.kb.retract(i)) {
+            Some(axiom) => axiom,
+            None => {
+                error!("There is no axiom {args}, use ':list' to show all axioms");
+                return;
+            }
+        };
+        self.checkpoint(format!("retracting {axiom}"), before);
+
+        self.refresh();
+        println!("> Retracted: {axiom}");
+    }
+
+    /// Saves the state of the knowledge base, see [KnowledgeBase::push]: `:push`.
+    fn push(&mut self, args: &str) {
+        if !args.is_empty() {
+            error!("Expected ':push'");
+            return;
+        }
 
-pub fn repl() {
-    let mut cnf = Cnf::new();
+        let depth = self.kb.push();
+        println!("> Pushed context {depth}, ':pop' returns to the state before it");
+    }
 
-    let stdin = io::stdin();
-    for line in stdin.lock().lines() {
-        let ln = line.unwrap();
+    /// Returns to the state saved by the last `:push`, see [KnowledgeBase::pop]: `:pop`, or
+    /// `:pop <n>` to undo the last n pushes at once.
+    fn pop(&mut self, args: &str) {
+        let count = if args.is_empty() { Result::Ok(1) } else { args.parse::<usize>() };
+        let count = match count {
+            Result::Ok(n) if n > 0 => n,
+            _ => {
+                error!("Expected ':pop' or ':pop n'");
+                return;
+            }
+        };
 
-        let mut par = Parser::new(ln);
-        match par.expr() {
+        let depth = self.kb.depth();
+        if depth == 0 {
+            error!("There is no context to pop, use ':push' to save one");
+            return;
+        }
+        if count > depth {
+            error!("There are only {depth} contexts to pop, use ':push' to save one");
+            return;
+        }
 
-            // on question: check if we have the requested statement along our knowledge
-            ParsedStatement::Question(o) => {
-                let n = o.cnf();
-                println!("> CNF: {n}");
+        for _ in 0..count {
+            self.kb.pop();
+        }
+        self.refresh();
+        println!("> Popped back to context {}, with {} axioms", depth - count, self.kb.axioms().len());
+    }
 
-                if cnf.contains_all(&n) {
-                    println!("> Satisfied!")
-                } else {
-                    println!("> Not satisfied!")
+    /// Forgets everything that was derived from the clauses of the knowledge base, after they
+    /// changed in another way than by asserting an axiom.
+    fn refresh(&mut self) {
+        self.models = None;
+        self.facts = if self.kb.clauses().is_horn() {
+            Rules::new(self.kb.clauses()).facts()
+        } else {
+            HashSet::new()
+        };
+    }
+
+    /// Writes all axioms to a file: `:save <path>`.
+    fn save(&mut self, args: &str) {
+        if args.is_empty() {
+            error!("Expected ':save path'");
+            return;
+        }
+
+        match self.kb.save(args) {
+            Ok(()) => println!("> Saved {} axioms to {args}", self.kb.axioms().len()),
+            Err(msg) => error!("Could not save to {args}: {msg}"),
+        }
+    }
+
+    /// Replaces all axioms by the axioms in a file: `:load <path>`.
+    fn load(&mut self, args: &str) {
+        if args.is_empty() {
+            error!("Expected ':load path'");
+            return;
+        }
+
+        match KnowledgeBase::load(args) {
+            Result::Ok(kb) => self.loaded(kb, args),
+            Result::Err(msg) => error!("Could not load {args}: {msg}"),
+        }
+    }
+
+    /// Replaces the knowledge base by one that was loaded from the given source, under the
+    /// same limits, and warns if its axioms contradict each other.
+    fn loaded(&mut self, mut kb: KnowledgeBase, source: &str) {
+        kb.set_limits(self.kb.limits().clone());
+        let before = std::mem::replace(&mut self.kb, kb);
+        self.checkpoint(format!("loading {source}"), before);
+        self.refresh();
+        println!("> Loaded {} axioms from {source}", self.kb.axioms().len());
+
+        if let Some(core) = self.kb.core(&Cnf::new()) {
+            println!("> {}", paint(&format!("Warning! The axioms contradict each other: {}", self.labels(&core)), YELLOW));
+        }
+    }
+
+    /// Compares the knowledge base to the axioms in a file, as saved by `:save`: `:diff <path>`.
+    /// Shows which axioms and clauses were added, removed or changed since the file was saved.
+    fn diff(&mut self, args: &str) {
+        if args.is_empty() {
+            error!("Expected ':diff path'");
+            return;
+        }
+
+        let old = match KnowledgeBase::load(args) {
+            Ok(kb) => kb,
+            Err(msg) => {
+                error!("Could not load {args}: {msg}");
+                return;
+            }
+        };
+
+        let diff = self.kb.diff(&old);
+        if diff.is_empty() {
+            println!("> No differences");
+            return;
+        }
+
+        for axiom in diff.added.iter() {
+            println!("> Added: {axiom}");
+        }
+        for axiom in diff.removed.iter() {
+            println!("> Removed: {axiom}");
+        }
+        for (old, new) in diff.changed.iter() {
+            println!("> Changed: {old}");
+            println!(">      to: {new}");
+        }
+
+        if !diff.added_clauses.terms.is_empty() {
+            println!("> Added clauses: {}", diff.added_clauses);
+        }
+        if !diff.removed_clauses.terms.is_empty() {
+            println!("> Removed clauses: {}", diff.removed_clauses);
+        }
+    }
+
+    /// Compares the knowledge base to the axioms in a file by entailment: `:compare <path>`.
+    /// Shows whether either entails the other, along with models that witness the differences.
+    fn compare(&mut self, args: &str) {
+        if args.is_empty() {
+            error!("Expected ':compare path'");
+            return;
+        }
+
+        let other = match KnowledgeBase::load(args) {
+            Ok(kb) => kb,
+            Err(msg) => {
+                error!("Could not load {args}: {msg}");
+                return;
+            }
+        };
+
+        match self.kb.compare(&other) {
+            Entailment::Equivalent => {
+                println!("> Equivalent: the axioms and {args} entail each other");
+            },
+            Entailment::Stronger(m) => {
+                println!("> Stronger: the axioms entail {args}, but not the other way around");
+                println!("> Model of {args} only:");
+                self.print_model(&m);
+            },
+            Entailment::Weaker(m) => {
+                println!("> Weaker: {args} entails the axioms, but not the other way around");
+                println!("> Model of the axioms only:");
+                self.print_model(&m);
+            },
+            Entailment::Neither(m, n) => {
+                println!("> Neither the axioms nor {args} entails the other");
+                println!("> Model of the axioms only:");
+                self.print_model(&m);
+                println!("> Model of {args} only:");
+                self.print_model(&n);
+            },
+        }
+    }
+
+    /// Replaces the axioms by a smaller set of clauses with the same models: `:compact`.
+    /// Auxiliary symbols, whose names start with `_`, may be eliminated.
+    fn compact(&mut self, _args: &str) {
+        let size = |cnf: &Cnf| {
+            let literals: usize = cnf.terms.iter().map(Disj::len).sum();
+            return (cnf.terms.len(), literals);
+        };
+
+        let (clauses, literals) = size(self.kb.clauses());
+        self.checkpoint(String::from("compacting"), self.kb.clone());
+        self.kb.compact();
+        self.refresh();
+
+        let (new_clauses, new_literals) = size(self.kb.clauses());
+        println!("> Compacted {clauses} clauses with {literals} literals into {new_clauses} clauses with {new_literals} literals");
+    }
+
+    /// Compacts the knowledge base, see [KnowledgeBase::gc]: `:gc`.
+    fn gc(&mut self, _args: &str) {
+        match self.kb.gc() {
+            0 => println!("> Nothing to free"),
+            n => println!("> Freed room for {n} clauses"),
+        }
+    }
+
+    /// Shows the size of the knowledge base: the number of axioms, of clauses of the enabled
+    /// axioms, of which unit clauses, and of symbols in them. Also shows the work done
+    /// computing resolvents so far, see [crate::cnf::SolveStats].
+    fn stats(&mut self, _args: &str) {
+        let clauses = self.kb.clauses();
+        let units = clauses.terms.iter().filter(|d| d.len() == 1).count();
+
+        println!("> Axioms: {}", self.kb.axioms().len());
+        println!("> Clauses: {}", clauses.terms.len());
+        println!("> Unit clauses: {units}");
+        println!("> Symbols: {}", clauses.symbols().len());
+        let stats = self.kb.stats();
+        println!("> Inferences: {}", stats.inferences);
+        println!("> Generated: {}", stats.generated);
+        println!("> Subsumed: {}", stats.subsumed);
+        if stats.is_incomplete() {
+            println!("> Discarded: {}, so resolution is possibly incomplete", stats.discarded);
+        }
+        println!("> Solving time: {}s", stats.time.as_secs_f64());
+    }
+
+    /// Points out suspicious parts of the encoding of the enabled axioms: `:lint`. The rules
+    /// are listed by `:lint rules`, configured by `:lint level <rule> <severity>`, and single
+    /// findings are suppressed by `:lint allow <rule> <subject>`.
+    fn lint(&mut self, args: &str) {
+        let words: Vec<&str> = args.split_whitespace().collect();
+
+        match words[..] {
+            [] => {
+                let lints = self.linter.lint(&self.kb);
+                if lints.is_empty() {
+                    println!("> No findings, the encoding looks fine");
+                }
+                for lint in lints {
+                    println!("> {lint}");
+                }
+            },
+            ["rules"] => {
+                for rule in RULES.iter() {
+                    println!("> {} ({}): {}", rule.id, self.linter.level(rule), rule.description);
                 }
             },
+            ["level", rule, level] => {
+                let severity = match Severity::parse(level) {
+                    Some(s) => s,
+                    None => {
+                        error!("Unknown severity '{level}', expected off, info, warning or error");
+                        return;
+                    }
+                };
 
-            // on axiom: compute further resolvents from the axiom and existing knowledge
-            ParsedStatement::Axiom(o) => {
-                let n = o.cnf();
-                println!("> CNF: {n}");
+                match self.linter.set_level(rule, severity) {
+                    Result::Ok(()) => println!("> Set the severity of {rule} to {severity}"),
+                    Result::Err(msg) => error!("{msg}"),
+                }
+            },
+            ["allow", rule, ..] if words.len() > 2 => {
+                let subject = words[2..].join(" ");
+                match self.linter.allow(rule, &subject) {
+                    Result::Ok(()) => println!("> Suppressed the findings of {rule} about {subject}"),
+                    Result::Err(msg) => error!("{msg}"),
+                }
+            },
+            _ => error!("Expected ':lint', ':lint rules', ':lint level <rule> <severity>' or ':lint allow <rule> <subject>'"),
+        }
+    }
+
+    /// Starts over entirely: `:reset`. Settings, like the verbosity and the limits, are kept.
+    fn reset(&mut self, _args: &str) {
+        let (verbosity, failed, malformed) = (self.verbosity, self.failed, self.malformed);
+        let limits = self.kb.limits().clone();
+        *self = Repl::new();
+        self.kb.set_limits(limits);
+        self.verbosity = verbosity;
+        self.failed = failed;
+        self.malformed = malformed;
+        println!("> Reset everything");
+    }
+
+    /// Prints a model of the knowledge base: `:model` shows the first model, `:model next`
+    /// shows the next one, and `:model where P=true, Q=false` shows the first model in which
+    /// the given symbols have the given values. Further models are shown by `:model next`.
+    ///
+    /// Models are minimized, so they only show the symbols whose value matters. Models that
+    /// only differ in the symbols whose value does not matter are shown only once.
+    ///
+    /// The model that was shown last is saved under a name by `:model save <name>`, so that it
+    /// can be used as hint by `:solve hint <name>`.
+    fn model(&mut self, args: &str) {
+        if let Some(name) = args.strip_prefix("save") {
+            let name = name.trim();
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                error!("Expected ':model save name'");
+                return;
+            }
+
+            match &self.shown {
+                Some(m) => {
+                    self.saved.insert(name.to_string(), m.clone());
+                    println!("> Saved model {name}: {m}");
+                },
+                None => error!("No model to save, use ':model' or ':solve' first"),
+            }
+            return;
+        }
+
+        if args == "next" {
+            if self.models.is_none() {
+                error!("No models to page through, use ':model' first");
+                return;
+            }
+        } else {
+            let mut models = Models::minimal(self.kb.clauses());
+
+            if let Some(constraints) = args.strip_prefix("where") {
+                for c in constraints.split(|c: char| c == ',' || c.is_whitespace()).filter(|c| !c.is_empty()) {
+                    let disj = match c.split_once('=') {
+                        Some((sym, "true")) => Disj::axiom(sym),
+                        Some((sym, "false")) => Disj::axiom_not(sym),
+                        _ => {
+                            error!("Expected 'P=true' or 'P=false', found '{c}'");
+                            return;
+                        }
+                    };
+                    models.constrain(&disj);
+                }
+            } else if !args.is_empty() {
+                error!("Expected ':model', ':model next' or ':model where P=true'");
+                return;
+            }
+
+            self.models = Some(models);
+        }
+
+        self.shown = self.models.as_mut().and_then(Models::next);
+
+        match &self.shown {
+            Some(m) => {
+                self.print_model(m);
+
+                let mut free = Vec::new();
+                for disj in self.kb.clauses().terms.iter() {
+                    free.extend(disj.literals().iter().map(|l| l.atom.name()).filter(|s| m.get(s).is_none()));
+                }
+                free.sort();
+                free.dedup();
+
+                if !free.is_empty() {
+                    println!("> Don't care: {}", free.join(", "));
+                }
+            },
+            None if args == "next" => println!("> No more models"),
+            None => println!("> No model!"),
+        }
+    }
 
-                cnf.insert_all(&n);
+    /// Prints a model of the knowledge base that assigns every symbol: `:solve`. Given a model
+    /// saved by `:model save <name>`, `:solve hint <name>` prefers the values of that model, so
+    /// that a model of a slightly changed knowledge base is found with little search.
+    fn solve(&mut self, args: &str) {
+        let mut solver = Solver::new();
+        solver.add_cnf(self.kb.clauses());
 
-                let mut other = Cnf::new();
+        let hint = if let Some(name) = args.strip_prefix("hint") {
+            let name = name.trim();
+            match self.saved.get(name) {
+                Some(m) => {
+                    solver.hint(m);
+                    Some((name, m))
+                },
+                None => {
+                    error!("No saved model named '{name}', use ':model save {name}' first");
+                    return;
+                }
+            }
+        } else if args.is_empty() {
+            None
+        } else {
+            error!("Expected ':solve' or ':solve hint name'");
+            return;
+        };
+
+        self.shown = solver.solve();
+
+        match &self.shown {
+            Some(m) => {
+                self.print_model(m);
+
+                if let Some((name, hint)) = hint {
+                    let changed = hint.iter().filter(|(s, v)| m.get(s).is_some_and(|w| w != **v)).count();
+                    println!("> Differs from {name} in {changed} symbols");
+                }
+            },
+            None => println!("> No model!"),
+        }
+    }
+
+    /// Prints multiple models of the knowledge base: `:models diverse k` shows `k` models that
+    /// differ from each other as much as possible.
+    fn models(&mut self, args: &str) {
+        let k = match args.strip_prefix("diverse").map(|k| k.trim().parse::<usize>()) {
+            Some(Ok(k)) if k > 0 => k,
+            _ => {
+                error!("Expected ':models diverse k', where k is at least 1");
+                return;
+            }
+        };
+
+        let models = solver::diverse_models(self.kb.clauses(), k);
+        if models.is_empty() {
+            println!("> No model!");
+            return;
+        }
+
+        for m in models.iter() {
+            self.print_model(m);
+        }
+
+        if models.len() < k {
+            println!("> Found only {} models", models.len());
+        }
+
+        if models.len() > 1 {
+            let mut min = usize::MAX;
+            let mut sum = 0;
+            for i in 0..models.len() {
+                for j in (i + 1)..models.len() {
+                    let d = models[i].distance(&models[j]);
+                    min = min.min(d);
+                    sum += d;
+                }
+            }
 
-                loop {
-                    other.clear();
-                    cnf.resolve(&mut other);
-                    if !cnf.insert_all(&other) {
-                        break;
+            let pairs = models.len() * (models.len() - 1) / 2;
+            println!("> Pairwise distance: minimum {min}, average {:.2}", sum as f64 / pairs as f64);
+        }
+    }
+
+    /// Adds a soft statement: `:soft <weight> <expression>`. Lists the soft statements with
+    /// `:soft`, and removes them with `:soft clear`.
+    fn soft(&mut self, args: &str) {
+        match args {
+            "" if self.kb.soft().is_empty() => println!("> No soft statements"),
+            "" => {
+                for (i, (stmt, weight)) in self.kb.soft().iter().enumerate() {
+                    println!("> {}: {stmt} (weight {weight})", i + 1);
+                }
+            },
+            "clear" => {
+                self.kb.clear_soft();
+                println!("> Removed all soft statements");
+            },
+            _ => {
+                let (weight, expr) = args.split_once(' ').unwrap_or((args, ""));
+                let weight = match weight.parse::<u64>() {
+                    Result::Ok(w) => w,
+                    Result::Err(_) => {
+                        error!("Expected ':soft <weight> <expression>', with a whole number as weight");
+                        return;
                     }
+                };
+                match parse_stmt(expr.trim()) {
+                    Result::Ok(stmt) => {
+                        println!("> Soft statement {}: {stmt} (weight {weight})", self.kb.soft().len() + 1);
+                        self.kb.assert_soft(stmt, weight);
+                    },
+                    Result::Err(e) => error!("{e}"),
+                }
+            },
+        }
+    }
+
+    /// Shows a model of the knowledge base that makes soft statements of the largest total
+    /// weight true, along with the soft statements it makes false: `:maxsat`.
+    fn maxsat(&mut self, _args: &str) {
+        let optimum = match self.kb.maximize() {
+            Some(o) => o,
+            None => {
+                println!("> No model!");
+                return;
+            }
+        };
+
+        let total: u64 = self.kb.soft().iter().map(|(_, w)| w).sum();
+        self.print_model(&optimum.model);
+        println!("> Satisfied weight: {} of {total}", optimum.weight);
+        for i in optimum.violated.iter() {
+            let (stmt, weight) = &self.kb.soft()[*i];
+            println!("> Violated {}: {stmt} (weight {weight})", i + 1);
+        }
+
+        self.shown = Some(optimum.model);
+    }
+
+    /// Checks a chain of proof obligations: given `A => B => C`, checks that `B` follows from the
+    /// knowledge base along with `A`, and that `C` follows from the knowledge base along with
+    /// `B`. For every step that does not follow, a counterexample is shown.
+    fn derive(&mut self, steps: &[Stmt]) {
+        let mut failed = Vec::new();
+
+        for (i, step) in steps.windows(2).enumerate() {
+            let obligation = step[0].clone().implies(step[1].clone());
+
+            match self.kb.counter_model(&obligation) {
+                None => {
+                    println!("> Step {}: {} => {} holds", i + 1, step[0], step[1]);
+                    self.print_proof(&obligation);
+                },
+                Some(m) => {
+                    println!("> Step {}: {} => {} does not follow", i + 1, step[0], step[1]);
+
+                    let mut cnf = self.kb.clauses().clone();
+                    cnf.insert_all(&obligation.not().cnf());
+                    println!("> Counterexample:");
+                    self.print_model(&m.minimize(&cnf));
+
+                    failed.push((i + 1).to_string());
                 }
+            }
+        }
+
+        self.failed |= !failed.is_empty();
+        match failed.len() {
+            0 => println!("> Derived!"),
+            1 => println!("> Not derived! Step {} fails", failed[0]),
+            _ => println!("> Not derived! Steps {} fail", failed.join(", ")),
+        }
+    }
+
+    /// Prints the axioms that are needed to prove the given statement, and remembers it as
+    /// the statement proved last, see `:dot`.
+    fn print_proof(&mut self, o: &Stmt) {
+        self.proved = Some(o.clone());
+        match self.kb.core(&o.clone().not().cnf()) {
+            Some(core) if !core.is_empty() => println!("> Using: {}", self.labels(&core)),
+            _ => {},
+        }
+    }
+
+    /// Prints a model, along with the answers of all decoders.
+    fn print_model(&self, model: &Model) {
+        println!("> Model: {model}");
+
+        for (_, answer) in self.decoders.decode(model) {
+            for line in answer.lines() {
+                println!(">   {line}");
+            }
+        }
+    }
+
+    /// Checks if the given statement follows from the knowledge base. When the knowledge base
+    /// consists of Horn clauses and the question is a conjunction of symbols, the question is
+    /// answered by backward chaining. Otherwise, the question is checked against the resolved
+    /// knowledge.
+    fn question(&mut self, o: Stmt) {
+        let n = o.cnf();
+        if self.verbosity >= Verbosity::Normal {
+            println!("> CNF: {n}");
+        }
+
+        if !n.terms.is_empty() && self.kb.is_known(&n) {
+            println!("> Satisfied! (by lemmas)");
+            self.print_proof(&o);
+            return;
+        }
+
+        let goals: Option<Vec<String>> = n.terms.iter()
+            .map(|d| match (d.len(), d.pos().next()) {
+                (1, Some(p)) => Some(p.to_string()),
+                _ => None,
+            })
+            .collect();
+
+        if let Some(goals) = goals.filter(|_| self.kb.clauses().is_horn()) {
+            match Rules::new(self.kb.clauses()).prove(&goals, SLD_DEPTH) {
+                Sld::Proved => {
+                    println!("> Satisfied! (by backward chaining)");
+                    self.kb.learn(&n);
+                    self.print_proof(&o);
+                    return;
+                },
+                Sld::NotProved => {
+                    println!("> Not satisfied! (by backward chaining)");
+                    self.failed = true;
+                    return;
+                },
+                Sld::DepthLimit => {
+                    println!("> Backward chaining reached its depth limit");
+                },
+            }
+        }
+
+        if let Some(budget) = self.auto {
+            let (entailed, method) = self.kb.entails_escalating(&n, budget);
+            if entailed {
+                println!("> Satisfied! (by {method})");
+                self.kb.learn(&n);
+                self.print_proof(&o);
+            } else {
+                println!("> Not satisfied! (by {method})");
+                self.failed = true;
+            }
+            return;
+        }
+
+        if self.kb.resolve().entails(&n) {
+            println!("> Satisfied!");
+            self.kb.learn(&n);
+            self.print_proof(&o);
+        } else {
+            println!("> Not satisfied!");
+            self.failed = true;
+        }
+    }
+
+    /// Returns what came out of the input so far: syntax errors take precedence over questions
+    /// that did not follow.
+    fn outcome(&self) -> Outcome {
+        if self.malformed {
+            return Outcome::ParseError;
+        }
+        if self.failed {
+            return Outcome::NotEntailed;
+        }
+        return Outcome::Entailed;
+    }
+
+    /// Proves the conjectures declared so far, at the end of the input, and forgets them. Like
+    /// in TPTP problems, every conjecture is negated and refuted against the axioms: it is a
+    /// theorem when the axioms contradict its negation, and counter-satisfiable when a model of
+    /// the axioms falsifies it.
+    fn prove_conjectures(&mut self) {
+        let conjectures = std::mem::take(&mut self.conjectures);
+
+        for (i, o) in conjectures.iter().enumerate() {
+            match self.kb.counter_model(o) {
+                None => {
+                    println!("> Conjecture {}: {o}: Theorem", i + 1);
+                    self.print_proof(o);
+                },
+                Some(model) => {
+                    println!("> Conjecture {}: {o}: CounterSatisfiable", i + 1);
+                    self.failed = true;
+                    println!("> Counter-model: {model}");
+                },
+            }
+        }
+    }
+
+    /// Evaluates a line of input: a command, a statement or an empty line. Returns false when
+    /// the REPL should stop.
+    fn eval(&mut self, ln: &str) -> bool {
+        if let Some(cmd) = ln.trim().strip_prefix(':') {
+            self.command(cmd);
+            return true;
+        }
+
+        // on empty input: stop, the parser does not handle empty input
+        if ln.trim().is_empty() {
+            return false;
+        }
+
+        let mut par = Parser::with_recovery(ln.to_string());
+        match par.expr() {
+
+            // on question: check if we have the requested statement along our knowledge
+            ParsedStatement::Question(o) => self.question(o),
+
+            // on axiom: compute further resolvents from the axiom and existing knowledge
+            ParsedStatement::Axiom(o) => self.assert(None, &[], o),
+
+            // on named or tagged axiom: same as axiom, but remember its name and tags
+            ParsedStatement::Labelled(name, tags, o) => self.assert(name.as_deref(), &tags, o),
 
-                println!("> Resolved: {cnf}");
+            // on conjecture: remember it, to prove it when all axioms are known
+            ParsedStatement::Conjecture(o) => {
+                self.conjectures.push(o);
+                println!("> Conjecture {}, to be proved at the end of the input", self.conjectures.len());
+            },
+
+            // on derivation: check that every step follows from the previous
+            ParsedStatement::Derive(steps) => self.derive(&steps),
+
+            // on enum: assert that exactly one of the enum's values holds
+            ParsedStatement::Enum(name, values) => self.declare_enum(&name, &values),
+
+            // on first-order formula: show the steps of clausification
+            #[cfg(feature = "fol")]
+            ParsedStatement::FirstOrder(o) => {
+                println!("> Prenex: {}", o.prenex());
+                println!("> Skolemized: {}", o.skolemize());
 
-                if cnf.contains(&Disj::contradiction()) {
-                    println!("> Contradiction! Resetting statements");
-                    cnf.clear();
+                let clauses: Vec<String> = o.clauses().iter().map(|c| format!("({c})")).collect();
+                if clauses.is_empty() {
+                    println!("> Clauses: *");
+                } else {
+                    println!("> Clauses: {}", clauses.join(" & "));
                 }
             },
 
             // on stop: just exit
-            ParsedStatement::Stop => break,
+            ParsedStatement::Stop => return false,
 
             // on error: mark where the error is in the input and print the error message
             ParsedStatement::Error(msg, idx) => {
                 for _ in 0..idx {
                     print!(" ");
                 }
-                println!("^");
+                println!("{}", paint("^", RED));
+                self.malformed = true;
+
+                error!("{msg}");
+            }
+
+            // on multiple errors: mark all errors in the input and print every error message
+            ParsedStatement::Errors(errors) => {
+                let mut marks = String::new();
+                for (_, idx) in errors.iter() {
+                    while marks.len() < *idx {
+                        marks.push(' ');
+                    }
+                    if marks.len() == *idx {
+                        marks.push('^');
+                    }
+                }
+                println!("{}", marks.replace('^', &paint("^", RED)));
+                self.malformed = true;
+
+                for (msg, idx) in errors.iter() {
+                    error!("{msg} (column {})", idx + 1);
+                }
+            }
+        }
+
+        return true;
+    }
+}
+
+/// How much the REPL shows after every axiom.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Verbosity {
+    /// Only answers to questions, errors, warnings and contradictions.
+    Quiet,
+
+    /// Also the clauses of every axiom, and the symbols and clauses newly derived from it.
+    Normal,
+
+    /// Also all resolved clauses after every axiom that is not Horn.
+    Verbose
+}
+
+impl Display for Verbosity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Verbosity::Quiet => write!(f, "quiet"),
+            Verbosity::Normal => write!(f, "normal"),
+            Verbosity::Verbose => write!(f, "verbose"),
+        };
+    }
+}
+
+impl FromStr for Verbosity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Verbosity, String> {
+        return match s {
+            "quiet" => Ok(Verbosity::Quiet),
+            "normal" => Ok(Verbosity::Normal),
+            "verbose" => Ok(Verbosity::Verbose),
+            _ => Err(format!("Expected verbosity quiet, normal or verbose, found \"{s}\"")),
+        };
+    }
+}
+
+/// What came out of running the REPL, which is its exit status in batch mode, see
+/// [Options::batch].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Outcome {
+    /// All questions, derivations and conjectures followed from the axioms.
+    Entailed,
+
+    /// Some question, derivation or conjecture did not follow from the axioms.
+    NotEntailed,
+
+    /// Some input had syntax errors.
+    ParseError
+}
+
+impl Outcome {
+    /// Returns the exit status for this outcome: 0 if everything followed, 1 if something did
+    /// not, and 2 if there were syntax errors.
+    pub fn exit_code(&self) -> i32 {
+        return match self {
+            Outcome::Entailed => 0,
+            Outcome::NotEntailed => 1,
+            Outcome::ParseError => 2,
+        };
+    }
+}
+
+/// How the REPL reads its input and presents its output.
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// Whether a banner first shows the version, backend, features and limits of this build,
+    /// see `:capabilities`, when input is typed in.
+    pub banner: bool,
+
+    /// Whether output is colored with ANSI escape codes: errors in red, contradictions in bold
+    /// red, warnings in yellow, and newly derived symbols and clauses in green.
+    pub color: bool,
+
+    /// How much is shown after every axiom, which `:verbosity` changes later on.
+    pub verbosity: Verbosity,
+
+    /// Whether input is read without interaction, from a file or from standard input until
+    /// its end, rather than typed in. The [Outcome] tells whether all questions followed.
+    pub batch: bool,
+
+    /// The format of the file, or of input that is piped in, or `None` to detect it, see
+    /// [Format::detect]. Files that are not in the input syntax of the REPL are loaded.
+    pub format: Option<Format>,
+
+    /// The limits on resolution in the knowledge base, see [KnowledgeBase::set_limits].
+    pub limits: SaturationConfig,
+
+    /// Inputs to evaluate after the file, as if they were typed in, after which the REPL
+    /// stops rather than reading input.
+    pub eval: Vec<String>
+}
+
+impl Options {
+    /// Returns the options for the given terminal: a banner, normal verbosity, colors if
+    /// standard output is a terminal and the `NO_COLOR` environment variable is not set, batch
+    /// mode if standard input is not a terminal, and the limits of a new knowledge base.
+    pub fn new() -> Options {
+        return Options {
+            banner: true,
+            color: std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+            verbosity: Verbosity::Normal,
+            batch: !std::io::stdin().is_terminal(),
+            format: None,
+            limits: KnowledgeBase::new().limits().clone(),
+            eval: Vec::new()
+        };
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+/// Runs the REPL. When a file is given, every line of the file is evaluated as if it were
+/// typed in, before reading input. Lines of the file are shown along with their output, and
+/// empty lines in the file are skipped rather than stopping the REPL.
+///
+/// Conjectures (`conjecture: A`) are proved at the end of the file, and at the end of the
+/// input.
+///
+/// Files in the DIMACS CNF format are loaded as by `:load` instead. The same goes for input
+/// that is piped in rather than typed, so that `rustyproof < problem.cnf` loads the problem.
+///
+/// Output is presented according to the given [Options]. In batch mode, no input is read after
+/// the file, and without a file, all input is read until its end before it is evaluated. When
+/// there are inputs to evaluate, they are evaluated after the file, and no input is read.
+///
+/// Returns whether all questions followed from the axioms, and whether there were syntax errors.
+pub fn repl(file: Option<&str>, options: &Options) -> Result<Outcome, String> {
+    COLOR.store(options.color, Ordering::Relaxed);
+    let mut repl = Repl::new();
+    repl.verbosity = options.verbosity;
+    repl.kb.set_limits(options.limits.clone());
+
+    let interactive = options.eval.is_empty();
+    let format = |text: &str| options.format.unwrap_or_else(|| Format::detect(text));
+
+    if file.is_none() && interactive && (options.batch || !std::io::stdin().is_terminal()) {
+        let text = std::io::read_to_string(std::io::stdin()).map_err(|e| format!("Could not read input: {e}"))?;
+
+        if format(&text) != Format::Rustyproof {
+            match KnowledgeBase::read_as(&text, format(&text)) {
+                Result::Ok(kb) => repl.loaded(kb, "input"),
+                Result::Err(msg) => error!("Could not load input: {msg}"),
+            }
+            return Ok(repl.outcome());
+        }
+
+        for ln in text.lines().filter(|ln| !ln.trim().is_empty()) {
+            if !repl.eval(ln) {
+                break;
+            }
+        }
+        repl.prove_conjectures();
+        return Ok(repl.outcome());
+    }
+
+    // The banner is left out of batch runs, so that their output does not depend on the build
+    if options.banner && interactive && !options.batch && std::io::stdin().is_terminal() {
+        repl.banner(file);
+    }
+
+    if let Some(file) = file {
+        let text = std::fs::read_to_string(file).map_err(|e| format!("Could not read {file}: {e}"))?;
+
+        if format(&text) != Format::Rustyproof {
+            match KnowledgeBase::read_as(&text, format(&text)) {
+                Result::Ok(kb) => repl.loaded(kb, file),
+                Result::Err(msg) => error!("Could not load {file}: {msg}"),
+            }
+        } else {
+            for ln in text.lines().filter(|ln| !ln.trim().is_empty()) {
+                println!("{ln}");
+                if !repl.eval(ln) {
+                    repl.prove_conjectures();
+                    return Ok(repl.outcome());
+                }
+            }
+        }
+        repl.prove_conjectures();
+
+        if options.batch && interactive {
+            return Ok(repl.outcome());
+        }
+    }
 
-                println!("> Error! {msg}");
+    if !interactive {
+        for input in options.eval.iter() {
+            if !repl.eval(input) {
+                break;
             }
         }
+        repl.prove_conjectures();
+        return Ok(repl.outcome());
+    }
+
+    let mut editor = DefaultEditor::new().map_err(|e| e.to_string())?;
+    loop {
+        let ln = match editor.readline("") {
+            Ok(ln) => ln,
+
+            // on ctrl-C: discard the current input
+            Err(ReadlineError::Interrupted) => continue,
+
+            // on ctrl-D: stop
+            Err(ReadlineError::Eof) => break,
+
+            Err(e) => return Err(e.to_string())
+        };
+
+        if !ln.trim().is_empty() {
+            let _ = editor.add_history_entry(ln.as_str());
+        }
+
+        if !repl.eval(&ln) {
+            break;
+        }
     }
-}
\ No newline at end of file
+    repl.prove_conjectures();
+
+    return Ok(repl.outcome());
+}
@@ -0,0 +1,111 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// The extension of problem files in a corpus.
+const PROBLEM: &str = "rp";
+
+/// The extension of the files with the expected output of a problem.
+const EXPECTED: &str = "out";
+
+/// Runs a problem file through the REPL of the current executable, as `rustyproof <file>`
+/// does, and returns what it writes.
+fn run_problem(path: &Path) -> Result<String, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("Cannot find the rustyproof executable: {e}"))?;
+
+    let output = Command::new(exe)
+        .arg("--file")
+        .arg(path)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Cannot run {}: {e}", path.display()))?;
+
+    return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+}
+
+/// Finds the first line in which the actual output differs from the expected output, ignoring
+/// trailing whitespace, and returns its number along with both versions of the line.
+fn first_difference(expected: &str, actual: &str) -> Option<(usize, String, String)> {
+    let expected: Vec<&str> = expected.lines().map(str::trim_end).collect();
+    let actual: Vec<&str> = actual.lines().map(str::trim_end).collect();
+
+    for i in 0..expected.len().max(actual.len()) {
+        let e = expected.get(i).copied().unwrap_or("<end of output>");
+        let a = actual.get(i).copied().unwrap_or("<end of output>");
+        if e != a {
+            return Some((i + 1, e.to_string(), a.to_string()));
+        }
+    }
+
+    return None;
+}
+
+/// Runs the `replay` command: `replay <dir>`, optionally followed by `--update`. Every problem
+/// file `name.rp` in the directory is run through the REPL, and its output is compared to the
+/// expected output stored in `name.out`. Mismatches are reported with the first line that
+/// differs. With `--update`, the expected outputs are written instead of compared, which is
+/// how a corpus is recorded in the first place.
+///
+/// Problems are run by the current executable, so this is meant to be called from the
+/// rustyproof binary. Fails if any problem does not match its expected output.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let usage = "Usage: replay <dir> [--update]";
+
+    let (dir, rest) = args.split_first().ok_or(usage)?;
+    let update = match rest {
+        [] => false,
+        [flag] if flag == "--update" => true,
+        _ => return Err(String::from(usage)),
+    };
+
+    let entries = fs::read_dir(dir).map_err(|e| format!("Cannot read {dir}: {e}"))?;
+    let mut problems: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == PROBLEM))
+        .collect();
+    problems.sort();
+
+    if problems.is_empty() {
+        return Err(format!("No .{PROBLEM} files in {dir}"));
+    }
+
+    let mut failed = 0;
+    for problem in problems.iter() {
+        let name = problem.file_stem().unwrap_or_default().to_string_lossy();
+        let actual = run_problem(problem)?;
+        let expected_path = problem.with_extension(EXPECTED);
+
+        if update {
+            fs::write(&expected_path, &actual).map_err(|e| format!("Cannot write {}: {e}", expected_path.display()))?;
+            println!("updated {name}");
+            continue;
+        }
+
+        let expected = match fs::read_to_string(&expected_path) {
+            Ok(expected) => expected,
+            Err(_) => {
+                println!("MISSING {name}: no expected output, record it with --update");
+                failed += 1;
+                continue;
+            }
+        };
+
+        match first_difference(&expected, &actual) {
+            None => println!("ok {name}"),
+            Some((line, e, a)) => {
+                println!("FAILED {name}: line {line} differs");
+                println!("  expected: {e}");
+                println!("  actual:   {a}");
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{failed} of {} problems failed", problems.len()));
+    }
+    if !update {
+        println!("All {} problems passed", problems.len());
+    }
+    return Ok(());
+}
@@ -0,0 +1,220 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::cnf::{Cnf, ResolutionStrategy, Saturation, SaturationConfig, SaturationOutcome, SolveStats};
+use crate::kb::KnowledgeBase;
+use crate::parser::parse_stmt;
+
+/// The first line of a checkpoint file.
+const HEADER: &str = "# rustyproof saturation checkpoint";
+
+/// The line that starts the clauses that are resolved with each other in a checkpoint file.
+const CLAUSES: &str = "# clauses";
+
+/// The line that starts the frontier in a checkpoint file.
+const FRONTIER: &str = "# frontier";
+
+/// The line that starts the input clauses of [ResolutionStrategy::Input] in a checkpoint file.
+const INPUTS: &str = "# inputs";
+
+/// The line that starts the stats in a checkpoint file.
+const STATS: &str = "# stats";
+
+/// The number of seconds between checkpoints, unless given by `--every`.
+const EVERY: f64 = 60.0;
+
+/// Writes the state of a [Saturation] as a checkpoint: a header line, followed by the clauses
+/// that are resolved with each other, the frontier and the input clauses of
+/// [ResolutionStrategy::Input], if any, each in a section with one clause per line, in the
+/// syntax of rustyproof. The last section holds the stats, one per line, like `inferences 12`.
+/// See [read_checkpoint] to read it back.
+pub fn write_checkpoint(saturation: &Saturation) -> String {
+    let (clauses, frontier) = saturation.clone().into_parts();
+
+    let mut out = format!("{HEADER}\n{CLAUSES}\n");
+    for disj in clauses.sorted() {
+        out.push_str(&format!("{disj}\n"));
+    }
+    out.push_str(&format!("{FRONTIER}\n"));
+    for disj in frontier.sorted() {
+        out.push_str(&format!("{disj}\n"));
+    }
+    if let Some(inputs) = saturation.inputs() {
+        out.push_str(&format!("{INPUTS}\n"));
+        for disj in inputs.sorted() {
+            out.push_str(&format!("{disj}\n"));
+        }
+    }
+
+    let stats = saturation.stats();
+    out.push_str(&format!("{STATS}\n"));
+    out.push_str(&format!("inferences {}\n", stats.inferences));
+    out.push_str(&format!("generated {}\n", stats.generated));
+    out.push_str(&format!("subsumed {}\n", stats.subsumed));
+    out.push_str(&format!("discarded {}\n", stats.discarded));
+    out.push_str(&format!("time {}\n", stats.time.as_secs_f64()));
+    return out;
+}
+
+/// Reads a line of the stats section of a checkpoint into the given stats.
+fn read_stat(line: &str, stats: &mut SolveStats) -> Result<(), String> {
+    let error = || format!("Expected a stat, like 'inferences 12', found \"{line}\"");
+    let (name, value) = line.split_once(' ').ok_or_else(error)?;
+
+    if name == "time" {
+        let secs = value.parse().ok().filter(|s: &f64| *s >= 0.0 && s.is_finite()).ok_or_else(error)?;
+        stats.time = Duration::from_secs_f64(secs);
+        return Ok(());
+    }
+
+    let value = value.parse().map_err(|_| error())?;
+    match name {
+        "inferences" => stats.inferences = value,
+        "generated" => stats.generated = value,
+        "subsumed" => stats.subsumed = value,
+        "discarded" => stats.discarded = value,
+        _ => return Err(error()),
+    }
+    return Ok(());
+}
+
+/// Reads a checkpoint written by [write_checkpoint], and returns the [Saturation] it holds, so
+/// that it can be resumed.
+pub fn read_checkpoint(text: &str) -> Result<Saturation, String> {
+    let mut lines = text.lines().enumerate();
+    if lines.next().map(|(_, l)| l.trim()) != Some(HEADER) {
+        return Err(String::from("Not a checkpoint: it does not start with the checkpoint header"));
+    }
+
+    let mut clauses = Cnf::new();
+    let mut frontier = Cnf::new();
+    let mut inputs = None;
+    let mut stats = SolveStats::default();
+    let mut section = None;
+
+    for (i, line) in lines {
+        let line = line.trim();
+        match line {
+            "" => continue,
+            CLAUSES | FRONTIER | STATS => section = Some(line),
+            INPUTS => {
+                inputs = Some(Cnf::new());
+                section = Some(line);
+            },
+            _ => {
+                let cnf = match section {
+                    Some(CLAUSES) => &mut clauses,
+                    Some(FRONTIER) => &mut frontier,
+                    Some(INPUTS) => inputs.as_mut().unwrap(),
+                    Some(_) => {
+                        read_stat(line, &mut stats).map_err(|e| format!("Line {}: {e}", i + 1))?;
+                        continue;
+                    },
+                    None => return Err(format!("Line {}: Expected '{CLAUSES}' or '{FRONTIER}'", i + 1)),
+                };
+                let stmt = parse_stmt(line).map_err(|e| format!("Line {}: {e}", i + 1))?;
+                cnf.insert_all(&stmt.cnf());
+            }
+        }
+    }
+
+    let mut saturation = Saturation::new(clauses, frontier);
+    saturation.set_inputs(inputs);
+    saturation.set_stats(stats);
+    return Ok(saturation);
+}
+
+/// Writes a checkpoint to the given file. It is written to a temporary file first, which
+/// then replaces the file, so that an interruption while writing never leaves a broken
+/// checkpoint behind.
+fn store(path: &Path, saturation: &Saturation) -> Result<(), String> {
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, write_checkpoint(saturation)).map_err(|e| format!("Cannot write {}: {e}", temp.display()))?;
+    return fs::rename(&temp, path).map_err(|e| format!("Cannot write {}: {e}", path.display()));
+}
+
+/// Runs the `saturate` command: `saturate <file>`, optionally followed by `--checkpoint <file>`,
+/// `--every <seconds>` and `--strategy full|unit|input`. All resolvents of the axioms in the
+/// file, which may be a DIMACS CNF file, are computed by the given [ResolutionStrategy], after
+/// which the [crate::cnf::SolveStats] of the run are printed, followed by the clauses, or
+/// whether they are refuted.
+///
+/// With `--checkpoint`, the state of the saturation is written to the checkpoint file every
+/// 60 seconds, or as often as `--every` says, and when it is done. When the checkpoint file
+/// exists, the saturation resumes from it instead of starting from the axioms, so that a long
+/// run that was interrupted is not lost.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let usage = "Usage: saturate <file> [--checkpoint <file>] [--every <seconds>] [--strategy full|unit|input]";
+
+    let (file, mut rest) = args.split_first().ok_or(usage)?;
+    let mut checkpoint = None;
+    let mut every = EVERY;
+    let mut strategy = ResolutionStrategy::Full;
+
+    loop {
+        rest = match rest {
+            [] => break,
+            [flag, path, rest @ ..] if flag == "--checkpoint" => {
+                checkpoint = Some(Path::new(path));
+                rest
+            },
+            [flag, secs, rest @ ..] if flag == "--every" => {
+                every = secs.parse().ok().filter(|s: &f64| *s > 0.0).ok_or(format!("Expected a number of seconds, found \"{secs}\""))?;
+                rest
+            },
+            [flag, name, rest @ ..] if flag == "--strategy" => {
+                strategy = match name.as_str() {
+                    "full" => ResolutionStrategy::Full,
+                    "unit" => ResolutionStrategy::Unit,
+                    "input" => ResolutionStrategy::Input,
+                    _ => return Err(format!("Expected strategy full, unit or input, found \"{name}\"")),
+                };
+                rest
+            },
+            _ => return Err(String::from(usage)),
+        };
+    }
+
+    let mut saturation = match checkpoint.filter(|p| p.exists()) {
+        Some(path) => {
+            let text = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+            let saturation = read_checkpoint(&text).map_err(|e| format!("Cannot resume from {}: {e}", path.display()))?;
+            println!("Resuming from {}: {} clauses, {} to resolve", path.display(), saturation.clauses().terms.len(), saturation.frontier().len());
+            saturation
+        },
+        None => {
+            let kb = KnowledgeBase::load(file).map_err(|e| format!("Cannot load {file}: {e}"))?;
+            Saturation::new(Cnf::new(), kb.clauses().clone())
+        },
+    };
+
+    let config = SaturationConfig {
+        strategy,
+        max_time: checkpoint.map(|_| Duration::from_secs_f64(every)),
+        ..SaturationConfig::default()
+    };
+
+    let outcome = loop {
+        let outcome = saturation.run(&config);
+        if let Some(path) = checkpoint {
+            store(path, &saturation)?;
+        }
+        if outcome != SaturationOutcome::LimitReached {
+            break outcome;
+        }
+        println!("Checkpoint: {} clauses, {} to resolve", saturation.clauses().terms.len(), saturation.frontier().len());
+    };
+
+    println!("Stats: {}", saturation.stats());
+    match outcome {
+        SaturationOutcome::Refuted => println!("Refuted!"),
+        _ => {
+            println!("Saturated: {} clauses", saturation.clauses().terms.len());
+            for disj in saturation.clauses().sorted() {
+                println!("{disj}");
+            }
+        },
+    }
+    return Ok(());
+}
@@ -0,0 +1,484 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::io::Read;
+
+use crate::literal::Literal;
+use crate::qbf;
+use crate::solver::{Model, Solver};
+use crate::stmt::Stmt;
+
+/// The commands that a script may start with, by which [is_smtlib] recognizes scripts.
+const COMMANDS: [&str; 7] = ["set-logic", "set-info", "set-option", "declare-const", "declare-fun", "define-fun", "assert"];
+
+/// An S-expression: an atom, like a symbol, keyword or string, or a list of S-expressions.
+#[derive(PartialEq, Eq, Clone, Debug)]
+enum Sexp {
+    /// An atom. Strings keep their quotes, and quoted symbols like `|a b|` lose their bars.
+    Atom(String),
+    List(Vec<Sexp>)
+}
+
+impl Display for Sexp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Sexp::Atom(s) => write!(f, "{}", quote(s)),
+            Sexp::List(v) => write!(f, "({})", v.iter().map(Sexp::to_string).collect::<Vec<_>>().join(" ")),
+        };
+    }
+}
+
+/// Writes a symbol in bars, like `|a b|`, unless it is a simple symbol, which consists of
+/// letters, digits and the characters `~!@$%^&*_-+=<>.?/`, and does not start with a digit.
+/// Strings, keywords and numerals are written as they are.
+fn quote(symbol: &str) -> String {
+    let simple = |c: char| c.is_ascii_alphanumeric() || "~!@$%^&*_-+=<>.?/".contains(c);
+    let numeral = !symbol.is_empty() && symbol.chars().all(|c| c.is_ascii_digit());
+    if numeral || symbol.starts_with(['"', ':']) || (symbol.chars().all(simple) && symbol.starts_with(|c: char| !c.is_ascii_digit())) {
+        return symbol.to_string();
+    }
+    return format!("|{symbol}|");
+}
+
+/// Reads all S-expressions of a text. Comments run from `;` to the end of the line.
+fn parse_sexps(text: &str) -> Result<Vec<Sexp>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut stack: Vec<Vec<Sexp>> = vec![Vec::new()];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            _ if c.is_whitespace() => i += 1,
+            ';' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            },
+            '(' => {
+                stack.push(Vec::new());
+                i += 1;
+            },
+            ')' => {
+                let list = stack.pop().filter(|_| !stack.is_empty()).ok_or("Unexpected ')'")?;
+                stack.last_mut().unwrap().push(Sexp::List(list));
+                i += 1;
+            },
+            '|' => {
+                let end = chars[i + 1..].iter().position(|&c| c == '|').ok_or("Unterminated quoted symbol")?;
+                stack.last_mut().unwrap().push(Sexp::Atom(chars[i + 1..i + 1 + end].iter().collect()));
+                i += end + 2;
+            },
+            '"' => {
+                // A quote in a string is written as two quotes
+                let start = i;
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(String::from("Unterminated string")),
+                        Some('"') if chars.get(i + 1) == Some(&'"') => i += 2,
+                        Some('"') => break,
+                        Some(_) => i += 1,
+                    }
+                }
+                i += 1;
+                stack.last_mut().unwrap().push(Sexp::Atom(chars[start..i].iter().collect()));
+            },
+            _ => {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() && !"();|\"".contains(chars[i]) {
+                    i += 1;
+                }
+                stack.last_mut().unwrap().push(Sexp::Atom(chars[start..i].iter().collect()));
+            },
+        }
+    }
+
+    if stack.len() > 1 {
+        return Err(String::from("Expected ')'"));
+    }
+    return Ok(stack.pop().unwrap());
+}
+
+/// Tests whether a text is an SMT-LIB 2 script, by its first command.
+pub fn is_smtlib(text: &str) -> bool {
+    let code = text.lines()
+        .map(|l| l.split(';').next().unwrap())
+        .find(|l| !l.trim().is_empty())
+        .unwrap_or("");
+
+    return code.trim_start().strip_prefix('(').is_some_and(|c| {
+        let c = c.trim_start();
+        COMMANDS.iter().any(|cmd| c.strip_prefix(cmd).is_some_and(|r| r.starts_with(|c: char| c.is_whitespace() || c == '(' || c == ')')))
+    });
+}
+
+/// Reads the assertions of an SMT-LIB 2 script as statements, see [Session]. Commands that
+/// solve or ask for results, like `check-sat` and `get-model`, are skipped. Assertions that
+/// are popped off are left out.
+pub fn read(text: &str) -> Result<Vec<Stmt>, String> {
+    let mut session = Session::new();
+    session.solving = false;
+    session.execute(text)?;
+    return Ok(session.assertions);
+}
+
+/// The state of an SMT-LIB 2 script that is executed, for the fragment of Booleans: like the
+/// logic `QF_UF`, but with constants of sort `Bool` only. Every constant is a symbol, and
+/// every term a statement.
+///
+/// The commands are `declare-const`, `declare-fun` and `define-fun` for constants, `assert`,
+/// `check-sat`, `check-sat-assuming`, `get-model`, `get-value`, `push`, `pop`, `reset`,
+/// `reset-assertions`, `echo` and `exit`. `set-logic`, `set-info` and `set-option` are
+/// accepted and ignored, and other commands answer `unsupported`. Terms are `true`, `false`,
+/// constants, `not`, `and`, `or`, `xor`, `=>`, `=`, `distinct`, `ite`, `let`, `forall` and
+/// `exists` over `Bool`, and annotations like `(! A :named a)`.
+pub struct Session {
+    /// The declared constants, in order of declaration.
+    declared: Vec<String>,
+
+    /// The defined constants, by name, with the statement they stand for.
+    definitions: HashMap<String, Stmt>,
+
+    assertions: Vec<Stmt>,
+
+    /// The state of the constants and assertions at every `push` that is not popped yet.
+    scopes: Vec<(Vec<String>, HashMap<String, Stmt>, usize)>,
+
+    /// The model found by the last `check-sat`, if it was `sat`.
+    model: Option<Model>,
+
+    /// Whether `exit` was executed.
+    exited: bool,
+
+    /// Whether to solve, or only to read the assertions.
+    solving: bool
+}
+
+impl Session {
+    pub fn new() -> Session {
+        return Session {
+            declared: Vec::new(),
+            definitions: HashMap::new(),
+            assertions: Vec::new(),
+            scopes: Vec::new(),
+            model: None,
+            exited: false,
+            solving: true
+        };
+    }
+
+    /// Returns the assertions that are in effect.
+    pub fn assertions(&self) -> &[Stmt] {
+        return &self.assertions;
+    }
+
+    /// Tests whether the script executed `exit`, after which further commands are ignored.
+    pub fn has_exited(&self) -> bool {
+        return self.exited;
+    }
+
+    /// Executes the commands of a script, and returns the responses to them, like `sat` or a
+    /// model, one per command that responds. Stops at the first command that fails.
+    pub fn execute(&mut self, text: &str) -> Result<Vec<String>, String> {
+        let mut out = Vec::new();
+        for command in parse_sexps(text)? {
+            if self.exited {
+                break;
+            }
+            self.command(&command, &mut out).map_err(|e| format!("{command}: {e}"))?;
+        }
+        return Ok(out);
+    }
+
+    fn command(&mut self, command: &Sexp, out: &mut Vec<String>) -> Result<(), String> {
+        let Sexp::List(items) = command else {
+            return Err(String::from("Expected a command"));
+        };
+        let Some((Sexp::Atom(name), args)) = items.split_first() else {
+            return Err(String::from("Expected a command"));
+        };
+
+        match (name.as_str(), args) {
+            ("set-logic" | "set-info" | "set-option", _) => {},
+            ("declare-const", [Sexp::Atom(c), sort]) => self.declare(c, sort)?,
+            ("declare-fun", [Sexp::Atom(c), Sexp::List(params), sort]) if params.is_empty() => self.declare(c, sort)?,
+            ("declare-fun", [Sexp::Atom(c), _, _]) => return Err(format!("Only constants are supported, but {c} has parameters")),
+            ("define-fun", [Sexp::Atom(c), Sexp::List(params), sort, body]) if params.is_empty() => {
+                expect_bool(sort)?;
+                if self.declared.contains(c) || self.definitions.contains_key(c) {
+                    return Err(format!("{c} is already declared"));
+                }
+                let stmt = self.term(body, &mut Vec::new())?;
+                self.definitions.insert(c.clone(), stmt);
+            },
+            ("assert", [term]) => {
+                let stmt = self.term(term, &mut Vec::new())?;
+                self.assertions.push(stmt);
+            },
+            ("check-sat", []) if self.solving => out.push(self.check_sat(&[])?),
+            ("check-sat-assuming", [Sexp::List(lits)]) if self.solving => {
+                let lits = lits.iter().map(|l| self.literal(l)).collect::<Result<Vec<_>, _>>()?;
+                out.push(self.check_sat(&lits)?);
+            },
+            ("get-model", []) if self.solving => {
+                let model = self.model.as_ref().ok_or("No model, the last check-sat was not sat")?;
+                let mut lines = vec![String::from("(")];
+                for c in self.declared.iter() {
+                    lines.push(format!("  (define-fun {} () Bool {})", quote(c), model.get(c).unwrap_or(false)));
+                }
+                lines.push(String::from(")"));
+                out.push(lines.join("\n"));
+            },
+            ("get-value", [Sexp::List(terms)]) if self.solving => {
+                let model = self.model.as_ref().ok_or("No model, the last check-sat was not sat")?;
+                let mut values = Vec::new();
+                for term in terms {
+                    let stmt = self.term(term, &mut Vec::new())?;
+                    let fixed = stmt.symbols().iter().fold(stmt, |s, sym| s.fix(sym, model.get(sym).unwrap_or(false)));
+                    values.push(format!("({term} {})", qbf::decide(&fixed)));
+                }
+                out.push(format!("({})", values.join(" ")));
+            },
+            ("check-sat" | "check-sat-assuming" | "get-model" | "get-value", _) if !self.solving => {},
+            ("push", []) => self.push(1),
+            ("push", [Sexp::Atom(n)]) => self.push(n.parse().map_err(|_| format!("Expected a number, found {n}"))?),
+            ("pop", []) => self.pop(1)?,
+            ("pop", [Sexp::Atom(n)]) => self.pop(n.parse().map_err(|_| format!("Expected a number, found {n}"))?)?,
+            ("reset" | "reset-assertions", []) => {
+                // Declarations are never global, so resetting the assertions resets everything
+                let solving = self.solving;
+                *self = Session::new();
+                self.solving = solving;
+            },
+            ("echo", [Sexp::Atom(s)]) if s.starts_with('"') => if self.solving { out.push(s.clone()) },
+            ("exit", []) => self.exited = true,
+            ("declare-const" | "declare-fun" | "define-fun" | "assert" | "check-sat" | "check-sat-assuming"
+                | "get-model" | "get-value" | "push" | "pop" | "reset" | "reset-assertions" | "echo" | "exit", _) => {
+                return Err(String::from("Malformed command"));
+            },
+            _ => if self.solving { out.push(String::from("unsupported")) },
+        }
+
+        return Ok(());
+    }
+
+    fn declare(&mut self, name: &str, sort: &Sexp) -> Result<(), String> {
+        expect_bool(sort)?;
+        if self.declared.iter().any(|c| c == name) || self.definitions.contains_key(name) {
+            return Err(format!("{name} is already declared"));
+        }
+        self.declared.push(name.to_string());
+        return Ok(());
+    }
+
+    fn push(&mut self, n: usize) {
+        for _ in 0..n {
+            self.scopes.push((self.declared.clone(), self.definitions.clone(), self.assertions.len()));
+        }
+    }
+
+    fn pop(&mut self, n: usize) -> Result<(), String> {
+        if n > self.scopes.len() {
+            return Err(format!("Cannot pop {n} scopes, only {} are pushed", self.scopes.len()));
+        }
+        for _ in 0..n {
+            let (declared, definitions, assertions) = self.scopes.pop().unwrap();
+            self.declared = declared;
+            self.definitions = definitions;
+            self.assertions.truncate(assertions);
+        }
+        self.model = None;
+        return Ok(());
+    }
+
+    /// Solves the assertions, under the given assumptions, and returns `sat` or `unsat`.
+    fn check_sat(&mut self, assumptions: &[Literal]) -> Result<String, String> {
+        let mut solver = Solver::new();
+        for stmt in self.assertions.iter() {
+            solver.add_cnf(&stmt.try_cnf().map_err(|e| e.to_string())?);
+        }
+
+        self.model = solver.solve_assuming(assumptions).ok();
+        return Ok(String::from(if self.model.is_some() { "sat" } else { "unsat" }));
+    }
+
+    /// Translates an assumption of `check-sat-assuming`: a constant, or its negation.
+    fn literal(&self, lit: &Sexp) -> Result<Literal, String> {
+        return match lit {
+            Sexp::Atom(c) if self.declared.contains(c) => Ok(Literal::pos(c)),
+            Sexp::List(v) => match &v[..] {
+                [Sexp::Atom(not), Sexp::Atom(c)] if not == "not" && self.declared.contains(c) => Ok(Literal::neg(c)),
+                _ => Err(format!("Expected a declared constant or its negation, found {lit}")),
+            },
+            _ => Err(format!("Expected a declared constant or its negation, found {lit}")),
+        };
+    }
+
+    /// Translates a term to a statement, where `env` holds the names bound by `let` and by
+    /// quantifiers around the term, innermost last.
+    fn term(&self, term: &Sexp, env: &mut Vec<(String, Stmt)>) -> Result<Stmt, String> {
+        let items = match term {
+            Sexp::Atom(s) if s == "true" => return Ok(Stmt::Taut),
+            Sexp::Atom(s) if s == "false" => return Ok(Stmt::Cont),
+            Sexp::Atom(s) => {
+                if let Some((_, stmt)) = env.iter().rev().find(|(name, _)| name == s) {
+                    return Ok(stmt.clone());
+                }
+                if let Some(stmt) = self.definitions.get(s) {
+                    return Ok(stmt.clone());
+                }
+                if self.declared.contains(s) {
+                    return Ok(Stmt::symbol(s));
+                }
+                return Err(format!("Unknown constant {s}"));
+            },
+            Sexp::List(items) => items,
+        };
+
+        let Some((Sexp::Atom(op), args)) = items.split_first() else {
+            return Err(format!("Expected an operator in {term}"));
+        };
+
+        match (op.as_str(), args) {
+            ("let", [Sexp::List(bindings), body]) => {
+                // The bindings are parallel: each is translated outside of all of them
+                let mut bound = Vec::new();
+                for binding in bindings {
+                    let Sexp::List(pair) = binding else { return Err(format!("Expected a binding, found {binding}")) };
+                    let [Sexp::Atom(name), value] = &pair[..] else { return Err(format!("Expected a binding, found {binding}")) };
+                    bound.push((name.clone(), self.term(value, env)?));
+                }
+
+                let len = env.len();
+                env.extend(bound);
+                let body = self.term(body, env);
+                env.truncate(len);
+                return body;
+            },
+            ("forall" | "exists", [Sexp::List(vars), body]) => {
+                // Every variable is bound to a symbol that is fresh, so that the terms bound by
+                // an outer let, which are inlined, cannot be captured by the quantifier
+                let mut used: HashSet<String> = self.declared.iter().cloned().collect();
+                for (_, stmt) in env.iter() {
+                    used.extend(stmt.symbols());
+                }
+
+                let mut names = Vec::new();
+                for var in vars {
+                    let Sexp::List(pair) = var else { return Err(format!("Expected a variable, found {var}")) };
+                    let [Sexp::Atom(name), sort] = &pair[..] else { return Err(format!("Expected a variable, found {var}")) };
+                    expect_bool(sort)?;
+                    names.push((name.clone(), fresh(name, &mut used)));
+                }
+
+                let len = env.len();
+                env.extend(names.iter().map(|(n, f)| (n.clone(), Stmt::symbol(f))));
+                let body = self.term(body, env);
+                env.truncate(len);
+
+                let quantify = if op == "forall" { Stmt::forall } else { Stmt::exists };
+                return Ok(names.iter().rev().fold(body?, |s, (_, f)| quantify(f, s)));
+            },
+            ("!", [term, ..]) => return self.term(term, env),
+            _ => {},
+        }
+
+        let mut operands = args.iter().map(|a| self.term(a, env)).collect::<Result<Vec<_>, _>>()?;
+        if matches!(op.as_str(), "xor" | "=>" | "=" | "distinct") && operands.len() < 2 {
+            return Err(format!("{op} expects at least 2 operands, found {}", operands.len()));
+        }
+
+        return Ok(match op.as_str() {
+            "not" if operands.len() == 1 => operands.pop().unwrap().not(),
+            "and" => Stmt::all(operands),
+            "or" => Stmt::any(operands),
+            "xor" => {
+                let first = operands.remove(0);
+                operands.into_iter().fold(first, Stmt::xor)
+            },
+            "=>" => {
+                let last = operands.pop().unwrap();
+                operands.into_iter().rev().fold(last, |r, l| l.implies(r))
+            },
+            "=" => {
+                Stmt::all(operands.windows(2).map(|p| p[0].clone().equiv(p[1].clone())).collect())
+            },
+            "distinct" => {
+                let mut pairs = Vec::new();
+                for (i, l) in operands.iter().enumerate() {
+                    for r in operands[i + 1..].iter() {
+                        pairs.push(l.clone().xor(r.clone()));
+                    }
+                }
+                Stmt::all(pairs)
+            },
+            "ite" if operands.len() == 3 => {
+                let e = operands.pop().unwrap();
+                let t = operands.pop().unwrap();
+                operands.pop().unwrap().ite(t, e)
+            },
+            "not" | "ite" => return Err(format!("Wrong number of operands for {op}")),
+            _ => return Err(format!("Unknown operator {op}, only Boolean operators are supported")),
+        });
+    }
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+/// Generates a name based on the given one, which is not in the set of used names, and
+/// adds it to that set.
+fn fresh(base: &str, used: &mut HashSet<String>) -> String {
+    let mut name = base.to_string();
+    let mut i = 1;
+    while used.contains(&name) {
+        name = format!("{base}{i}");
+        i += 1;
+    }
+
+    used.insert(name.clone());
+    return name;
+}
+
+/// Fails unless the given sort is `Bool`.
+fn expect_bool(sort: &Sexp) -> Result<(), String> {
+    if *sort != Sexp::Atom(String::from("Bool")) {
+        return Err(format!("Only the sort Bool is supported, found {sort}"));
+    }
+    return Ok(());
+}
+
+/// Runs the `smt` command: `smt [<file>]`. Executes an SMT-LIB 2 script from the file, or
+/// from standard input if no file is given, and prints the responses, see [Session]. A
+/// command that fails responds with `(error "<message>")`.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let text = match args {
+        [] => {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text).map_err(|e| format!("Cannot read standard input: {e}"))?;
+            text
+        },
+        [file] => std::fs::read_to_string(file).map_err(|e| format!("Cannot read {file}: {e}"))?,
+        _ => return Err(String::from("Usage: smt [<file>]")),
+    };
+
+    // Like other solvers, an error is a response of its own, after which the script goes on
+    let mut session = Session::new();
+    for command in parse_sexps(&text)? {
+        if session.exited {
+            break;
+        }
+        let mut out = Vec::new();
+        let result = session.command(&command, &mut out);
+        for response in out {
+            println!("{response}");
+        }
+        if let Err(msg) = result {
+            println!("(error \"{}\")", format!("{command}: {msg}").replace('"', "\"\""));
+        }
+    }
+    return Ok(());
+}
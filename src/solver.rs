@@ -0,0 +1,787 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+
+use crate::cnf::{Cnf, Disj};
+use crate::literal::Literal;
+use crate::symbol::Symbol;
+
+/// An assignment of truth values to symbols, which satisfies some set of clauses.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Model {
+    values: BTreeMap<String, bool>
+}
+
+#[allow(unused)]
+impl Model {
+    /// Returns the value assigned to the given symbol, or `None` if the symbol is not assigned.
+    pub fn get(&self, sym: &str) -> Option<bool> {
+        return self.values.get(sym).copied();
+    }
+
+    /// Returns the symbols that are assigned, along with their values, in order of name.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &bool)> {
+        return self.values.iter();
+    }
+
+    /// Returns the symbols that are assigned true, in order of name.
+    pub fn true_symbols(&self) -> impl Iterator<Item = &String> {
+        return self.values.iter().filter(|(_, v)| **v).map(|(s, _)| s);
+    }
+
+    /// Returns the number of symbols to which this model and the other assign a different
+    /// value, i.e. the Hamming distance between the models. Symbols that are not assigned by
+    /// one of the models count as false in that model.
+    pub fn distance(&self, other: &Model) -> usize {
+        let mut d = self.values.iter()
+            .filter(|(s, v)| other.get(s).unwrap_or(false) != **v)
+            .count();
+
+        d += other.values.iter()
+            .filter(|(s, v)| **v && !self.values.contains_key(*s))
+            .count();
+
+        return d;
+    }
+
+    /// Returns the clause that is satisfied by every assignment, except by this model. Adding
+    /// this clause to a solver makes sure it will not find this model again.
+    pub fn blocking(&self) -> Disj {
+        let pos = self.values.iter().filter(|(_, v)| !**v).map(|(s, _)| s.clone()).collect();
+        let neg = self.values.iter().filter(|(_, v)| **v).map(|(s, _)| s.clone()).collect();
+        return Disj::new(pos, neg).unwrap();
+    }
+
+    /// Tests whether the given clause is satisfied by this model.
+    pub fn satisfies(&self, disj: &Disj) -> bool {
+        return disj.literals().iter().any(|l| self.get(&l.atom) == Some(l.polarity));
+    }
+
+    /// Returns the number of symbols assigned by this model.
+    pub fn len(&self) -> usize {
+        return self.values.len();
+    }
+
+    /// Tests whether this model assigns no symbols.
+    pub fn is_empty(&self) -> bool {
+        return self.values.is_empty();
+    }
+
+    /// Minimizes this model to a partial model that still satisfies all clauses of the given
+    /// [Cnf], by leaving out the symbols whose value does not matter.
+    ///
+    /// For example, the model `A, B, !C` of `A | B` minimizes to `A`: given `A`, the values
+    /// of `B` and `C` don't matter. Symbols are left out greedily, starting with the symbols
+    /// that satisfy the fewest clauses. The result is minimal in the sense that no symbol can
+    /// be left out further, but a smaller partial model may exist.
+    pub fn minimize(&self, cnf: &Cnf) -> Model {
+        // For every clause satisfied by this model: the symbols that satisfy it
+        let mut clauses: Vec<Vec<&str>> = Vec::new();
+        for disj in cnf.terms.iter() {
+            let sat: Vec<&str> = disj.literals().iter()
+                .filter(|l| self.get(&l.atom) == Some(l.polarity))
+                .map(|l| l.atom.name())
+                .collect();
+            clauses.push(sat);
+        }
+
+        let mut count: HashMap<&str, usize> = HashMap::new();
+        for sat in clauses.iter() {
+            for s in sat.iter() {
+                *count.entry(s).or_default() += 1;
+            }
+        }
+
+        let mut order = Vec::from_iter(self.values.keys().map(String::as_str));
+        order.sort_by_key(|s| count.get(s).copied().unwrap_or(0));
+
+        let mut values = self.values.clone();
+        let mut support: Vec<usize> = clauses.iter().map(Vec::len).collect();
+
+        for s in order {
+            let needed = clauses.iter().zip(support.iter()).any(|(sat, n)| *n == 1 && sat.contains(&s));
+            if needed {
+                continue;
+            }
+
+            values.remove(s);
+            for (sat, n) in clauses.iter().zip(support.iter_mut()) {
+                if sat.contains(&s) {
+                    *n -= 1;
+                }
+            }
+        }
+
+        return Model { values };
+    }
+}
+
+impl Display for Model {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.values.is_empty() {
+            return write!(f, "*");
+        }
+
+        let mut sep = false;
+
+        for (s, v) in self.values.iter() {
+            if sep {
+                write!(f, ", ")?;
+            } else {
+                sep = true;
+            }
+
+            if *v {
+                write!(f, "{s}")?;
+            } else {
+                write!(f, "!{s}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A literal as used internally by the [Solver]: a symbol index shifted left by one, with
+/// the lowest bit set when the literal is inverted.
+type Lit = usize;
+
+fn lit(var: usize, neg: bool) -> Lit {
+    return var << 1 | neg as usize;
+}
+
+fn var(l: Lit) -> usize {
+    return l >> 1;
+}
+
+fn is_neg(l: Lit) -> bool {
+    return l & 1 == 1;
+}
+
+/// A satisfiability solver based on DPLL: it searches for a [Model] by deciding symbols one
+/// at a time, propagating unit clauses after each decision, and backtracking on conflicts.
+/// Propagation uses two watched literals per clause.
+#[derive(Clone)]
+pub struct Solver {
+    names: Vec<Symbol>,
+    index: HashMap<Symbol, usize>,
+    clauses: Vec<Vec<Lit>>,
+    contradiction: bool,
+
+    /// The preferred value of every symbol, which is tried first when deciding it.
+    phases: Vec<bool>
+}
+
+/// The search state of a single [Solver::solve] call.
+#[derive(Clone)]
+struct Search {
+    clauses: Vec<Vec<Lit>>,
+    watches: Vec<Vec<usize>>,
+    assigns: Vec<Option<bool>>,
+    trail: Vec<Lit>,
+
+    /// For every decision: the trail index where it was made, and whether it has already
+    /// been flipped after a conflict.
+    levels: Vec<(usize, bool)>,
+    head: usize
+}
+
+impl Solver {
+    pub fn new() -> Solver {
+        return Solver {
+            names: Vec::new(),
+            index: HashMap::new(),
+            clauses: Vec::new(),
+            contradiction: false,
+            phases: Vec::new()
+        };
+    }
+
+    /// Returns the index of the given symbol, registering it if it is new.
+    fn symbol(&mut self, symbol: Symbol) -> usize {
+        if let Some(i) = self.index.get(&symbol) {
+            return *i;
+        }
+
+        let i = self.names.len();
+        self.names.push(symbol);
+        self.index.insert(symbol, i);
+        self.phases.push(false);
+        return i;
+    }
+
+    /// Sets the value to try first when deciding the given symbol. By default, symbols are
+    /// tried false first. Preferred values steer which model is found, but never whether
+    /// a model is found.
+    pub fn prefer(&mut self, sym: &str, value: bool) {
+        let i = self.symbol(Symbol::intern(sym));
+        self.phases[i] = value;
+    }
+
+    /// Uses the values of the given model as preferred values, so that the solver finds the
+    /// model again, or a model close to it, without searching. This speeds up solving a
+    /// sequence of similar problems. Symbols of the model that do not occur in the added
+    /// clauses are ignored, so hints must be given after adding the clauses.
+    pub fn hint(&mut self, model: &Model) {
+        for (sym, value) in model.iter() {
+            if let Some(i) = self.index.get(&Symbol::intern(sym)) {
+                self.phases[*i] = *value;
+            }
+        }
+    }
+
+    /// Adds a clause that must be satisfied.
+    pub fn add_disj(&mut self, disj: &Disj) {
+        if disj.is_contradiction() {
+            self.contradiction = true;
+            return;
+        }
+
+        let mut clause = Vec::new();
+        for l in disj.literals().iter() {
+            clause.push(lit(self.symbol(l.atom), !l.polarity));
+        }
+
+        self.clauses.push(clause);
+    }
+
+    /// Adds all clauses of the given [Cnf].
+    pub fn add_cnf(&mut self, cnf: &Cnf) {
+        for disj in cnf.terms.iter() {
+            self.add_disj(disj);
+        }
+    }
+
+    /// Searches for a model that satisfies all added clauses. Returns `None` if the clauses
+    /// are unsatisfiable. The model assigns a value to every symbol that occurs in the clauses.
+    pub fn solve(&self) -> Option<Model> {
+        let mut search = self.start()?;
+        if !search.run(&self.phases) {
+            return None;
+        }
+
+        return Some(self.model(&search));
+    }
+
+    /// Searches for a model that satisfies all added clauses, and in which the given literals
+    /// are true. The assumptions only hold for this call, so that the same clauses can be
+    /// solved under different assumptions, and clauses can still be added in between.
+    ///
+    /// When there is no such model, returns the assumptions that fail: a subset of the given
+    /// literals that cannot all be true. It is minimal, in that every literal left out of it
+    /// makes the rest satisfiable, but a smaller failing subset may exist. It is empty when
+    /// the clauses are unsatisfiable by themselves.
+    pub fn solve_assuming(&self, assumptions: &[Literal]) -> Result<Model, Vec<Literal>> {
+        if let Some(mut search) = self.assume(assumptions) {
+            if search.run(&self.phases) {
+                let mut model = self.model(&search);
+                for l in assumptions.iter() {
+                    model.values.entry(l.atom.to_string()).or_insert(l.polarity);
+                }
+                return Ok(model);
+            }
+        }
+
+        // Assumptions are left out one at a time, as long as the rest still fails
+        let mut failed = assumptions.to_vec();
+        let mut i = 0;
+        while i < failed.len() {
+            let mut rest = failed.clone();
+            rest.remove(i);
+
+            if self.assume(&rest).is_some_and(|mut search| search.run(&self.phases)) {
+                i += 1;
+            } else {
+                failed = rest;
+            }
+        }
+        return Err(failed);
+    }
+
+    /// Sets up the search state for the added clauses like [Self::start], with the given
+    /// literals assigned true before any symbol is decided, so that backtracking never undoes
+    /// them. Returns `None` if this already leads to a conflict.
+    fn assume(&self, assumptions: &[Literal]) -> Option<Search> {
+        let mut search = self.start()?;
+
+        let mut values: HashMap<Symbol, bool> = HashMap::new();
+        for l in assumptions.iter() {
+            if *values.entry(l.atom).or_insert(l.polarity) != l.polarity {
+                return None;
+            }
+            // Symbols that no clause mentions can take any value
+            if let Some(i) = self.index.get(&l.atom) {
+                if !search.enqueue(lit(*i, !l.polarity)) {
+                    return None;
+                }
+            }
+        }
+
+        if !search.propagate() {
+            return None;
+        }
+        return Some(search);
+    }
+
+    /// Tests whether propagating the unit clauses alone, without deciding any symbol, leads to
+    /// a conflict. This is much cheaper than [Self::solve], and shows that the clauses are
+    /// unsatisfiable, but not all unsatisfiable clauses are refuted this way.
+    pub fn propagates_conflict(&self) -> bool {
+        return self.start().is_none();
+    }
+
+    /// Sets up the search state for the added clauses, and propagates the unit clauses.
+    /// Returns `None` if this already leads to a conflict.
+    fn start(&self) -> Option<Search> {
+        if self.contradiction {
+            return None;
+        }
+
+        let mut search = Search {
+            clauses: Vec::new(),
+            watches: vec![Vec::new(); self.names.len() * 2],
+            assigns: vec![None; self.names.len()],
+            trail: Vec::new(),
+            levels: Vec::new(),
+            head: 0
+        };
+
+        for clause in self.clauses.iter() {
+            if clause.len() == 1 {
+                if !search.enqueue(clause[0]) {
+                    return None;
+                }
+            } else {
+                let i = search.clauses.len();
+                search.watches[clause[0]].push(i);
+                search.watches[clause[1]].push(i);
+                search.clauses.push(clause.clone());
+            }
+        }
+
+        if !search.propagate() {
+            return None;
+        }
+
+        return Some(search);
+    }
+
+    /// Returns the model found by a finished search.
+    fn model(&self, search: &Search) -> Model {
+        let mut values = BTreeMap::new();
+        for (i, name) in self.names.iter().enumerate() {
+            values.insert(name.to_string(), search.assigns[i] == Some(true));
+        }
+
+        return Model { values };
+    }
+
+    /// Returns an iterator over all models of the added clauses. See [Models].
+    pub fn models(self) -> Models {
+        return Models { solver: self, done: false, minimize: None };
+    }
+
+    /// Searches for a model of the added clauses, the hard clauses, that satisfies soft
+    /// clauses of the largest total weight. Every soft constraint is a set of clauses with a
+    /// weight, which is satisfied when all its clauses are. Returns `None` if the hard clauses
+    /// are unsatisfiable.
+    ///
+    /// The search is branch and bound: it enumerates assignments like [Self::solve], and
+    /// backtracks as soon as the soft constraints falsified so far weigh at least as much as
+    /// those falsified by the best model found, until no better model remains.
+    pub fn maximize(&self, soft: &[(Cnf, u64)]) -> Option<Optimum> {
+        let mut solver = self.clone();
+
+        // Symbols are tried with the values that satisfy the soft clauses first, so that a good
+        // model is found early, which prunes more of the search
+        let mut clauses = Vec::new();
+        for (i, (cnf, _)) in soft.iter().enumerate() {
+            for disj in cnf.terms.iter() {
+                let mut clause = Vec::new();
+                for l in disj.literals().iter() {
+                    let v = solver.symbol(l.atom);
+                    solver.phases[v] = l.polarity;
+                    clause.push(lit(v, !l.polarity));
+                }
+                clauses.push((i, clause));
+            }
+        }
+
+        let falsified = |search: &Search| {
+            let mut out: Vec<usize> = clauses.iter()
+                .filter(|(_, c)| c.iter().all(|l| search.value(*l) == Some(false)))
+                .map(|(i, _)| *i)
+                .collect();
+            out.dedup();
+            return out;
+        };
+        let cost = |falsified: &[usize]| falsified.iter().map(|i| soft[*i].1).sum::<u64>();
+
+        let mut search = solver.start()?;
+        let mut best: Option<(u64, Model, Vec<usize>)> = None;
+        loop {
+            let bounded = search.propagate() && best.as_ref().is_none_or(|(b, _, _)| cost(&falsified(&search)) < *b);
+            if !bounded {
+                if !search.backtrack() {
+                    break;
+                }
+                continue;
+            }
+
+            match search.assigns.iter().position(Option::is_none) {
+                Some(v) => {
+                    search.levels.push((search.trail.len(), false));
+                    search.enqueue(lit(v, !solver.phases[v]));
+                },
+                None => {
+                    let violated = falsified(&search);
+                    let c = cost(&violated);
+                    best = Some((c, solver.model(&search), violated));
+                    if c == 0 || !search.backtrack() {
+                        break;
+                    }
+                },
+            }
+        }
+
+        let total: u64 = soft.iter().map(|(_, w)| w).sum();
+        return best.map(|(c, model, violated)| Optimum { model, weight: total - c, violated });
+    }
+}
+
+/// The best model found by [Solver::maximize].
+#[derive(Clone, Debug)]
+pub struct Optimum {
+    /// The model, which satisfies all hard clauses.
+    pub model: Model,
+
+    /// The total weight of the soft constraints that the model satisfies.
+    pub weight: u64,
+
+    /// The indices of the soft constraints that the model falsifies.
+    pub violated: Vec<usize>
+}
+
+/// Enumerates all models of a set of clauses, one at a time. After a model is found, a
+/// blocking clause that excludes exactly that model is added to the solver, so that the next
+/// search finds a different model.
+///
+/// When created by [Models::minimal], every model is minimized (see [Model::minimize]), and the
+/// blocking clause excludes every model that extends the minimized one. This way, models that
+/// differ only in symbols whose value does not matter are enumerated only once.
+pub struct Models {
+    solver: Solver,
+    done: bool,
+
+    /// The clauses to minimize against, if models are minimized.
+    minimize: Option<Cnf>
+}
+
+impl Models {
+    /// Returns an iterator over the minimized models of the given clauses.
+    pub fn minimal(cnf: &Cnf) -> Models {
+        let mut solver = Solver::new();
+        solver.add_cnf(cnf);
+
+        return Models { solver, done: false, minimize: Some(cnf.clone()) };
+    }
+
+    /// Adds a clause that must be satisfied by the models that are yet to be enumerated.
+    pub fn constrain(&mut self, disj: &Disj) {
+        self.solver.add_disj(disj);
+
+        if let Some(cnf) = self.minimize.as_mut() {
+            cnf.insert(disj.clone());
+        }
+    }
+}
+
+impl Iterator for Models {
+    type Item = Model;
+
+    fn next(&mut self) -> Option<Model> {
+        if self.done {
+            return None;
+        }
+
+        let mut model = match self.solver.solve() {
+            Some(m) => m,
+            None => {
+                self.done = true;
+                return None;
+            }
+        };
+
+        if let Some(cnf) = self.minimize.as_ref() {
+            model = model.minimize(cnf);
+        }
+
+        // Note that a model without symbols gives an empty blocking clause, after which
+        // there are no more models
+        self.solver.add_disj(&model.blocking());
+
+        return Some(model);
+    }
+}
+
+/// Answers a batch of questions about the same clauses, sharing work between them. The
+/// clauses are set up and their unit clauses are propagated only once. Every model found is
+/// kept, so that a later question that is falsified by one of them is answered without
+/// searching. And every search prefers the values of the model found last, since related
+/// questions tend to have similar counter-models.
+pub struct Queries {
+    solver: Solver,
+
+    /// The search state after propagating the unit clauses, or `None` if the clauses are
+    /// unsatisfiable.
+    base: Option<Search>,
+
+    /// The models found so far.
+    models: Vec<Model>
+}
+
+impl Queries {
+    pub fn new(cnf: &Cnf) -> Queries {
+        let mut solver = Solver::new();
+        solver.add_cnf(cnf);
+
+        let base = solver.start();
+        return Queries { solver, base, models: Vec::new() };
+    }
+
+    /// Finds a model of the clauses in which the given conclusion is false, i.e. one of its
+    /// clauses is false. Returns `None` if there is no such model, i.e. if the clauses entail
+    /// the conclusion.
+    pub fn counter_model(&mut self, conclusion: &Cnf) -> Option<Model> {
+        for disj in conclusion.terms.iter() {
+            if let Some(m) = self.falsify(disj) {
+                return Some(m);
+            }
+        }
+
+        return None;
+    }
+
+    /// Tests whether the clauses entail the given conclusion.
+    pub fn entails(&mut self, conclusion: &Cnf) -> bool {
+        return self.counter_model(conclusion).is_none();
+    }
+
+    /// Finds a model of the clauses in which the given clause is false.
+    fn falsify(&mut self, disj: &Disj) -> Option<Model> {
+        let base = self.base.as_ref()?;
+
+        // Symbols that a model does not assign can be assigned as needed
+        let falsified = |m: &Model| {
+            let mut m = m.clone();
+            for l in disj.literals().iter() {
+                m.values.entry(l.atom.to_string()).or_insert(!l.polarity);
+            }
+            return m;
+        };
+
+        if let Some(m) = self.models.iter().find(|m| !m.satisfies(disj)) {
+            return Some(falsified(m));
+        }
+
+        let mut search = base.clone();
+        for l in disj.literals().iter() {
+            if let Some(i) = self.solver.index.get(&l.atom) {
+                if !search.enqueue(lit(*i, l.polarity)) {
+                    return None;
+                }
+            }
+        }
+
+        if !search.run(&self.solver.phases) {
+            return None;
+        }
+
+        for (i, value) in search.assigns.iter().enumerate() {
+            self.solver.phases[i] = *value == Some(true);
+        }
+
+        let m = falsified(&self.solver.model(&search));
+        self.models.push(m.clone());
+        return Some(m);
+    }
+}
+
+/// Finds up to `k` distinct models of the given clauses which differ from each other as much
+/// as possible, i.e. with a large sum of pairwise [Model::distance]s.
+///
+/// Models are first picked one at a time, where each symbol prefers the value that the least
+/// of the models picked so far assign to it. Then the picked models are improved iteratively:
+/// each model is replaced by a model found the same way with respect to the other models,
+/// whenever that increases the sum of distances, until no model can be improved.
+pub fn diverse_models(cnf: &Cnf, k: usize) -> Vec<Model> {
+    let symbols = cnf.symbols();
+
+    // Finds a model that differs from all given models, and is far away from them
+    let away_from = |models: &[&Model]| -> Option<Model> {
+        let mut solver = Solver::new();
+        solver.add_cnf(cnf);
+
+        for s in symbols.iter() {
+            let count = models.iter().filter(|m| m.get(s) == Some(true)).count();
+            solver.prefer(s, count * 2 < models.len());
+        }
+        for m in models.iter() {
+            solver.add_disj(&m.blocking());
+        }
+
+        return solver.solve();
+    };
+
+    let mut picked: Vec<Model> = Vec::new();
+    while picked.len() < k {
+        match away_from(&picked.iter().collect::<Vec<_>>()) {
+            Some(m) => picked.push(m),
+            None => break,
+        }
+    }
+
+    let mut improved = true;
+    while improved {
+        improved = false;
+
+        for i in 0..picked.len() {
+            let others: Vec<&Model> = picked.iter().enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, m)| m)
+                .collect();
+
+            let current: usize = others.iter().map(|m| m.distance(&picked[i])).sum();
+            if let Some(m) = away_from(&others) {
+                if others.iter().map(|o| o.distance(&m)).sum::<usize>() > current {
+                    picked[i] = m;
+                    improved = true;
+                }
+            }
+        }
+    }
+
+    return picked;
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl Search {
+    /// Decides symbols, trying the given phases first, propagates and backtracks until all
+    /// symbols are assigned. Returns false if there is no such assignment.
+    fn run(&mut self, phases: &[bool]) -> bool {
+        loop {
+            if !self.propagate() {
+                if !self.backtrack() {
+                    return false;
+                }
+                continue;
+            }
+
+            match self.assigns.iter().position(Option::is_none) {
+                Some(v) => {
+                    self.levels.push((self.trail.len(), false));
+                    self.enqueue(lit(v, !phases[v]));
+                },
+                None => return true,
+            }
+        }
+    }
+
+    /// Returns the value of a literal under the current assignment.
+    fn value(&self, l: Lit) -> Option<bool> {
+        return self.assigns[var(l)].map(|v| v != is_neg(l));
+    }
+
+    /// Assigns the given literal true. Returns false if it is already assigned false.
+    fn enqueue(&mut self, l: Lit) -> bool {
+        return match self.value(l) {
+            Some(v) => v,
+            None => {
+                self.assigns[var(l)] = Some(!is_neg(l));
+                self.trail.push(l);
+                true
+            }
+        };
+    }
+
+    /// Propagates all assignments on the trail. Returns false if some clause became false.
+    fn propagate(&mut self) -> bool {
+        while self.head < self.trail.len() {
+            let falsified = self.trail[self.head] ^ 1;
+            self.head += 1;
+
+            let watching = std::mem::take(&mut self.watches[falsified]);
+            let mut keep = Vec::with_capacity(watching.len());
+            let mut ok = true;
+
+            for (n, &ci) in watching.iter().enumerate() {
+                if !ok {
+                    keep.extend_from_slice(&watching[n..]);
+                    break;
+                }
+
+                let clause = &mut self.clauses[ci];
+                if clause[0] == falsified {
+                    clause.swap(0, 1);
+                }
+
+                let other = clause[0];
+                if self.assigns[var(other)].map(|v| v != is_neg(other)) == Some(true) {
+                    keep.push(ci);
+                    continue;
+                }
+
+                let mut moved = false;
+                for k in 2..clause.len() {
+                    let l = clause[k];
+                    if self.assigns[var(l)].map(|v| v != is_neg(l)) != Some(false) {
+                        clause.swap(1, k);
+                        self.watches[l].push(ci);
+                        moved = true;
+                        break;
+                    }
+                }
+
+                if !moved {
+                    keep.push(ci);
+                    if !self.enqueue(other) {
+                        ok = false;
+                    }
+                }
+            }
+
+            self.watches[falsified] = keep;
+            if !ok {
+                return false;
+            }
+        }
+
+        return true;
+    }
+
+    /// Undoes assignments up to the most recent decision that was not flipped yet, and flips
+    /// it. Returns false if there is no such decision, meaning the clauses are unsatisfiable.
+    fn backtrack(&mut self) -> bool {
+        while let Some((start, flipped)) = self.levels.pop() {
+            let decision = self.trail[start];
+
+            for l in self.trail.drain(start..) {
+                self.assigns[var(l)] = None;
+            }
+            self.head = start;
+
+            if !flipped {
+                self.levels.push((start, true));
+                self.enqueue(decision ^ 1);
+                return true;
+            }
+        }
+
+        return false;
+    }
+}
@@ -0,0 +1,154 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::cnf::{Cnf, Disj};
+use crate::parser::parse_stmt;
+use crate::stmt::Stmt;
+
+/// A pattern that stops a [Stepper] when a resolvent matches it, see [Stepper::run_until].
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Breakpoint {
+    /// Matches the resolvents in which the symbol occurs, inverted or not.
+    Atom(String),
+
+    /// Matches the resolvents that contain all literals of the clause, i.e. that the clause
+    /// subsumes. For example, `P | !Q` matches `P | !Q | R`, but not `P | Q`.
+    Clause(Disj)
+}
+
+impl Breakpoint {
+    /// Tests whether the given resolvent matches this breakpoint.
+    pub fn matches(&self, disj: &Disj) -> bool {
+        return match self {
+            Breakpoint::Atom(name) => disj.literals().iter().any(|l| l.atom == name.as_str()),
+            Breakpoint::Clause(pattern) => pattern.subsumes(disj),
+        };
+    }
+}
+
+impl Display for Breakpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Breakpoint::Atom(name) => write!(f, "{name}"),
+            Breakpoint::Clause(pattern) => write!(f, "{pattern}"),
+        };
+    }
+}
+
+// A symbol, like `Q`, is a breakpoint on that atom, and any other expression that is a single
+// clause, like `!Q` or `P | !Q`, is a breakpoint on that clause.
+impl FromStr for Breakpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Breakpoint, String> {
+        let stmt = parse_stmt(s).map_err(|e| e.to_string())?;
+        if let Stmt::Symbol(name) = stmt {
+            return Ok(Breakpoint::Atom(name));
+        }
+
+        let cnf = stmt.cnf();
+        let clauses = cnf.sorted();
+        return match clauses[..] {
+            [disj] if !disj.is_contradiction() => Ok(Breakpoint::Clause(disj.clone())),
+            _ => Err(format!("Expected an atom or a single clause, found {stmt}")),
+        };
+    }
+}
+
+/// A clause found by a [Stepper]: a premise, or the resolvent of two earlier steps.
+#[derive(Clone, Debug)]
+pub struct Step {
+    pub clause: Disj,
+
+    /// The positions of the steps that the clause is resolved from, the first with the pivot
+    /// non-inverted and the second inverted, along with the pivot, or `None` for a premise.
+    pub parents: Option<(usize, usize, String)>
+}
+
+/// Resolves clauses one resolvent at a time, so that saturation can be followed step by step.
+/// Every pair of clauses is resolved in turn: each clause with all clauses before it, in the
+/// order they were found, after which the resolvents found so far take their turn. Resolvents
+/// that an earlier clause subsumes are skipped, but clauses are never removed, so that every
+/// step keeps its number. Stepping ends when every pair is resolved, or when the empty clause
+/// is derived.
+#[derive(Clone, Debug)]
+pub struct Stepper {
+    /// The clauses stepping started from.
+    premises: Cnf,
+
+    /// The premises, followed by the resolvents found so far.
+    steps: Vec<Step>,
+
+    /// The positions of the next pair of steps to resolve: a step, and a step before it.
+    next: (usize, usize)
+}
+
+impl Stepper {
+    /// Starts to step through the resolvents of the given clauses. Clauses that an earlier
+    /// clause subsumes, in the order of [Cnf::sorted], are left out.
+    pub fn new(clauses: &Cnf) -> Stepper {
+        let mut steps: Vec<Step> = Vec::new();
+        for disj in clauses.sorted() {
+            if !steps.iter().any(|s| s.clause.subsumes(disj)) {
+                steps.push(Step { clause: disj.clone(), parents: None });
+            }
+        }
+        return Stepper { premises: clauses.clone(), steps, next: (1, 0) };
+    }
+
+    /// Returns the clauses stepping started from.
+    pub fn premises(&self) -> &Cnf {
+        return &self.premises;
+    }
+
+    /// Returns the premises, followed by the resolvents found so far, in order.
+    pub fn steps(&self) -> &[Step] {
+        return &self.steps;
+    }
+
+    /// Tests whether the empty clause was derived.
+    pub fn is_refuted(&self) -> bool {
+        return self.steps.iter().any(|s| s.clause.is_contradiction());
+    }
+
+    /// Tests whether stepping ended: the empty clause was derived, or every pair of clauses
+    /// is resolved and no resolvents are left.
+    pub fn is_done(&self) -> bool {
+        return self.is_refuted() || self.next.0 >= self.steps.len();
+    }
+
+    /// Resolves pairs of clauses until a new resolvent is found, and returns its position in
+    /// [Self::steps], or `None` if stepping ended, see [Self::is_done].
+    pub fn step(&mut self) -> Option<usize> {
+        while !self.is_done() {
+            let (j, i) = self.next;
+            self.next = if i + 1 < j { (j, i + 1) } else { (j + 1, 0) };
+
+            let (left, right) = (&self.steps[j].clause, &self.steps[i].clause);
+            let Some(lit) = left.literals().iter().find(|l| right.contains(&!*l)) else { continue };
+            let Some(resolvent) = left.resolve(right, &lit.atom) else { continue };
+            if self.steps.iter().any(|s| s.clause.subsumes(&resolvent)) {
+                continue;
+            }
+
+            let (pos, neg) = if lit.polarity { (j, i) } else { (i, j) };
+            let parents = Some((pos, neg, lit.atom.to_string()));
+            self.steps.push(Step { clause: resolvent, parents });
+            return Some(self.steps.len() - 1);
+        }
+        return None;
+    }
+
+    /// Steps until a resolvent matches one of the given breakpoints, and returns its position
+    /// in [Self::steps], or `None` if stepping ended first, or if the given number of steps
+    /// were taken without a match.
+    pub fn run_until(&mut self, breakpoints: &[Breakpoint], max_steps: usize) -> Option<usize> {
+        for _ in 0..max_steps {
+            let index = self.step()?;
+            if breakpoints.iter().any(|b| b.matches(&self.steps[index].clause)) {
+                return Some(index);
+            }
+        }
+        return None;
+    }
+}
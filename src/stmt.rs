@@ -1,9 +1,41 @@
+use std::collections::BTreeSet;
 use std::fmt::Display;
+use std::str::FromStr;
 
 use crate::cnf::{Cnf, Disj};
+use crate::dnf::{Conj, Dnf};
+use crate::parser::{parse_stmt, ParseError};
+use crate::solver::Solver;
+use crate::symbol::Symbol;
+
+/// An error in the conversion of a statement to a normal form, see [Stmt::try_cnf]. Such an
+/// error means that a step of the conversion left the statement in a state that the next step
+/// does not expect.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum CnfError {
+    /// An implication, equivalence, exclusive disjunction or if-then-else expression was left
+    /// where only conjunctions, disjunctions and negations were expected.
+    NotExtrapolated(Stmt),
+
+    /// A statement was left that is not in the expected normal form, like a negated
+    /// conjunction in a clause.
+    NotNormal(Stmt)
+}
+
+impl Display for CnfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            CnfError::NotExtrapolated(s) => write!(f, "Must extrapolate before normalizing, found {s}"),
+            CnfError::NotNormal(s) => write!(f, "Not in normal form, found {s}"),
+        };
+    }
+}
+
+impl std::error::Error for CnfError {}
 
 /// A statement.
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Stmt {
     /// The contradictory statment, i.e., "false".
     Cont,
@@ -11,23 +43,48 @@ pub enum Stmt {
     /// The tautological statement, i.e., "true".
     Taut,
 
-    /// A basic symbol, declared by a single character.
-    Symbol(char),
+    /// A basic symbol, declared by its name.
+    Symbol(String),
 
     /// The inverse of a statement.
     Not(Box<Stmt>),
 
-    /// The conjunction of two statements.
-    And(Box<Stmt>, Box<Stmt>),
+    /// The conjunction of any number of statements. Statements built by [Stmt::and] and
+    /// [Stmt::all] have at least two operands, none of which is a conjunction itself.
+    And(Vec<Stmt>),
 
-    /// The disjunction of two statements.
-    Or(Box<Stmt>, Box<Stmt>),
+    /// The disjunction of any number of statements. Statements built by [Stmt::or] and
+    /// [Stmt::any] have at least two operands, none of which is a disjunction itself.
+    Or(Vec<Stmt>),
 
     /// The implication of one statement from another.
     Implies(Box<Stmt>, Box<Stmt>),
 
     /// The equivalence (bi-implication) of two statements.
-    Equiv(Box<Stmt>, Box<Stmt>)
+    Equiv(Box<Stmt>, Box<Stmt>),
+
+    /// The exclusive disjunction of two statements, which holds when exactly one of them holds.
+    Xor(Box<Stmt>, Box<Stmt>),
+
+    /// The if-then-else of three statements: the second if the first holds, otherwise the third.
+    Ite(Box<Stmt>, Box<Stmt>, Box<Stmt>),
+
+    /// The universal quantification of a statement over a symbol, which holds when the
+    /// statement holds for both values of the symbol.
+    Forall(String, Box<Stmt>),
+
+    /// The existential quantification of a statement over a symbol, which holds when the
+    /// statement holds for some value of the symbol.
+    Exists(String, Box<Stmt>),
+
+    /// The necessity of a statement: it holds in every world accessible from this one. Only
+    /// [crate::modal] reasons about worlds: everywhere else, a modal statement is an opaque
+    /// symbol, named after how it is written, e.g. `[]A`.
+    Necessarily(Box<Stmt>),
+
+    /// The possibility of a statement: it holds in some world accessible from this one. Like
+    /// [Stmt::Necessarily], this is an opaque symbol outside of [crate::modal].
+    Possibly(Box<Stmt>)
 }
 
 impl Stmt {
@@ -39,8 +96,8 @@ impl Stmt {
         return Stmt::Cont;
     }
 
-    pub fn symbol(c: char) -> Stmt {
-        return Stmt::Symbol(c);
+    pub fn symbol(name: &str) -> Stmt {
+        return Stmt::Symbol(name.to_string());
     }
 
     pub fn not(self) -> Stmt {
@@ -48,11 +105,50 @@ impl Stmt {
     }
 
     pub fn and(self, e: Stmt) -> Stmt {
-        return Stmt::And(Box::new(self), Box::new(e));
+        return Stmt::all(vec![self, e]);
     }
 
     pub fn or(self, e: Stmt) -> Stmt {
-        return Stmt::Or(Box::new(self), Box::new(e));
+        return Stmt::any(vec![self, e]);
+    }
+
+    /// Creates the conjunction of the given statements. Operands that are conjunctions
+    /// themselves are flattened into it, so `A & (B & C)` becomes `A & B & C`. The conjunction
+    /// of no statements is a tautology, and the conjunction of one statement is that statement.
+    pub fn all(stmts: Vec<Stmt>) -> Stmt {
+        let mut out = Vec::with_capacity(stmts.len());
+        for stmt in stmts {
+            match stmt {
+                Stmt::And(v) => out.extend(v),
+                s => out.push(s),
+            }
+        }
+
+        return match out.len() {
+            0 => Stmt::Taut,
+            1 => out.pop().unwrap(),
+            _ => Stmt::And(out),
+        };
+    }
+
+    /// Creates the disjunction of the given statements. Operands that are disjunctions
+    /// themselves are flattened into it, so `A | (B | C)` becomes `A | B | C`. The disjunction
+    /// of no statements is a contradiction, and the disjunction of one statement is that
+    /// statement.
+    pub fn any(stmts: Vec<Stmt>) -> Stmt {
+        let mut out = Vec::with_capacity(stmts.len());
+        for stmt in stmts {
+            match stmt {
+                Stmt::Or(v) => out.extend(v),
+                s => out.push(s),
+            }
+        }
+
+        return match out.len() {
+            0 => Stmt::Cont,
+            1 => out.pop().unwrap(),
+            _ => Stmt::Or(out),
+        };
     }
 
     pub fn implies(self, e: Stmt) -> Stmt {
@@ -63,18 +159,379 @@ impl Stmt {
         return Stmt::Equiv(Box::new(self), Box::new(e));
     }
 
-    /// Extrapolation expands implications and equivalences to basic conjunctions and disjunctions.
-    /// A resulting expression does not have any implications or equivalences.
+    pub fn xor(self, e: Stmt) -> Stmt {
+        return Stmt::Xor(Box::new(self), Box::new(e));
+    }
+
+    pub fn ite(self, t: Stmt, e: Stmt) -> Stmt {
+        return Stmt::Ite(Box::new(self), Box::new(t), Box::new(e));
+    }
+
+    pub fn forall(symbol: &str, body: Stmt) -> Stmt {
+        return Stmt::Forall(symbol.to_string(), Box::new(body));
+    }
+
+    pub fn exists(symbol: &str, body: Stmt) -> Stmt {
+        return Stmt::Exists(symbol.to_string(), Box::new(body));
+    }
+
+    pub fn necessarily(self) -> Stmt {
+        return Stmt::Necessarily(Box::new(self));
+    }
+
+    pub fn possibly(self) -> Stmt {
+        return Stmt::Possibly(Box::new(self));
+    }
+
+    /// Tests whether this statement is a modal statement, see [Stmt::Necessarily] and
+    /// [Stmt::Possibly].
+    pub fn is_modal(&self) -> bool {
+        return matches!(self, Stmt::Necessarily(_) | Stmt::Possibly(_));
+    }
+
+    /// Creates the statement of a clause: the disjunction of its literals, in order. The empty
+    /// clause is a contradiction.
+    pub fn clause(disj: &Disj) -> Stmt {
+        let lits = disj.literals().iter().map(|l| if l.polarity {
+            Stmt::symbol(&l.atom)
+        } else {
+            Stmt::symbol(&l.atom).not()
+        });
+
+        return Stmt::any(lits.collect());
+    }
+
+    /// Creates a statement that holds when exactly one of the given statements holds. This is
+    /// the disjunction of all statements (at least one), in conjunction with the negated
+    /// conjunction of every pair of statements (at most one).
+    ///
+    /// For example, given `P`, `Q` and `R`, it creates the statement
+    /// `(P | Q | R) & !(P & Q) & !(P & R) & !(Q & R)`.
+    pub fn exactly_one(stmts: &[Stmt]) -> Stmt {
+        let mut e = match stmts.iter().cloned().reduce(Stmt::or) {
+            Some(e) => e,
+            None => return Stmt::cont(),
+        };
+
+        for i in 0..stmts.len() {
+            for j in (i + 1)..stmts.len() {
+                e = e.and(stmts[i].clone().and(stmts[j].clone()).not());
+            }
+        }
+
+        return e;
+    }
+
+    /// Returns the name of this statement if it is a symbol.
+    pub fn as_symbol(&self) -> Option<&str> {
+        return match self {
+            Stmt::Symbol(s) => Some(s),
+            _ => None,
+        };
+    }
+
+    /// Returns the operand of this statement if it is a negation.
+    pub fn as_not(&self) -> Option<&Stmt> {
+        return match self {
+            Stmt::Not(o) => Some(o),
+            _ => None,
+        };
+    }
+
+    /// Returns the operands of this statement if it is a conjunction.
+    pub fn as_and(&self) -> Option<&[Stmt]> {
+        return match self {
+            Stmt::And(v) => Some(v),
+            _ => None,
+        };
+    }
+
+    /// Returns the operands of this statement if it is a disjunction.
+    pub fn as_or(&self) -> Option<&[Stmt]> {
+        return match self {
+            Stmt::Or(v) => Some(v),
+            _ => None,
+        };
+    }
+
+    /// Returns the premise and the conclusion of this statement if it is an implication.
+    pub fn as_implication(&self) -> Option<(&Stmt, &Stmt)> {
+        return match self {
+            Stmt::Implies(l, r) => Some((l, r)),
+            _ => None,
+        };
+    }
+
+    /// Returns both sides of this statement if it is an equivalence.
+    pub fn as_equiv(&self) -> Option<(&Stmt, &Stmt)> {
+        return match self {
+            Stmt::Equiv(l, r) => Some((l, r)),
+            _ => None,
+        };
+    }
+
+    /// Splits this statement into the statements it is a conjunction of. Nested conjunctions
+    /// are split as well, and a tautology splits into nothing. A statement that is not a
+    /// conjunction is returned as is.
+    ///
+    /// For example, `A & (B & C)` splits into `A`, `B` and `C`.
+    pub fn split_conjuncts(&self) -> Vec<&Stmt> {
+        return match self {
+            Stmt::Taut => Vec::new(),
+            Stmt::And(v) => v.iter().flat_map(Stmt::split_conjuncts).collect(),
+            s => vec![s]
+        };
+    }
+
+    /// Splits this statement into the statements it is a disjunction of, like
+    /// [Self::split_conjuncts]. A contradiction splits into nothing.
+    pub fn split_disjuncts(&self) -> Vec<&Stmt> {
+        return match self {
+            Stmt::Cont => Vec::new(),
+            Stmt::Or(v) => v.iter().flat_map(Stmt::split_disjuncts).collect(),
+            s => vec![s]
+        };
+    }
+
+    /// Returns the symbols that occur in this statement, in order of name. Symbols are left
+    /// out where a quantifier binds them, so `forall P. P | Q` has the symbol `Q` only. Modal
+    /// statements count as symbols themselves, so `[]P -> Q` has the symbols `Q` and `[]P`.
+    pub fn symbols(&self) -> BTreeSet<String> {
+        let mut out = BTreeSet::new();
+        self.collect_symbols(&mut out);
+        return out;
+    }
+
+    fn collect_symbols(&self, out: &mut BTreeSet<String>) {
+        match self {
+            Stmt::Cont | Stmt::Taut => {},
+            Stmt::Symbol(s) => {
+                out.insert(s.clone());
+            },
+            Stmt::Not(o) => o.collect_symbols(out),
+            Stmt::And(v) | Stmt::Or(v) => {
+                for s in v.iter() {
+                    s.collect_symbols(out);
+                }
+            },
+            Stmt::Implies(l, r) | Stmt::Equiv(l, r) | Stmt::Xor(l, r) => {
+                l.collect_symbols(out);
+                r.collect_symbols(out);
+            },
+            Stmt::Ite(c, t, e) => {
+                c.collect_symbols(out);
+                t.collect_symbols(out);
+                e.collect_symbols(out);
+            },
+            Stmt::Forall(sym, o) | Stmt::Exists(sym, o) => {
+                let mut inner = o.symbols();
+                inner.remove(sym);
+                out.extend(inner);
+            },
+            Stmt::Necessarily(_) | Stmt::Possibly(_) => {
+                out.insert(self.to_string());
+            },
+        }
+    }
+
+    /// Tests whether this statement has a quantifier, see [Stmt::Forall] and [Stmt::Exists].
+    /// Quantifiers within modal statements do not count, as those are opaque symbols.
+    pub fn has_quantifiers(&self) -> bool {
+        return match self {
+            Stmt::Cont | Stmt::Taut | Stmt::Symbol(_) => false,
+            Stmt::Not(o) => o.has_quantifiers(),
+            Stmt::And(v) | Stmt::Or(v) => v.iter().any(Stmt::has_quantifiers),
+            Stmt::Implies(l, r) | Stmt::Equiv(l, r) | Stmt::Xor(l, r) => l.has_quantifiers() || r.has_quantifiers(),
+            Stmt::Ite(c, t, e) => c.has_quantifiers() || t.has_quantifiers() || e.has_quantifiers(),
+            Stmt::Forall(..) | Stmt::Exists(..) => true,
+            Stmt::Necessarily(_) | Stmt::Possibly(_) => false,
+        };
+    }
+
+    /// Eliminates the quantifiers of this statement by expanding them: `forall P. A` becomes
+    /// the conjunction of `A` with `P` fixed to true and to false (see [Self::fix]), and
+    /// `exists P. A` becomes their disjunction. The result is equivalent, but every quantifier
+    /// doubles the size of its body, so it grows exponentially with the nesting of quantifiers.
+    ///
+    /// For example, expanding `exists P. (P & Q) | (!P & R)` gives `Q | R`.
+    pub fn expand(&self) -> Stmt {
+        if !self.has_quantifiers() {
+            return self.clone();
+        }
+
+        let exp = |s: &Stmt| Box::new(s.expand());
+        return match self {
+            Stmt::Not(o) => Stmt::Not(exp(o)),
+            Stmt::And(v) => Stmt::all_const(v.iter().map(Stmt::expand).collect()),
+            Stmt::Or(v) => Stmt::any_const(v.iter().map(Stmt::expand).collect()),
+            Stmt::Implies(l, r) => Stmt::Implies(exp(l), exp(r)),
+            Stmt::Equiv(l, r) => Stmt::Equiv(exp(l), exp(r)),
+            Stmt::Xor(l, r) => Stmt::Xor(exp(l), exp(r)),
+            Stmt::Ite(c, t, e) => Stmt::Ite(exp(c), exp(t), exp(e)),
+            Stmt::Forall(sym, o) => {
+                let o = o.expand();
+                Stmt::all_const(vec![o.fix(sym, true), o.fix(sym, false)])
+            },
+            Stmt::Exists(sym, o) => {
+                let o = o.expand();
+                Stmt::any_const(vec![o.fix(sym, true), o.fix(sym, false)])
+            },
+            s => s.clone()
+        };
+    }
+
+    /// Conditions this statement on the given value of a symbol: every occurrence of the symbol
+    /// is replaced by [Stmt::Taut] or [Stmt::Cont], after which constants are simplified away.
+    /// The result is either a constant, or a statement without constants that does not refer
+    /// to the symbol.
+    ///
+    /// For example, conditioning `(P & Q) | R` on `P` being false gives `R`.
+    pub fn fix(&self, symbol: &str, value: bool) -> Stmt {
+        return match self {
+            Stmt::Symbol(s) if s == symbol => if value { Stmt::Taut } else { Stmt::Cont },
+            s if s.is_modal() && s.to_string() == symbol => if value { Stmt::Taut } else { Stmt::Cont },
+            Stmt::Not(o) => o.fix(symbol, value).negate(),
+            Stmt::And(v) => Stmt::all_const(v.iter().map(|s| s.fix(symbol, value)).collect()),
+            Stmt::Or(v) => Stmt::any_const(v.iter().map(|s| s.fix(symbol, value)).collect()),
+            Stmt::Implies(l, r) => match (l.fix(symbol, value), r.fix(symbol, value)) {
+                (Stmt::Cont, _) | (_, Stmt::Taut) => Stmt::Taut,
+                (Stmt::Taut, o) => o,
+                (o, Stmt::Cont) => o.negate(),
+                (l, r) => l.implies(r)
+            },
+            Stmt::Equiv(l, r) => match (l.fix(symbol, value), r.fix(symbol, value)) {
+                (Stmt::Taut, o) | (o, Stmt::Taut) => o,
+                (Stmt::Cont, o) | (o, Stmt::Cont) => o.negate(),
+                (l, r) => l.equiv(r)
+            },
+            Stmt::Xor(l, r) => match (l.fix(symbol, value), r.fix(symbol, value)) {
+                (Stmt::Cont, o) | (o, Stmt::Cont) => o,
+                (Stmt::Taut, o) | (o, Stmt::Taut) => o.negate(),
+                (l, r) => l.xor(r)
+            },
+            Stmt::Ite(c, t, e) => match (c.fix(symbol, value), t.fix(symbol, value), e.fix(symbol, value)) {
+                (Stmt::Taut, t, _) => t,
+                (Stmt::Cont, _, e) => e,
+                (_, Stmt::Taut, Stmt::Taut) => Stmt::Taut,
+                (_, Stmt::Cont, Stmt::Cont) => Stmt::Cont,
+                (c, Stmt::Taut, Stmt::Cont) => c,
+                (c, Stmt::Cont, Stmt::Taut) => c.negate(),
+                (c, Stmt::Taut, e) => c.or(e),
+                (c, Stmt::Cont, e) => c.negate().and(e),
+                (c, t, Stmt::Taut) => c.negate().or(t),
+                (c, t, Stmt::Cont) => c.and(t),
+                (c, t, e) => c.ite(t, e)
+            },
+            // A quantifier that binds the symbol hides it from the outside
+            Stmt::Forall(sym, o) if sym != symbol => match o.fix(symbol, value) {
+                o @ (Stmt::Taut | Stmt::Cont) => o,
+                o => Stmt::forall(sym, o)
+            },
+            Stmt::Exists(sym, o) if sym != symbol => match o.fix(symbol, value) {
+                o @ (Stmt::Taut | Stmt::Cont) => o,
+                o => Stmt::exists(sym, o)
+            },
+            s => s.clone()
+        };
+    }
+
+    /// Replaces every occurrence of the given symbol in this statement by the given
+    /// replacement. Unlike [Self::fix], nothing is simplified. Occurrences bound by a
+    /// quantifier are not replaced, and a quantifier that binds a symbol of the replacement
+    /// binds a fresh symbol instead, so that it does not capture the replacement.
+    ///
+    /// For example, substituting `A & B` for `X` in `X -> C` gives `(A & B) -> C`, and
+    /// substituting `Q` for `X` in `forall Q. X & Q` gives `forall Q1. Q & Q1`.
+    pub fn substitute(&self, symbol: &str, replacement: &Stmt) -> Stmt {
+        let sub = |s: &Stmt| Box::new(s.substitute(symbol, replacement));
+
+        return match self {
+            Stmt::Symbol(s) if s == symbol => replacement.clone(),
+            s if s.is_modal() && s.to_string() == symbol => replacement.clone(),
+            Stmt::Not(o) => Stmt::Not(sub(o)),
+            Stmt::And(v) => Stmt::all(v.iter().map(|s| s.substitute(symbol, replacement)).collect()),
+            Stmt::Or(v) => Stmt::any(v.iter().map(|s| s.substitute(symbol, replacement)).collect()),
+            Stmt::Implies(l, r) => Stmt::Implies(sub(l), sub(r)),
+            Stmt::Equiv(l, r) => Stmt::Equiv(sub(l), sub(r)),
+            Stmt::Xor(l, r) => Stmt::Xor(sub(l), sub(r)),
+            Stmt::Ite(c, t, e) => Stmt::Ite(sub(c), sub(t), sub(e)),
+            Stmt::Forall(sym, o) | Stmt::Exists(sym, o) if sym != symbol => {
+                let mut sym = sym.clone();
+                let mut body = (**o).clone();
+
+                let free = replacement.symbols();
+                if free.contains(&sym) && body.symbols().contains(symbol) {
+                    let mut used = body.symbols();
+                    used.extend(free);
+                    used.insert(symbol.to_string());
+
+                    let mut i = 1;
+                    while used.contains(&format!("{sym}{i}")) {
+                        i += 1;
+                    }
+                    let fresh = format!("{sym}{i}");
+                    body = body.substitute(&sym, &Stmt::symbol(&fresh));
+                    sym = fresh;
+                }
+
+                let body = sub(&body);
+                if matches!(self, Stmt::Forall(..)) { Stmt::Forall(sym, body) } else { Stmt::Exists(sym, body) }
+            },
+            s => s.clone()
+        };
+    }
+
+    /// Creates the conjunction of the given statements, like [Stmt::all], leaving out
+    /// tautologies. If one of the statements is a contradiction, so is the conjunction.
+    fn all_const(stmts: Vec<Stmt>) -> Stmt {
+        if stmts.contains(&Stmt::Cont) {
+            return Stmt::Cont;
+        }
+        return Stmt::all(stmts.into_iter().filter(|s| *s != Stmt::Taut).collect());
+    }
+
+    /// Creates the disjunction of the given statements, like [Stmt::any], leaving out
+    /// contradictions. If one of the statements is a tautology, so is the disjunction.
+    fn any_const(stmts: Vec<Stmt>) -> Stmt {
+        if stmts.contains(&Stmt::Taut) {
+            return Stmt::Taut;
+        }
+        return Stmt::any(stmts.into_iter().filter(|s| *s != Stmt::Cont).collect());
+    }
+
+    /// Negates this statement, where the negation of a constant is the other constant.
+    fn negate(self) -> Stmt {
+        return match self {
+            Stmt::Taut => Stmt::Cont,
+            Stmt::Cont => Stmt::Taut,
+            s => s.not()
+        };
+    }
+
+    /// Extrapolation expands implications, equivalences, exclusive disjunctions and
+    /// if-then-else expressions to basic conjunctions and disjunctions, and quantifiers by
+    /// [Self::expand]. Modal statements are replaced by the symbols they stand for. A resulting
+    /// expression does not have any of these.
     fn extrapolate(self) -> Self {
         return match self {
             Stmt::Not(o) => Self::not((*o).extrapolate()),
-            Stmt::And(l, r) => (*l).extrapolate().and((*r).extrapolate()),
-            Stmt::Or(l, r) => (*l).extrapolate().or((*r).extrapolate()),
+            Stmt::And(v) => Stmt::all(v.into_iter().map(Stmt::extrapolate).collect()),
+            Stmt::Or(v) => Stmt::any(v.into_iter().map(Stmt::extrapolate).collect()),
             Stmt::Implies(l, r) => Self::not((*l).extrapolate()).or((*r).extrapolate()),
             Stmt::Equiv(l, r) => Self::and(
                 Self::not((*l).clone().extrapolate()).or((*r).clone().extrapolate()), 
                 Self::not((*r).extrapolate()).or((*l).extrapolate())
             ),
+            Stmt::Xor(l, r) => Self::and(
+                (*l).clone().extrapolate().or((*r).clone().extrapolate()),
+                Self::not((*l).extrapolate()).or(Self::not((*r).extrapolate()))
+            ),
+            Stmt::Ite(c, t, e) => Self::and(
+                Self::not((*c).clone().extrapolate()).or((*t).extrapolate()),
+                (*c).extrapolate().or((*e).extrapolate())
+            ),
+            s @ (Stmt::Forall(..) | Stmt::Exists(..)) => s.expand().extrapolate(),
+            s @ (Stmt::Necessarily(_) | Stmt::Possibly(_)) => Stmt::Symbol(s.to_string()),
             s => s,
         };
     }
@@ -83,148 +540,341 @@ impl Stmt {
     /// a statement. This can only happen after [Self::extrapolate].
     /// A resulting expression is either [Stmt::Cont], [Stmt::Taut], or some other expression
     /// not including contradictions or tautologies.
-    fn extract_cont_taut(self) -> Self {
-        return match self {
+    fn extract_cont_taut(self) -> Result<Self, CnfError> {
+        return Ok(match self {
             Stmt::Cont => self,
             Stmt::Taut => self,
             Stmt::Symbol(_) => self,
-            Stmt::Not(o) => match (*o).extract_cont_taut() {
+            Stmt::Not(o) => match (*o).extract_cont_taut()? {
                 Stmt::Cont => Stmt::Taut,
                 Stmt::Taut => Stmt::Cont,
                 s => Self::not(s)
             },
-            Stmt::And(l, r) => match ((*l).extract_cont_taut(), (*r).extract_cont_taut()) {
-                (Stmt::Cont, _) => Stmt::Cont,
-                (_, Stmt::Cont) => Stmt::Cont,
-                (Stmt::Taut, o) => o,
-                (o, Stmt::Taut) => o,
-                (l, r) => l.and(r)
-            },
-            Stmt::Or(l, r) => match ((*l).extract_cont_taut(), (*r).extract_cont_taut()) {
-                (Stmt::Taut, _) => Stmt::Taut,
-                (_, Stmt::Taut) => Stmt::Taut,
-                (Stmt::Cont, o) => o,
-                (o, Stmt::Cont) => o,
-                (l, r) => l.or(r)
-            },
-            _ => panic!("Must extrapolate implications before extracting cont/taut"),
-        };
+            Stmt::And(v) => Stmt::all_const(v.into_iter().map(Stmt::extract_cont_taut).collect::<Result<_, _>>()?),
+            Stmt::Or(v) => Stmt::any_const(v.into_iter().map(Stmt::extract_cont_taut).collect::<Result<_, _>>()?),
+            s => return Err(CnfError::NotExtrapolated(s)),
+        });
     }
 
     /// Applies DeMorgan recursively.
     /// A resulting expression does not have any negated conjunctions or disjunctions.
     /// I.e. !P can exist but !(P | Q) cannot.
-    fn demorgan_pos(self) -> Self {
-        return match self {
-            Stmt::Not(o) => (*o).demorgan_neg(),
-            Stmt::And(l, r) => (*l).demorgan_pos().and((*r).demorgan_pos()),
-            Stmt::Or(l, r) => (*l).demorgan_pos().or((*r).demorgan_pos()),
+    fn demorgan_pos(self) -> Result<Self, CnfError> {
+        return Ok(match self {
+            Stmt::Not(o) => (*o).demorgan_neg()?,
+            Stmt::And(v) => Stmt::all(v.into_iter().map(Stmt::demorgan_pos).collect::<Result<_, _>>()?),
+            Stmt::Or(v) => Stmt::any(v.into_iter().map(Stmt::demorgan_pos).collect::<Result<_, _>>()?),
             Stmt::Symbol(_) | Stmt::Taut | Stmt::Cont => self,
-            _ => panic!("Must extrapolate implications before DeMorgan"),
-        }
+            s => return Err(CnfError::NotExtrapolated(s)),
+        });
     }
 
     /// Negates this expression by applying DeMorgan recursively. Used with [Self::demorgan_pos].
-    fn demorgan_neg(self) -> Self {
-        return match self {
-            Stmt::Not(o) => *o,
-            Stmt::And(l, r) => (*l).demorgan_neg().or((*r).demorgan_neg()),
-            Stmt::Or(l, r) => (*l).demorgan_neg().and((*r).demorgan_neg()),
+    fn demorgan_neg(self) -> Result<Self, CnfError> {
+        return Ok(match self {
+            Stmt::Not(o) => (*o).demorgan_pos()?,
+            Stmt::And(v) => Stmt::any(v.into_iter().map(Stmt::demorgan_neg).collect::<Result<_, _>>()?),
+            Stmt::Or(v) => Stmt::all(v.into_iter().map(Stmt::demorgan_neg).collect::<Result<_, _>>()?),
             Stmt::Symbol(_) => Self::not(self),
             Stmt::Taut => Stmt::Cont,
             Stmt::Cont => Stmt::Taut,
-            _ => panic!("Must extrapolate implications before DeMorgan"),
+            s => return Err(CnfError::NotExtrapolated(s)),
+        });
+    }
+
+    /// Picks one statement out of every given group of statements, in every possible way.
+    /// This distributes a disjunction of conjunctions into a conjunction of disjunctions, and
+    /// vice versa.
+    fn product(groups: Vec<Vec<Stmt>>) -> Vec<Vec<Stmt>> {
+        let mut out: Vec<Vec<Stmt>> = vec![Vec::new()];
+
+        for group in groups {
+            let mut next = Vec::with_capacity(out.len() * group.len());
+            for picked in out.iter() {
+                for s in group.iter() {
+                    let mut p = picked.clone();
+                    p.push(s.clone());
+                    next.push(p);
+                }
+            }
+            out = next;
         }
+
+        return out;
     }
 
-    /// Distributes disjunctions over conjunctions. When called repeatedly, after applying
-    /// DeMorgan, contradiction-tautology-extraction and extrapolation, the resulting expression
-    /// will eventually become conjunction-normal-form.
+    /// Distributes disjunctions over conjunctions, after applying DeMorgan,
+    /// contradiction-tautology-extraction and extrapolation. The resulting expression is in
+    /// conjunctive normal form: a conjunction of clauses, or a single clause.
     fn dist_disj(self) -> Self {
         return match self {
-            Stmt::Or(l, r) => {
-                match ((*l).dist_disj(), (*r).dist_disj()) {
-                    (Stmt::And(ll, lr), Stmt::And(rl, rr)) => {
-                            ((*ll).clone().or((*rl).clone()))
-                        .and((*ll).clone().or((*rr).clone()))
-                        .and((*lr).clone().or((*rl).clone()))
-                        .and((*lr).clone().or((*rr).clone()))
-                    }
+            Stmt::Or(v) => {
+                // Every clause of the result picks one clause of every operand
+                let groups = v.into_iter().map(Stmt::dist_disj).map(|o| match o {
+                    Stmt::And(c) => c,
+                    o => vec![o]
+                });
 
-                    (Stmt::And(ll, lr), rd) => {
-                            ((*ll).or(rd.clone()))
-                        .and((*lr).or(rd.clone()))
-                    }
+                Stmt::all(Self::product(groups.collect()).into_iter().map(Stmt::any).collect())
+            },
+            Stmt::And(v) => Stmt::all(v.into_iter().map(Stmt::dist_disj).collect()),
+            s => s
+        };
+    }
 
-                    (ld, Stmt::And(rl, rr)) => {
-                            (ld.clone().or(*rl))
-                        .and(ld.clone().or(*rr))
-                    }
+    /// Distributes conjunctions over disjunctions, like [Self::dist_disj] the other way
+    /// around. The resulting expression is in disjunctive normal form: a disjunction of
+    /// conjunctions, or a single conjunction.
+    fn dist_conj(self) -> Self {
+        return match self {
+            Stmt::And(v) => {
+                // Every conjunction of the result picks one conjunction of every operand
+                let groups = v.into_iter().map(Stmt::dist_conj).map(|o| match o {
+                    Stmt::Or(c) => c,
+                    o => vec![o]
+                });
 
-                    (ld, rd) => ld.or(rd)
-                }
+                Stmt::any(Self::product(groups.collect()).into_iter().map(Stmt::all).collect())
             },
-            Stmt::And(l, r) => l.dist_disj().and(r.dist_disj()),
-            Stmt::Not(o) => Stmt::not(o.dist_disj()),
+            Stmt::Or(v) => Stmt::any(v.into_iter().map(Stmt::dist_conj).collect()),
             s => s
         };
     }
 
-    /// Translates this expression to conjunctive normal form (CNF).
-    fn base_cnf(self) -> Self {
-        let mut e = self;
+    /// Translates this expression to negation normal form (NNF): implications, equivalences,
+    /// exclusive disjunctions and if-then-else expressions are expanded, constants are
+    /// simplified away, and negations are pushed inwards until they only apply to symbols.
+    /// The result is either [Stmt::Cont], [Stmt::Taut], or a statement built from
+    /// conjunctions, disjunctions, symbols and negated symbols only. Fails when a step of the
+    /// translation goes wrong.
+    ///
+    /// For example, the NNF of `!(A -> (B | ~))` is `(A & !B)`.
+    pub fn try_nnf(&self) -> Result<Stmt, CnfError> {
+        let mut e = self.clone();
         e = e.extrapolate();
-        e = e.extract_cont_taut();
-        e = e.demorgan_pos();
-        loop {
-            let n = e.clone().dist_disj();
-            if n == e {
-                return n;
-            }
-            e = n;
-        }
+        e = e.extract_cont_taut()?;
+        e = e.demorgan_pos()?;
+        return Ok(e);
     }
 
-    /// If this expression is a clause, returns a [Disj] of that clause.
-    fn disj(&self) -> Option<Disj> {
+    /// If this expression is a clause, returns a [Disj] of that clause. Fails if it is not.
+    fn disj(&self) -> Result<Option<Disj>, CnfError> {
         // Returns None in case of a tautology
-        return match self {
+        return Ok(match self {
             Stmt::Taut => None,
             Stmt::Cont => Some(Disj::contradiction()),
-            Stmt::Symbol(c) => Some(Disj::axiom(*c)),
-            Stmt::Not(o) => {
-                if let Stmt::Symbol(c) = **o {
-                    Some(Disj::axiom_not(c))
-                } else {
-                    panic!("Not in CNF")
+            Stmt::Symbol(c) => Some(Disj::axiom(c)),
+            Stmt::Not(o) => match &**o {
+                Stmt::Symbol(c) => Some(Disj::axiom_not(c)),
+                _ => return Err(CnfError::NotNormal(self.clone())),
+            },
+            Stmt::Or(v) => {
+                let mut out = Some(Disj::contradiction());
+                for s in v.iter() {
+                    // Combine with tautology: True | P is still True
+                    out = match (out, s.disj()?) {
+                        (Some(out), Some(d)) => out.combine(&d),
+                        _ => None,
+                    };
                 }
+                out
+            },
+            _ => return Err(CnfError::NotNormal(self.clone())),
+        });
+    }
+
+    /// If this expression is a conjunction of symbols, returns a [Conj] of that conjunction.
+    /// Fails if it is not.
+    fn conj(&self) -> Result<Option<Conj>, CnfError> {
+        // Returns None in case of a contradiction
+        return Ok(match self {
+            Stmt::Taut => Some(Conj::tautology()),
+            Stmt::Cont => None,
+            Stmt::Symbol(c) => Some(Conj::fact(c)),
+            Stmt::Not(o) => match &**o {
+                Stmt::Symbol(c) => Some(Conj::fact_not(c)),
+                _ => return Err(CnfError::NotNormal(self.clone())),
             },
-            Stmt::Or(l, r) => match (l.disj(), r.disj()) {
-                // Combine with tautology
-                (Some(l), Some(r)) => l.combine(&r),
-                _ => None, // Either side is tautology: True | P is still True
+            Stmt::And(v) => {
+                let mut out = Some(Conj::tautology());
+                for s in v.iter() {
+                    // Combine with contradiction: False & P is still False
+                    out = match (out, s.conj()?) {
+                        (Some(out), Some(c)) => out.combine(&c),
+                        _ => None,
+                    };
+                }
+                out
             },
-            _ => panic!("Not in CNF"),
+            _ => return Err(CnfError::NotNormal(self.clone())),
+        });
+    }
+
+    /// Converts this expression to disjunctive normal form and returns it as a [Dnf] object.
+    /// Every term of the result is a way in which the statement can be true. Terms that
+    /// include another term are left out, as they are covered by that term. Fails when a step
+    /// of the conversion goes wrong.
+    pub fn try_dnf(&self) -> Result<Dnf, CnfError> {
+        let mut dnf = Dnf::new();
+
+        let terms = match self.try_nnf()?.dist_conj() {
+            Stmt::Or(v) => v,
+            o => vec![o]
+        };
+        for term in terms.iter() {
+            if let Some(conj) = term.conj()? {
+                dnf.insert(conj);
+            }
+        }
+
+        let covered: Vec<Conj> = dnf.terms.iter()
+            .filter(|c| dnf.terms.iter().any(|d| d != *c && d.pos().is_subset(c.pos()) && d.neg().is_subset(c.neg())))
+            .cloned()
+            .collect();
+        for c in covered.iter() {
+            dnf.terms.remove(c);
         }
+
+        return Ok(dnf);
+    }
+
+    /// Converts this expression to conjunctive normal form, like [Self::try_cnf], but panics
+    /// when a step of the conversion goes wrong. The conversion does not fail on any
+    /// statement, so this only panics on a bug in the conversion itself.
+    pub(crate) fn cnf(&self) -> Cnf {
+        return self.try_cnf().unwrap_or_else(|e| panic!("{e}"));
     }
 
     /// Converts this expression to conjunctive normal form and returns it as a [Cnf] object.
-    pub fn cnf(&self) -> Cnf {
+    /// Fails when a step of the conversion goes wrong.
+    pub fn try_cnf(&self) -> Result<Cnf, CnfError> {
         let mut cnf = Cnf::new();
 
-        match self.clone().base_cnf() {
-            Stmt::And(l, r) => {
-                cnf.insert_all(&l.cnf());
-                cnf.insert_all(&r.cnf());
-            },
-            o => {
-                if let Some(disj) = o.disj() {
-                    cnf.insert(disj);
-                }
+        let clauses = match self.try_nnf()?.dist_disj() {
+            Stmt::And(v) => v,
+            o => vec![o]
+        };
+        for clause in clauses.iter() {
+            if let Some(disj) = clause.disj()? {
+                cnf.insert(disj);
             }
+        }
+
+        return Ok(cnf);
+    }
+
+    /// Tests whether this statement is equivalent to the other: whether both are true in
+    /// exactly the same models. This is the case when their exclusive disjunction, which holds
+    /// where they differ, is unsatisfiable.
+    ///
+    /// For example, `!(A & B)` is equivalent to `!A | !B`.
+    pub fn equivalent(&self, other: &Stmt) -> bool {
+        return !self.clone().xor(other.clone()).is_satisfiable();
+    }
+
+    /// Tests whether this statement is satisfiable: whether it is true in some model. The
+    /// clauses of the statement are handed to the [Solver].
+    pub fn is_satisfiable(&self) -> bool {
+        let mut solver = Solver::new();
+        solver.add_cnf(&self.cnf());
+        return solver.solve().is_some();
+    }
+
+    /// Tests whether this statement is a tautology: whether it is true in every model. This is
+    /// the case when its negation is unsatisfiable.
+    ///
+    /// For example, `(A -> B) | (B -> A)` is a tautology.
+    pub fn is_tautology(&self) -> bool {
+        return !self.clone().not().is_satisfiable();
+    }
+
+    /// Renders this statement as a tree, one node per line, with the operands of every
+    /// operator indented below it. Unlike the displayed form, this shows how the statement is
+    /// structured without relying on parentheses, e.g. that `A -> B -> C` is `A -> (B -> C)`
+    /// and that `A & B & C` is a single conjunction of three operands.
+    pub fn tree(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        self.tree_into(0, &mut out);
+        return out;
+    }
+
+    fn tree_into(&self, depth: usize, out: &mut Vec<String>) {
+        let indent = "  ".repeat(depth);
+        let (node, operands): (String, Vec<&Stmt>) = match self {
+            Stmt::Cont => (String::from("~ (false)"), vec![]),
+            Stmt::Taut => (String::from("* (true)"), vec![]),
+            Stmt::Symbol(sym) => (sym.clone(), vec![]),
+            Stmt::Not(o) => (String::from("! (not)"), vec![o]),
+            Stmt::And(v) => (String::from("& (and)"), v.iter().collect()),
+            Stmt::Or(v) => (String::from("| (or)"), v.iter().collect()),
+            Stmt::Implies(l, r) => (String::from("-> (implies)"), vec![l, r]),
+            Stmt::Equiv(l, r) => (String::from("<-> (equivalent)"), vec![l, r]),
+            Stmt::Xor(l, r) => (String::from("^ (xor)"), vec![l, r]),
+            Stmt::Ite(c, t, e) => (String::from("? : (if-then-else)"), vec![c, t, e]),
+            Stmt::Forall(sym, o) => (format!("forall {sym}"), vec![o]),
+            Stmt::Exists(sym, o) => (format!("exists {sym}"), vec![o]),
+            Stmt::Necessarily(o) => (String::from("[] (necessarily)"), vec![o]),
+            Stmt::Possibly(o) => (String::from("<> (possibly)"), vec![o]),
         };
 
-        return cnf;
+        out.push(format!("{indent}{node}"));
+        for o in operands {
+            o.tree_into(depth + 1, out);
+        }
+    }
+
+    /// Writes this statement for LaTeX math mode, with `\land`, `\lor`, `\lnot`,
+    /// `\rightarrow`, `\leftrightarrow` and `\oplus` for the operators, `\top` and `\bot`
+    /// for the constants, and `\Box` and `\Diamond` for the modal operators. Unlike the
+    /// displayed form, only operands are parenthesized, so `(A & B) -> C` is written as
+    /// `(A \land B) \rightarrow C`.
+    pub fn to_latex(&self) -> String {
+        return self.latex_into(false);
+    }
+
+    /// Writes this statement for LaTeX math mode, in parentheses if it is an operand of an
+    /// operator and has operators of its own, see [Self::to_latex].
+    fn latex_into(&self, operand: bool) -> String {
+        let nary = |v: &[Stmt], op: &str| v.iter().map(|s| s.latex_into(true)).collect::<Vec<_>>().join(op);
+        let latex = match self {
+            Stmt::Cont => return String::from("\\bot"),
+            Stmt::Taut => return String::from("\\top"),
+            Stmt::Or(v) if v.is_empty() => return String::from("\\bot"),
+            Stmt::And(v) if v.is_empty() => return String::from("\\top"),
+            Stmt::Symbol(sym) => return Symbol::latex(sym),
+            Stmt::Not(o) => return format!("\\lnot {}", o.latex_into(true)),
+            Stmt::Necessarily(o) => return format!("\\Box {}", o.latex_into(true)),
+            Stmt::Possibly(o) => return format!("\\Diamond {}", o.latex_into(true)),
+            Stmt::And(v) => nary(v, " \\land "),
+            Stmt::Or(v) => nary(v, " \\lor "),
+            Stmt::Implies(l, r) => format!("{} \\rightarrow {}", l.latex_into(true), r.latex_into(true)),
+            Stmt::Equiv(l, r) => format!("{} \\leftrightarrow {}", l.latex_into(true), r.latex_into(true)),
+            Stmt::Xor(l, r) => format!("{} \\oplus {}", l.latex_into(true), r.latex_into(true)),
+            Stmt::Ite(c, t, e) => format!("{} \\mathrel{{?}} {} : {}", c.latex_into(true), t.latex_into(true), e.latex_into(true)),
+            Stmt::Forall(sym, o) => format!("\\forall {}.\\, {}", Symbol::latex(sym), o.latex_into(true)),
+            Stmt::Exists(sym, o) => format!("\\exists {}.\\, {}", Symbol::latex(sym), o.latex_into(true)),
+        };
+
+        return if operand { format!("({latex})") } else { latex };
+    }
+}
+
+impl Stmt {
+    /// Formats a conjunction or disjunction, given its operator and the constant it stands for
+    /// when it has no operands.
+    fn fmt_nary(f: &mut std::fmt::Formatter<'_>, v: &[Stmt], op: &str, empty: &str) -> std::fmt::Result {
+        if v.is_empty() {
+            return write!(f, "{empty}");
+        }
+
+        write!(f, "(")?;
+        for (i, s) in v.iter().enumerate() {
+            if i > 0 {
+                write!(f, " {op} ")?;
+            }
+            write!(f, "{s}")?;
+        }
+        return write!(f, ")");
     }
 }
 
@@ -235,10 +885,25 @@ impl Display for Stmt {
             Stmt::Taut => write!(f, "*"),
             Stmt::Symbol(sym) => write!(f, "{sym}"),
             Stmt::Not(o) => write!(f, "!{o}"),
-            Stmt::And(l, r) => write!(f, "({l} & {r})"),
-            Stmt::Or(l, r) => write!(f, "({l} | {r})"),
+            Stmt::And(v) => Self::fmt_nary(f, v, "&", "*"),
+            Stmt::Or(v) => Self::fmt_nary(f, v, "|", "~"),
             Stmt::Implies(l, r) => write!(f, "({l} -> {r})"),
             Stmt::Equiv(l, r) => write!(f, "({l} <-> {r})"),
+            Stmt::Xor(l, r) => write!(f, "({l} ^ {r})"),
+            Stmt::Ite(c, t, e) => write!(f, "({c} ? {t} : {e})"),
+            Stmt::Forall(sym, o) => write!(f, "(forall {sym}. {o})"),
+            Stmt::Exists(sym, o) => write!(f, "(exists {sym}. {o})"),
+            Stmt::Necessarily(o) => write!(f, "[]{o}"),
+            Stmt::Possibly(o) => write!(f, "<>{o}"),
         };
     }
+}
+
+// Parses an expression like `parse_stmt`, so that `"A & B -> C".parse::<Stmt>()` works.
+impl FromStr for Stmt {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Stmt, ParseError> {
+        return parse_stmt(s);
+    }
 }
\ No newline at end of file
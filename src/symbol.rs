@@ -0,0 +1,202 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::{OnceLock, RwLock};
+
+/// The names of symbols, each with a small number: its ID. Every name is stored once, and
+/// lives as long as the program, so that a [Symbol] can refer to its name without owning it.
+///
+/// There is one global table, see [Self::global], through which all [Symbol]s are interned.
+/// Names are never removed from it, so it holds every name ever used, which is fine for the
+/// symbols of a knowledge base, but not for an unbounded stream of generated names.
+///
+/// Names are only added by [Symbol::intern], so that no other table exists: symbols are
+/// compared by their ID, which would mix up symbols of different tables.
+pub struct SymbolTable {
+    names: Vec<&'static str>,
+    ids: HashMap<&'static str, u32>
+}
+
+impl SymbolTable {
+    fn new() -> SymbolTable {
+        return SymbolTable { names: Vec::new(), ids: HashMap::new() };
+    }
+
+    /// Returns the global table.
+    pub fn global() -> &'static RwLock<SymbolTable> {
+        static GLOBAL: OnceLock<RwLock<SymbolTable>> = OnceLock::new();
+        return GLOBAL.get_or_init(|| RwLock::new(SymbolTable::new()));
+    }
+
+    /// Returns the symbol with the given name, adding the name if it is new.
+    fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(id) = self.ids.get(name) {
+            return Symbol { id: *id, name: self.names[*id as usize] };
+        }
+
+        let id = u32::try_from(self.names.len()).expect("Too many symbols");
+        let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+        self.names.push(name);
+        self.ids.insert(name, id);
+        return Symbol { id, name };
+    }
+
+    /// Returns the symbol with the given name, or `None` if no symbol has this name.
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        return self.ids.get(name).map(|id| Symbol { id: *id, name: self.names[*id as usize] });
+    }
+
+    /// Returns the symbol with the given ID, or `None` if there is no such symbol.
+    pub fn by_id(&self, id: u32) -> Option<Symbol> {
+        return self.names.get(id as usize).map(|name| Symbol { id, name });
+    }
+
+    /// Returns the number of symbols.
+    pub fn len(&self) -> usize {
+        return self.names.len();
+    }
+
+    /// Tests whether the table has no symbols.
+    pub fn is_empty(&self) -> bool {
+        return self.names.is_empty();
+    }
+}
+
+/// The name of a symbol, interned in the global [SymbolTable]. Symbols are compared and
+/// hashed by their ID, which is much cheaper than comparing names, and copied without
+/// allocating. They are still ordered by name, so that clauses are shown in the same order as
+/// when symbols were plain strings.
+///
+/// A symbol dereferences to its name, so it can be used wherever a `&str` is expected.
+#[derive(Clone, Copy)]
+pub struct Symbol {
+    id: u32,
+    name: &'static str
+}
+
+impl Symbol {
+    /// Returns the symbol with the given name, interning it in the global [SymbolTable] if it
+    /// is new.
+    pub fn intern(name: &str) -> Symbol {
+        if let Some(symbol) = SymbolTable::global().read().unwrap().get(name) {
+            return symbol;
+        }
+        return SymbolTable::global().write().unwrap().intern(name);
+    }
+
+    /// Returns the ID of this symbol in the global [SymbolTable].
+    pub fn id(&self) -> u32 {
+        return self.id;
+    }
+
+    /// Returns the name of this symbol.
+    pub fn name(&self) -> &'static str {
+        return self.name;
+    }
+
+    /// Writes the name of a symbol for LaTeX math mode: a single letter as it is, a letter
+    /// followed by digits with the digits as a subscript, like `x_{12}`, and other names in
+    /// `\mathit`, so that they read as one word, with the characters that LaTeX reserves
+    /// escaped.
+    pub fn latex(name: &str) -> String {
+        let escaped: String = name.chars().map(|c| match c {
+            '_' | '#' | '$' | '%' | '&' | '{' | '}' => format!("\\{c}"),
+            '\\' => String::from("\\backslash{}"),
+            '~' => String::from("{\\sim}"),
+            '^' => String::from("{\\wedge}"),
+            c => c.to_string(),
+        }).collect();
+
+        let mut chars = name.chars();
+        return match (chars.next(), chars.as_str()) {
+            (Some(_), "") => escaped,
+            (Some(c), digits) if c.is_ascii_alphabetic() && digits.chars().all(|d| d.is_ascii_digit()) => format!("{c}_{{{digits}}}"),
+            _ => format!("\\mathit{{{escaped}}}"),
+        };
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        return self.id == other.id;
+    }
+}
+
+impl Eq for Symbol {}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        return self.name == other;
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        return self.name == *other;
+    }
+}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+// Equal names have equal IDs, so only symbols with different IDs need their names compared
+impl Ord for Symbol {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.id == other.id {
+            return Ordering::Equal;
+        }
+        return self.name.cmp(other.name);
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        return self.name;
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Symbol {
+        return Symbol::intern(name);
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}", self.name);
+    }
+}
+
+impl Debug for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{:?}", self.name);
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Symbol {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return serializer.serialize_str(self.name);
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Symbol {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Symbol, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        return Ok(Symbol::intern(&name));
+    }
+}
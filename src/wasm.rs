@@ -0,0 +1,77 @@
+use wasm_bindgen::prelude::*;
+
+use crate::kb::KnowledgeBase;
+use crate::parser::parse_stmt;
+use crate::stmt::Stmt;
+
+/// Parses an expression, naming the position of the error in the message if it is not valid.
+fn parse_expr(input: &str) -> Result<Stmt, String> {
+    return parse_stmt(input).map_err(|e| e.to_string());
+}
+
+/// Parses an expression and returns it as rustyproof shows it, with explicit parentheses
+/// where precedence matters. Throws an error message if the expression is not valid.
+#[wasm_bindgen]
+pub fn parse(input: &str) -> Result<String, String> {
+    return Ok(parse_expr(input)?.to_string());
+}
+
+/// A knowledge base for JavaScript: axioms are asserted into it, and questions are asked to
+/// it, as in the REPL. Every method takes an expression in the input syntax of the REPL, and
+/// throws an error message if it is not valid.
+#[wasm_bindgen]
+pub struct Prover {
+    kb: KnowledgeBase
+}
+
+#[wasm_bindgen]
+impl Prover {
+    /// Creates a prover without axioms.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Prover {
+        return Prover { kb: KnowledgeBase::new() };
+    }
+
+    /// Asserts an expression as an axiom, and returns its clauses.
+    pub fn assert(&mut self, input: &str) -> Result<String, String> {
+        let axiom = self.kb.assert(parse_expr(input)?)?;
+        return Ok(axiom.clauses.to_string());
+    }
+
+    /// Answers whether an expression follows from the axioms.
+    pub fn query(&self, input: &str) -> Result<bool, String> {
+        return Ok(self.kb.entails(&parse_expr(input)?));
+    }
+
+    /// Explains whether an expression follows from the axioms, like `:why`: if it does, the
+    /// steps of a resolution proof that the axioms contradict its negation, one per line, and
+    /// otherwise a model of the axioms in which it is false.
+    pub fn explain(&self, input: &str) -> Result<String, String> {
+        let stmt = parse_expr(input)?;
+
+        if let Some(model) = self.kb.counter_model(&stmt) {
+            return Ok(format!("Not satisfied! {stmt} does not follow from the axioms\nCounterexample: {model}"));
+        }
+
+        let Some(proof) = self.kb.proof(&stmt) else {
+            return Ok(String::from("Satisfied! But no proof was found within the limits on resolution"));
+        };
+        return Ok(format!("Satisfied! Suppose {stmt} is false; then the axioms lead to a contradiction:\n{proof}\nSo {stmt} must be true"));
+    }
+
+    /// Returns the axioms asserted so far, in order, as rustyproof shows them.
+    pub fn axioms(&self) -> Vec<String> {
+        return self.kb.axioms().iter().map(|a| a.to_string()).collect();
+    }
+
+    /// Removes all axioms.
+    pub fn clear(&mut self) {
+        self.kb.clear();
+    }
+}
+
+impl Default for Prover {
+    fn default() -> Self {
+        return Self::new();
+    }
+}